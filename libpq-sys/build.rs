@@ -82,6 +82,11 @@ impl Display for LinkingOptions {
 fn main() {
     bindgen();
 
+    if cfg!(feature = "vendored") {
+        // pq-src builds and links libpq.
+        return;
+    }
+
     println!("cargo:rerun-if-env-changed=PQ_LIB_DIR");
     println!("cargo:rerun-if-env-changed=PQ_LIB_STATIC");
     println!("cargo:rerun-if-env-changed=TARGET");
@@ -102,10 +107,7 @@ fn bindgen() {
 
     let bindings = bindgen::Builder::default()
         .rustified_enum(".*")
-        .clang_arg(format!(
-            "-I{}",
-            pg_config_output("--includedir").unwrap_or_else(|| "/usr/include".to_string())
-        ))
+        .clang_arg(format!("-I{}", include_dir()))
         .header("wrapper.h")
         .generate()
         .expect("Unable to generate bindings");
@@ -116,6 +118,15 @@ fn bindgen() {
         .expect("Couldn't write bindings!");
 }
 
+fn include_dir() -> String {
+    if cfg!(feature = "vendored") {
+        // Exported by the build script of pq-src, see its `links` key.
+        return env::var("DEP_PQ_SRC_INCLUDE").expect("pq-src doesn't provide the libpq headers");
+    }
+
+    pg_config_output("--includedir").unwrap_or_else(|| "/usr/include".to_string())
+}
+
 #[cfg(not(target_env = "msvc"))]
 fn configured() -> bool {
     pkg_config::probe_library("libpq").is_ok()
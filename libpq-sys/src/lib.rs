@@ -4,6 +4,9 @@
 #![allow(clippy::redundant_static_lifetimes)]
 #![allow(clippy::upper_case_acronyms)]
 
+#[cfg(feature = "vendored")]
+extern crate pq_src;
+
 include!(concat!(env!("OUT_DIR"), "/bindings.rs"));
 
 #[cfg(test)]
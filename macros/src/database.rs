@@ -0,0 +1,223 @@
+pub(crate) const BOOL: u32 = 16;
+pub(crate) const INT8: u32 = 20;
+pub(crate) const INT2: u32 = 21;
+pub(crate) const INT4: u32 = 23;
+pub(crate) const OID: u32 = 26;
+pub(crate) const FLOAT4: u32 = 700;
+pub(crate) const FLOAT8: u32 = 701;
+
+pub(crate) struct Column {
+    pub name: String,
+    pub oid: u32,
+    pub nullable: bool,
+}
+
+pub(crate) struct Description {
+    pub params: Vec<u32>,
+    pub columns: Vec<Column>,
+}
+
+/**
+ * Prepares `sql` on the database and describes its parameters and columns.
+ */
+pub(crate) fn describe(dsn: &str, sql: &str) -> Result<Description, String> {
+    let conn = Connection::new(dsn)?;
+
+    let c_sql = std::ffi::CString::new(sql).map_err(|err| err.to_string())?;
+    let c_name = std::ffi::CString::default();
+
+    conn.check(unsafe {
+        pq_sys::PQprepare(conn.0, c_name.as_ptr(), c_sql.as_ptr(), 0, std::ptr::null())
+    })?;
+
+    let describe = conn.check(unsafe { pq_sys::PQdescribePrepared(conn.0, c_name.as_ptr()) })?;
+
+    let params = (0..unsafe { pq_sys::PQnparams(describe.0) })
+        .map(|x| unsafe { pq_sys::PQparamtype(describe.0, x) })
+        .collect();
+
+    let outer_join = has_outer_join(sql);
+    let mut columns = Vec::new();
+
+    for x in 0..unsafe { pq_sys::PQnfields(describe.0) } {
+        let name = unsafe { std::ffi::CStr::from_ptr(pq_sys::PQfname(describe.0, x)) }
+            .to_string_lossy()
+            .to_string();
+        let oid = unsafe { pq_sys::PQftype(describe.0, x) };
+        let table = unsafe { pq_sys::PQftable(describe.0, x) };
+        let table_column = unsafe { pq_sys::PQftablecol(describe.0, x) };
+
+        // Only a column coming straight from a table can be known as not null, and not even
+        // then on the nullable side of an outer join.
+        let nullable = table == 0 || outer_join || !conn.not_null(table, table_column)?;
+
+        columns.push(Column {
+            name,
+            oid,
+            nullable,
+        });
+    }
+
+    Ok(Description { params, columns })
+}
+
+/**
+ * Returns `true` if `sql` contains a `LEFT`, `RIGHT` or `FULL` join, which can make any column
+ * null. Keywords in literals or comments count too, which only makes the columns nullable.
+ */
+fn has_outer_join(sql: &str) -> bool {
+    let words = sql
+        .split(|c: char| !c.is_alphanumeric() && c != '_')
+        .filter(|x| !x.is_empty())
+        .map(str::to_uppercase)
+        .collect::<Vec<_>>();
+
+    words.iter().enumerate().any(|(x, word)| {
+        matches!(word.as_str(), "LEFT" | "RIGHT" | "FULL")
+            && match words.get(x + 1).map(String::as_str) {
+                Some("JOIN") => true,
+                Some("OUTER") => words.get(x + 2).map(String::as_str) == Some("JOIN"),
+                _ => false,
+            }
+    })
+}
+
+struct Connection(*mut pq_sys::PGconn);
+
+impl Connection {
+    fn new(dsn: &str) -> Result<Self, String> {
+        let c_dsn = std::ffi::CString::new(dsn).map_err(|err| err.to_string())?;
+        let conn = Self(unsafe { pq_sys::PQconnectdb(c_dsn.as_ptr()) });
+
+        if matches!(
+            unsafe { pq_sys::PQstatus(conn.0) },
+            pq_sys::ConnStatusType::CONNECTION_OK
+        ) {
+            Ok(conn)
+        } else {
+            Err(conn.error_message())
+        }
+    }
+
+    fn check(&self, result: *mut pq_sys::PGresult) -> Result<PgResult, String> {
+        let result = PgResult(result);
+
+        if result.0.is_null() {
+            return Err(self.error_message());
+        }
+
+        match unsafe { pq_sys::PQresultStatus(result.0) } {
+            pq_sys::ExecStatusType::PGRES_COMMAND_OK | pq_sys::ExecStatusType::PGRES_TUPLES_OK => {
+                Ok(result)
+            }
+            _ => Err(
+                unsafe { std::ffi::CStr::from_ptr(pq_sys::PQresultErrorMessage(result.0)) }
+                    .to_string_lossy()
+                    .to_string(),
+            ),
+        }
+    }
+
+    fn not_null(&self, table: u32, column: i32) -> Result<bool, String> {
+        let c_sql = std::ffi::CString::new(
+            "SELECT attnotnull FROM pg_catalog.pg_attribute WHERE attrelid = $1 AND attnum = $2",
+        )
+        .unwrap();
+        let c_table = std::ffi::CString::new(table.to_string()).unwrap();
+        let c_column = std::ffi::CString::new(column.to_string()).unwrap();
+        let values = [c_table.as_ptr(), c_column.as_ptr()];
+
+        let result = self.check(unsafe {
+            pq_sys::PQexecParams(
+                self.0,
+                c_sql.as_ptr(),
+                2,
+                std::ptr::null(),
+                values.as_ptr(),
+                std::ptr::null(),
+                std::ptr::null(),
+                0,
+            )
+        })?;
+
+        let not_null = unsafe {
+            pq_sys::PQntuples(result.0) == 1
+                && std::ffi::CStr::from_ptr(pq_sys::PQgetvalue(result.0, 0, 0)).to_bytes() == b"t"
+        };
+
+        Ok(not_null)
+    }
+
+    fn error_message(&self) -> String {
+        unsafe { std::ffi::CStr::from_ptr(pq_sys::PQerrorMessage(self.0)) }
+            .to_string_lossy()
+            .to_string()
+    }
+}
+
+impl Drop for Connection {
+    fn drop(&mut self) {
+        unsafe { pq_sys::PQfinish(self.0) };
+    }
+}
+
+struct PgResult(*mut pq_sys::PGresult);
+
+impl Drop for PgResult {
+    fn drop(&mut self) {
+        unsafe { pq_sys::PQclear(self.0) };
+    }
+}
+
+#[cfg(test)]
+mod test {
+    fn dsn() -> String {
+        std::env::var("PQ_DSN").unwrap_or_else(|_| "host=localhost".to_string())
+    }
+
+    #[test]
+    fn has_outer_join() {
+        assert!(super::has_outer_join("SELECT * FROM a LEFT JOIN b ON true"));
+        assert!(super::has_outer_join(
+            "select * from a\nright outer join b using (id)"
+        ));
+        assert!(super::has_outer_join("SELECT * FROM a FULL JOIN b ON true"));
+        assert!(!super::has_outer_join("SELECT * FROM a JOIN b ON true"));
+        assert!(!super::has_outer_join("SELECT left(name, 1) FROM a"));
+        assert!(!super::has_outer_join("SELECT 1 AS left_join"));
+    }
+
+    #[test]
+    fn describe() {
+        let description = super::describe(
+            &dsn(),
+            "SELECT oid, relacl, relname || '' AS name FROM pg_catalog.pg_class WHERE relname = $1",
+        )
+        .unwrap();
+
+        assert_eq!(description.params, [19]);
+        assert_eq!(description.columns.len(), 3);
+        assert_eq!(description.columns[0].name, "oid");
+        assert_eq!(description.columns[0].oid, super::OID);
+        assert!(!description.columns[0].nullable);
+        assert!(description.columns[1].nullable);
+        // Expressions are always nullable.
+        assert!(description.columns[2].nullable);
+    }
+
+    #[test]
+    fn describe_outer_join() {
+        let description = super::describe(
+            &dsn(),
+            "SELECT c.oid FROM pg_catalog.pg_namespace n LEFT JOIN pg_catalog.pg_class c ON false",
+        )
+        .unwrap();
+
+        assert!(description.columns[0].nullable);
+    }
+
+    #[test]
+    fn describe_error() {
+        assert!(super::describe(&dsn(), "SELECT * FROM unknown_table").is_err());
+    }
+}
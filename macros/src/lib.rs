@@ -0,0 +1,251 @@
+mod database;
+
+use quote::quote;
+
+/**
+ * Prepares a query against the database of the `DATABASE_URL` environment variable at compile
+ * time, checks its number of parameters and generates a typed row struct.
+ *
+ * Columns of a table with a `NOT NULL` constraint are typed `T`, the others `Option<T>`.
+ *
+ * The nullability is only inferred from the constraint of the column a value comes from:
+ * expressions, including casts, are always `Option<T>`, and so are all the columns of a query
+ * with a `LEFT`, `RIGHT` or `FULL` join. Other ways for a `NOT NULL` column to produce a null,
+ * like `GROUP BY ROLLUP`, aren't detected and fail at runtime.
+ *
+ * ```ignore
+ * let users = libpq::query!("SELECT id, name FROM users WHERE id > $1", 10)
+ *     .fetch_all(&conn)?;
+ *
+ * for user in users {
+ *     println!("{}: {:?}", user.id, user.name);
+ * }
+ * ```
+ *
+ * The returned value provides `execute`, `fetch_all`, `fetch_optional` and `fetch_one` methods.
+ */
+#[proc_macro]
+pub fn query(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = syn::parse_macro_input!(input as Input);
+
+    match expand(&input) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+struct Input {
+    sql: syn::LitStr,
+    args: Vec<syn::Expr>,
+}
+
+impl syn::parse::Parse for Input {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let sql = input.parse()?;
+        let mut args = Vec::new();
+
+        while !input.is_empty() {
+            input.parse::<syn::Token![,]>()?;
+
+            if input.is_empty() {
+                break;
+            }
+
+            args.push(input.parse()?);
+        }
+
+        Ok(Self { sql, args })
+    }
+}
+
+fn expand(input: &Input) -> syn::Result<proc_macro2::TokenStream> {
+    let sql = input.sql.value();
+    let span = input.sql.span();
+
+    let dsn = std::env::var("DATABASE_URL")
+        .map_err(|_| syn::Error::new(span, "DATABASE_URL must be set to use query!"))?;
+
+    let description =
+        database::describe(&dsn, &sql).map_err(|err| syn::Error::new(span, err.trim_end()))?;
+
+    if description.params.len() != input.args.len() {
+        return Err(syn::Error::new(
+            span,
+            format!(
+                "expected {} parameters, got {}",
+                description.params.len(),
+                input.args.len()
+            ),
+        ));
+    }
+
+    let param_types = &description.params;
+    let args = &input.args;
+
+    let mut fields = Vec::new();
+    let mut decoders = Vec::new();
+
+    for (x, column) in description.columns.iter().enumerate() {
+        let name = syn::parse_str::<syn::Ident>(&column.name)
+            .or_else(|_| syn::parse_str::<syn::Ident>(&format!("r#{}", column.name)))
+            .map_err(|_| {
+                syn::Error::new(
+                    span,
+                    format!(
+                        "column name `{}` is not a valid identifier, use an alias",
+                        column.name
+                    ),
+                )
+            })?;
+        let ty = rust_type(column.oid);
+        let decode = decode(column.oid, &ty);
+        let column_name = &column.name;
+
+        if column.nullable {
+            fields.push(quote!(pub #name: ::std::option::Option<#ty>));
+            decoders.push(quote! {
+                #name: match result.value(row, #x) {
+                    ::std::option::Option::Some(value) => ::std::option::Option::Some(#decode),
+                    ::std::option::Option::None => ::std::option::Option::None,
+                }
+            });
+        } else {
+            fields.push(quote!(pub #name: #ty));
+            decoders.push(quote! {
+                #name: match result.value(row, #x) {
+                    ::std::option::Option::Some(value) => #decode,
+                    ::std::option::Option::None => {
+                        return ::std::result::Result::Err(::libpq::errors::Error::Backend(
+                            ::std::format!("unexpected null in column \"{}\"", #column_name),
+                        ))
+                    }
+                }
+            });
+        }
+    }
+
+    let tokens = quote! {{
+        #[allow(dead_code)]
+        #[derive(Debug)]
+        struct Row {
+            #(#fields,)*
+        }
+
+        struct Query {
+            params: ::std::vec::Vec<::std::option::Option<::std::vec::Vec<u8>>>,
+        }
+
+        #[allow(dead_code)]
+        impl Query {
+            fn execute(self, conn: &::libpq::Connection) -> ::libpq::errors::Result<::libpq::Result> {
+                let result = conn.exec_params(
+                    #sql,
+                    &[#(#param_types),*],
+                    &self.params,
+                    &[],
+                    ::libpq::Format::Text,
+                );
+
+                match result.status() {
+                    ::libpq::Status::TupplesOk | ::libpq::Status::CommandOk => {
+                        ::std::result::Result::Ok(result)
+                    }
+                    _ => ::std::result::Result::Err(::libpq::errors::Error::Backend(
+                        result.error_message()?.unwrap_or_default(),
+                    )),
+                }
+            }
+
+            fn fetch_all(
+                self,
+                conn: &::libpq::Connection,
+            ) -> ::libpq::errors::Result<::std::vec::Vec<Row>> {
+                let result = self.execute(conn)?;
+                let mut rows = ::std::vec::Vec::with_capacity(result.ntuples());
+
+                for row in 0..result.ntuples() {
+                    rows.push(Row {
+                        #(#decoders,)*
+                    });
+                }
+
+                ::std::result::Result::Ok(rows)
+            }
+
+            fn fetch_optional(
+                self,
+                conn: &::libpq::Connection,
+            ) -> ::libpq::errors::Result<::std::option::Option<Row>> {
+                ::std::result::Result::Ok(self.fetch_all(conn)?.into_iter().next())
+            }
+
+            fn fetch_one(self, conn: &::libpq::Connection) -> ::libpq::errors::Result<Row> {
+                self.fetch_optional(conn)?.ok_or_else(|| {
+                    ::libpq::errors::Error::Backend("query returned no rows".to_string())
+                })
+            }
+        }
+
+        Query {
+            params: ::libpq::params![#(#args),*],
+        }
+    }};
+
+    Ok(tokens)
+}
+
+fn rust_type(oid: u32) -> proc_macro2::TokenStream {
+    match oid {
+        database::BOOL => quote!(bool),
+        database::INT2 => quote!(i16),
+        database::INT4 => quote!(i32),
+        database::INT8 => quote!(i64),
+        database::OID => quote!(u32),
+        database::FLOAT4 => quote!(f32),
+        database::FLOAT8 => quote!(f64),
+        // Other types are returned in their text representation.
+        _ => quote!(::std::string::String),
+    }
+}
+
+fn decode(oid: u32, ty: &proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+    match oid {
+        database::BOOL => quote!(value == b"t"),
+        database::INT2
+        | database::INT4
+        | database::INT8
+        | database::OID
+        | database::FLOAT4
+        | database::FLOAT8 => quote! {
+            ::std::str::from_utf8(value)?
+                .parse::<#ty>()
+                .map_err(|err| ::libpq::errors::Error::Backend(err.to_string()))?
+        },
+        _ => quote!(::std::str::from_utf8(value)?.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #[test]
+    fn parse_input() {
+        let input = syn::parse_str::<super::Input>(r#""SELECT $1, $2", 1, name,"#).unwrap();
+        assert_eq!(input.sql.value(), "SELECT $1, $2");
+        assert_eq!(input.args.len(), 2);
+
+        let input = syn::parse_str::<super::Input>(r#""SELECT 1""#).unwrap();
+        assert!(input.args.is_empty());
+
+        assert!(syn::parse_str::<super::Input>("1, 2").is_err());
+    }
+
+    #[test]
+    fn rust_type() {
+        assert_eq!(super::rust_type(crate::database::INT4).to_string(), "i32");
+        assert_eq!(super::rust_type(crate::database::BOOL).to_string(), "bool");
+        assert_eq!(
+            super::rust_type(25).to_string(),
+            quote::quote!(::std::string::String).to_string()
+        );
+    }
+}
@@ -0,0 +1,250 @@
+/*!
+ * Opt-in cache of decoded query results, for read-heavy workloads running the same statements
+ * over and over.
+ *
+ * ```no_run
+ * struct Stat {
+ *     name: String,
+ *     total: i64,
+ * }
+ *
+ * impl libpq::result::FromRow for Stat {
+ *     fn from_row(row: &libpq::result::Row) -> libpq::errors::Result<Self> {
+ *         Ok(Self {
+ *             name: row.text(0)?.unwrap_or_default().to_string(),
+ *             total: row.parse(1)?.unwrap_or_default(),
+ *         })
+ *     }
+ * }
+ *
+ * # let conn = libpq::Connection::new("")?;
+ * let cache = libpq::cache::QueryCache::<Stat>::new(std::time::Duration::from_secs(30), 100);
+ * let sql = "SELECT name, count(*) FROM events WHERE kind = $1 GROUP BY name";
+ *
+ * let stats = cache.fetch(&conn, sql, &libpq::params!["click"])?;
+ * // Served from the cache for the next 30 seconds.
+ * let stats = cache.fetch(&conn, sql, &libpq::params!["click"])?;
+ *
+ * // After a write the statement depends on.
+ * cache.invalidate(sql);
+ * # Ok::<(), libpq::errors::Error>(())
+ * ```
+ */
+
+type Key = (Option<String>, String, Vec<Option<Vec<u8>>>);
+
+struct Entry<T> {
+    rows: std::sync::Arc<Vec<T>>,
+    inserted: std::time::Instant,
+}
+
+/**
+ * Cache of the rows, decoded as `T`, returned by a query for given parameters.
+ *
+ * The results are kept per [`Queryable::identity`](crate::connection::Queryable::identity), so a
+ * cache can be shared by connections to different databases or as different users.
+ *
+ * Entries expire after a time to live and the oldest one is evicted when the cache is full.
+ * Failed queries are not cached.
+ */
+pub struct QueryCache<T> {
+    ttl: std::time::Duration,
+    capacity: usize,
+    entries: std::sync::Mutex<std::collections::HashMap<Key, Entry<T>>>,
+}
+
+impl<T: crate::result::FromRow> QueryCache<T> {
+    /**
+     * Returns the rows of `sql` executed with `params`, in text format, from the cache or from
+     * `conn` if they are missing or expired.
+     */
+    pub fn fetch<Q>(
+        &self,
+        conn: &Q,
+        sql: &str,
+        params: &[Option<Vec<u8>>],
+    ) -> crate::errors::Result<std::sync::Arc<Vec<T>>>
+    where
+        Q: crate::connection::Queryable + ?Sized,
+    {
+        let key = (conn.identity(), sql.to_string(), params.to_vec());
+
+        if let Some(entry) = self.entries().get(&key) {
+            if entry.inserted.elapsed() < self.ttl {
+                return Ok(entry.rows.clone());
+            }
+        }
+
+        // The lock isn't held while querying, concurrent misses run the query each.
+        let result = conn.exec_params(sql, &[], params, &[], crate::Format::Text);
+
+        if result.status() != crate::Status::TupplesOk {
+            return Err(crate::errors::Error::Backend(
+                result.error_message()?.unwrap_or_default(),
+            ));
+        }
+
+        let rows = std::sync::Arc::new(result.to_vec()?);
+        self.insert(key, rows.clone());
+
+        Ok(rows)
+    }
+
+    fn insert(&self, key: Key, rows: std::sync::Arc<Vec<T>>) {
+        if self.capacity == 0 || self.ttl.is_zero() {
+            return;
+        }
+
+        let mut entries = self.entries();
+
+        if entries.len() >= self.capacity && !entries.contains_key(&key) {
+            entries.retain(|_, x| x.inserted.elapsed() < self.ttl);
+        }
+
+        if entries.len() >= self.capacity && !entries.contains_key(&key) {
+            let oldest = entries
+                .iter()
+                .min_by_key(|(_, x)| x.inserted)
+                .map(|(k, _)| k.clone());
+
+            if let Some(oldest) = oldest {
+                entries.remove(&oldest);
+            }
+        }
+
+        entries.insert(
+            key,
+            Entry {
+                rows,
+                inserted: std::time::Instant::now(),
+            },
+        );
+    }
+}
+
+impl<T> QueryCache<T> {
+    /**
+     * Creates a cache keeping up to `capacity` results, each for `ttl`.
+     */
+    pub fn new(ttl: std::time::Duration, capacity: usize) -> Self {
+        Self {
+            ttl,
+            capacity,
+            entries: Default::default(),
+        }
+    }
+
+    /**
+     * Removes the results of `sql`, for all the parameters and connections.
+     */
+    pub fn invalidate(&self, sql: &str) {
+        self.invalidate_if(|x, _| x == sql);
+    }
+
+    /**
+     * Removes the results for which `predicate`, called with the query and its parameters,
+     * returns `true`, for all the connections.
+     */
+    pub fn invalidate_if<F>(&self, mut predicate: F)
+    where
+        F: FnMut(&str, &[Option<Vec<u8>>]) -> bool,
+    {
+        self.entries()
+            .retain(|(_, sql, params), _| !predicate(sql, params));
+    }
+
+    /**
+     * Removes all the results.
+     */
+    pub fn clear(&self) {
+        self.entries().clear();
+    }
+
+    /**
+     * Returns the number of cached results, including the expired ones not evicted yet.
+     */
+    pub fn len(&self) -> usize {
+        self.entries().len()
+    }
+
+    /**
+     * Returns `true` if no result is cached.
+     */
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn entries(&self) -> std::sync::MutexGuard<'_, std::collections::HashMap<Key, Entry<T>>> {
+        self.entries.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    struct Value(String);
+
+    impl crate::result::FromRow for Value {
+        fn from_row(row: &crate::result::Row) -> crate::errors::Result<Self> {
+            Ok(Self(row.text(0)?.unwrap_or_default().to_string()))
+        }
+    }
+
+    #[test]
+    fn fetch() {
+        let conn = crate::test::new_conn();
+        let cache = super::QueryCache::<Value>::new(std::time::Duration::from_secs(60), 2);
+        let sql = "SELECT random()::text || $1";
+
+        let a = cache.fetch(&conn, sql, &crate::params!["a"]).unwrap();
+        let b = cache.fetch(&conn, sql, &crate::params!["a"]).unwrap();
+        assert!(std::sync::Arc::ptr_eq(&a, &b));
+
+        let c = cache.fetch(&conn, sql, &crate::params!["c"]).unwrap();
+        assert_ne!(a[0].0, c[0].0);
+        assert_eq!(cache.len(), 2);
+
+        cache.fetch(&conn, sql, &crate::params!["d"]).unwrap();
+        assert_eq!(cache.len(), 2);
+
+        cache.invalidate(sql);
+        assert!(cache.is_empty());
+
+        assert!(cache.fetch(&conn, "SELECT invalid", &[]).is_err());
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn identity() {
+        let cache = super::QueryCache::<Value>::new(std::time::Duration::from_secs(60), 10);
+        let sql = "SELECT current_user::text";
+
+        let conn = crate::test::new_conn();
+        let mock = crate::connection::MockConnection::new();
+        mock.set_result(
+            sql,
+            crate::result::Builder::new(crate::Status::TupplesOk)
+                .column("current_user", &crate::types::TEXT)
+                .row(&[Some("mock")]),
+        );
+
+        let a = cache.fetch(&conn, sql, &[]).unwrap();
+        let b = cache.fetch(&mock, sql, &[]).unwrap();
+        assert_ne!(a[0].0, "mock");
+        assert_eq!(b[0].0, "mock");
+        assert_eq!(cache.len(), 2);
+
+        let c = cache.fetch(&crate::test::new_conn(), sql, &[]).unwrap();
+        assert!(std::sync::Arc::ptr_eq(&a, &c));
+    }
+
+    #[test]
+    fn expired() {
+        let conn = crate::test::new_conn();
+        let cache = super::QueryCache::<Value>::new(std::time::Duration::ZERO, 10);
+
+        let a = cache.fetch(&conn, "SELECT random()::text", &[]).unwrap();
+        let b = cache.fetch(&conn, "SELECT random()::text", &[]).unwrap();
+        assert!(!std::sync::Arc::ptr_eq(&a, &b));
+        assert!(cache.is_empty());
+    }
+}
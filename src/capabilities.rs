@@ -0,0 +1,60 @@
+/**
+ * Features supported by the libpq the program is running with.
+ *
+ * It may be older than the libpq the program was built against: the bindings gated behind the
+ * `vXX` features would then fail to resolve or misbehave, check them before use.
+ */
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Capabilities {
+    /** [Pipeline mode](https://www.postgresql.org/docs/current/libpq-pipeline-mode.html), libpq 14. */
+    pub pipeline_mode: bool,
+    /** Tracing flags, see [`Connection::trace_set_flags`](crate::Connection::trace_set_flags), libpq 14. */
+    pub trace_flags: bool,
+    /** Results in chunks of rows, the `Status::TuplesChunk` status, libpq 17. */
+    pub chunked_rows: bool,
+    /** Encrypted and non-blocking cancel requests, the `PQcancelCreate` API, libpq 17. */
+    pub cancel_conn: bool,
+    /** Direct SSL negotiation, see `Builder::sslnegotiation`, libpq 17. */
+    pub direct_ssl: bool,
+    /** OAuth authentication, libpq 18. */
+    pub oauth: bool,
+    /** Negotiation of the protocol version 3.2, libpq 18. */
+    pub protocol_3_2: bool,
+}
+
+impl Capabilities {
+    /**
+     * Detects the capabilities from the version returned by [`crate::version`].
+     */
+    pub fn detect() -> Self {
+        Self::for_version(crate::version())
+    }
+
+    fn for_version(version: i32) -> Self {
+        Self {
+            pipeline_mode: version >= 140_000,
+            trace_flags: version >= 140_000,
+            chunked_rows: version >= 170_000,
+            cancel_conn: version >= 170_000,
+            direct_ssl: version >= 170_000,
+            oauth: version >= 180_000,
+            protocol_3_2: version >= 180_000,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #[test]
+    fn detect() {
+        assert_eq!(
+            super::Capabilities::for_version(130_000),
+            super::Capabilities::default()
+        );
+
+        let capabilities = super::Capabilities::for_version(170_002);
+        assert!(capabilities.pipeline_mode);
+        assert!(capabilities.chunked_rows);
+        assert!(!capabilities.oauth);
+    }
+}
@@ -0,0 +1,332 @@
+/*!
+ * Adapters for the [`postgres_types`] traits, to reuse their implementations, and derives, with
+ * this crate.
+ *
+ * Values are exchanged in binary format.
+ *
+ * [`Connection::query_sql`](crate::Connection::query_sql) supports the user-defined types, like
+ * enums, composites or domains, resolved with [`Connection::sql_type`](crate::Connection::sql_type).
+ * [`encode`] and [`decode`], without a connection, only know the built-in types.
+ */
+
+pub use postgres_types::{FromSql, ToSql};
+
+const ACCEPTED: &[crate::Status] = &[crate::Status::CommandOk, crate::Status::TupplesOk];
+
+fn type_of(oid: crate::Oid) -> crate::errors::Result<postgres_types::Type> {
+    postgres_types::Type::from_oid(oid).ok_or_else(|| {
+        crate::errors::Error::Backend(format!("type oid {oid} is not a built-in type"))
+    })
+}
+
+/**
+ * Encodes `value` in the binary format of the built-in type `oid`, for the parameters of
+ * [`Connection::exec_params`](crate::Connection::exec_params) with [`crate::Format::Binary`].
+ */
+pub fn encode(
+    value: &(dyn ToSql + Sync),
+    oid: crate::Oid,
+) -> crate::errors::Result<Option<Vec<u8>>> {
+    encode_as(value, &type_of(oid)?)
+}
+
+fn encode_as(
+    value: &(dyn ToSql + Sync),
+    ty: &postgres_types::Type,
+) -> crate::errors::Result<Option<Vec<u8>>> {
+    let mut buffer = bytes::BytesMut::new();
+
+    let is_null = value
+        .to_sql_checked(ty, &mut buffer)
+        .map_err(|err| crate::errors::Error::Backend(err.to_string()))?;
+
+    match is_null {
+        postgres_types::IsNull::Yes => Ok(None),
+        postgres_types::IsNull::No => Ok(Some(buffer.to_vec())),
+    }
+}
+
+/**
+ * Decodes the binary format `value` of the built-in type `oid`.
+ */
+pub fn decode<'a, T: FromSql<'a>>(
+    value: Option<&'a [u8]>,
+    oid: crate::Oid,
+) -> crate::errors::Result<T> {
+    decode_as(value, &type_of(oid)?)
+}
+
+fn decode_as<'a, T: FromSql<'a>>(
+    value: Option<&'a [u8]>,
+    ty: &postgres_types::Type,
+) -> crate::errors::Result<T> {
+    if !T::accepts(ty) {
+        return Err(crate::errors::Error::Backend(format!(
+            "cannot convert between the Rust type `{}` and the Postgres type `{ty}`",
+            std::any::type_name::<T>(),
+        )));
+    }
+
+    T::from_sql_nullable(ty, value).map_err(|err| crate::errors::Error::Backend(err.to_string()))
+}
+
+impl crate::Connection {
+    /**
+     * Executes `query` with parameters encoded by their [`ToSql`] implementation, for the types
+     * inferred by the server, and returns the rows in binary format.
+     *
+     * Columns are decoded with [`Row::decode`](crate::result::Row::decode), the types of the
+     * parameters and columns are resolved with [`Connection::sql_type`](crate::Connection::sql_type).
+     *
+     * ```no_run
+     * # let conn = libpq::Connection::new("")?;
+     * let result = conn.query_sql("SELECT $1::int4 + 1", &[&41i32])?;
+     * let answer: i32 = result.rows().next().unwrap().decode(0)?;
+     * # Ok::<(), libpq::errors::Error>(())
+     * ```
+     */
+    pub fn query_sql(
+        &self,
+        query: &str,
+        params: &[&(dyn ToSql + Sync)],
+    ) -> crate::errors::Result<crate::Result> {
+        self.check_result(self.prepare(None, query, &[]), ACCEPTED, query)?;
+
+        let description = self.check_result(self.describe_prepared(None), ACCEPTED, query)?;
+
+        if description.nparams() != params.len() {
+            return Err(crate::errors::Error::Backend(format!(
+                "expected {} parameters, got {}",
+                description.nparams(),
+                params.len()
+            )));
+        }
+
+        let oids = (0..params.len())
+            .map(|x| description.param_type(x).unwrap_or_default())
+            .collect::<Vec<_>>();
+        let values = params
+            .iter()
+            .zip(&oids)
+            .map(|(param, oid)| encode_as(*param, &self.sql_type(*oid)?))
+            .collect::<crate::errors::Result<Vec<_>>>()?;
+        let formats = vec![crate::Format::Binary; values.len()];
+
+        // Resolving the types runs queries, replacing the unnamed statement.
+        let mut result = self.check_result(
+            self.exec_params(query, &oids, &values, &formats, crate::Format::Binary),
+            ACCEPTED,
+            query,
+        )?;
+
+        result.sql_types = (0..result.nfields())
+            .map(|x| self.sql_type(result.field_type(x)))
+            .collect::<crate::errors::Result<_>>()?;
+
+        Ok(result)
+    }
+
+    /**
+     * Returns the [`postgres_types::Type`] of `oid`, read from `pg_type` for the types which
+     * aren't built-in.
+     *
+     * The types read are cached for the lifetime of the connection: changes, like the values
+     * added to an enum, aren't seen afterwards.
+     */
+    pub fn sql_type(&self, oid: crate::Oid) -> crate::errors::Result<postgres_types::Type> {
+        if let Some(ty) = postgres_types::Type::from_oid(oid) {
+            return Ok(ty);
+        }
+
+        if let Some(ty) = self.inner().sql_types.get(&oid) {
+            return Ok(ty.clone());
+        }
+
+        // The lock isn't held while querying, the resolution is recursive.
+        let ty = self.load_sql_type(oid)?;
+        self.inner().sql_types.insert(oid, ty.clone());
+
+        Ok(ty)
+    }
+
+    fn load_sql_type(&self, oid: crate::Oid) -> crate::errors::Result<postgres_types::Type> {
+        let query = "SELECT t.typname, n.nspname, t.typtype, t.typcategory, t.typelem, \
+                    t.typbasetype, t.typrelid, r.rngsubtype \
+             FROM pg_catalog.pg_type t \
+             JOIN pg_catalog.pg_namespace n ON n.oid = t.typnamespace \
+             LEFT JOIN pg_catalog.pg_range r ON r.rngtypid = t.oid \
+             WHERE t.oid = $1";
+        let result = self.catalog_query(query, oid)?;
+
+        let row = result.rows().next().ok_or_else(|| {
+            crate::errors::Error::Backend(format!("type oid {oid} not found in pg_type"))
+        })?;
+
+        let name = row.text(0)?.unwrap_or_default().into_owned();
+        let schema = row.text(1)?.unwrap_or_default().into_owned();
+        let category = row.text(3)?;
+        let element: crate::Oid = row.parse(4)?.unwrap_or_default();
+        let base: crate::Oid = row.parse(5)?.unwrap_or_default();
+        let relid: crate::Oid = row.parse(6)?.unwrap_or_default();
+        let subtype: crate::Oid = row.parse(7)?.unwrap_or_default();
+
+        let kind = match row.text(2)?.as_deref() {
+            Some("e") => postgres_types::Kind::Enum(self.enum_variants(oid)?),
+            Some("p") => postgres_types::Kind::Pseudo,
+            Some("d") => postgres_types::Kind::Domain(self.sql_type(base)?),
+            Some("r") => postgres_types::Kind::Range(self.sql_type(subtype)?),
+            Some("c") => postgres_types::Kind::Composite(self.composite_fields(relid)?),
+            _ if category.as_deref() == Some("A") && element != crate::oid::INVALID => {
+                postgres_types::Kind::Array(self.sql_type(element)?)
+            }
+            _ => postgres_types::Kind::Simple,
+        };
+
+        Ok(postgres_types::Type::new(name, oid, kind, schema))
+    }
+
+    fn enum_variants(&self, oid: crate::Oid) -> crate::errors::Result<Vec<String>> {
+        let query = "SELECT enumlabel FROM pg_catalog.pg_enum \
+             WHERE enumtypid = $1 ORDER BY enumsortorder";
+
+        self.catalog_query(query, oid)?
+            .rows()
+            .map(|row| Ok(row.text(0)?.unwrap_or_default().into_owned()))
+            .collect()
+    }
+
+    fn composite_fields(
+        &self,
+        relid: crate::Oid,
+    ) -> crate::errors::Result<Vec<postgres_types::Field>> {
+        let query = "SELECT attname, atttypid FROM pg_catalog.pg_attribute \
+             WHERE attrelid = $1 AND attnum > 0 AND NOT attisdropped ORDER BY attnum";
+
+        self.catalog_query(query, relid)?
+            .rows()
+            .map(|row| {
+                let name = row.text(0)?.unwrap_or_default().into_owned();
+                let ty = self.sql_type(row.parse(1)?.unwrap_or_default())?;
+
+                Ok(postgres_types::Field::new(name, ty))
+            })
+            .collect()
+    }
+
+    fn catalog_query(&self, query: &str, oid: crate::Oid) -> crate::errors::Result<crate::Result> {
+        self.check_result(
+            self.exec_params(query, &[], &crate::params![oid], &[], crate::Format::Text),
+            &[crate::Status::TupplesOk],
+            query,
+        )
+    }
+}
+
+impl<'a> crate::result::Row<'a> {
+    /**
+     * Decodes the value of a binary format column with its [`FromSql`] implementation.
+     */
+    pub fn decode<T: FromSql<'a>>(&self, column: usize) -> crate::errors::Result<T> {
+        if self.result().field_format(column) != crate::Format::Binary {
+            return Err(crate::errors::Error::Backend(format!(
+                "column {column} is not in binary format"
+            )));
+        }
+
+        match self.result().sql_types.get(column) {
+            Some(ty) => decode_as(self.value(column), ty),
+            None => decode(self.value(column), self.result().field_type(column)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #[test]
+    fn query_sql() {
+        let conn = crate::test::new_conn();
+
+        let result = conn
+            .query_sql(
+                "SELECT $1::int4 + 1, $2::text, $3::bytea",
+                &[&41i32, &None::<String>, &vec![0u8, 255]],
+            )
+            .unwrap();
+        let row = result.rows().next().unwrap();
+
+        assert_eq!(row.decode::<i32>(0), Ok(42));
+        assert_eq!(row.decode::<Option<&str>>(1), Ok(None));
+        assert_eq!(row.decode::<&[u8]>(2), Ok(&[0u8, 255][..]));
+        assert!(row.decode::<String>(0).is_err());
+
+        assert!(conn.query_sql("SELECT $1::int4", &[&"foo"]).is_err());
+        assert!(conn.query_sql("SELECT $1::int4", &[]).is_err());
+    }
+
+    #[derive(Debug, PartialEq)]
+    struct Mood(String);
+
+    impl<'a> super::FromSql<'a> for Mood {
+        fn from_sql(
+            _: &postgres_types::Type,
+            raw: &'a [u8],
+        ) -> Result<Self, Box<dyn std::error::Error + Sync + Send>> {
+            Ok(Self(std::str::from_utf8(raw)?.to_string()))
+        }
+
+        fn accepts(ty: &postgres_types::Type) -> bool {
+            ty.name() == "mood" && matches!(ty.kind(), postgres_types::Kind::Enum(_))
+        }
+    }
+
+    impl super::ToSql for Mood {
+        fn to_sql(
+            &self,
+            _: &postgres_types::Type,
+            out: &mut bytes::BytesMut,
+        ) -> Result<postgres_types::IsNull, Box<dyn std::error::Error + Sync + Send>> {
+            out.extend_from_slice(self.0.as_bytes());
+            Ok(postgres_types::IsNull::No)
+        }
+
+        fn accepts(ty: &postgres_types::Type) -> bool {
+            <Self as super::FromSql>::accepts(ty)
+        }
+
+        postgres_types::to_sql_checked!();
+    }
+
+    #[test]
+    fn user_defined_type() {
+        let conn = crate::test::new_conn();
+        conn.exec("CREATE TYPE pg_temp.mood AS ENUM ('sad', 'happy')");
+        conn.exec("CREATE TYPE pg_temp.entry AS (id int4, moods pg_temp.mood[])");
+
+        let result = conn
+            .query_sql(
+                "SELECT $1::pg_temp.mood, ARRAY['sad'::pg_temp.mood], NULL::pg_temp.entry",
+                &[&Mood("happy".to_string())],
+            )
+            .unwrap();
+        let row = result.rows().next().unwrap();
+        assert_eq!(row.decode::<Mood>(0), Ok(Mood("happy".to_string())));
+        assert_eq!(
+            row.decode::<Vec<Mood>>(1),
+            Ok(vec![Mood("sad".to_string())])
+        );
+        assert!(row.decode::<String>(0).is_err());
+
+        let mood = conn.sql_type(result.field_type(0)).unwrap();
+        assert_eq!(
+            mood.kind(),
+            &postgres_types::Kind::Enum(vec!["sad".to_string(), "happy".to_string()])
+        );
+
+        let entry = conn.sql_type(result.field_type(2)).unwrap();
+        let postgres_types::Kind::Composite(fields) = entry.kind() else {
+            panic!("{entry} isn't a composite");
+        };
+        assert_eq!(fields[0].type_(), &postgres_types::Type::INT4);
+        assert_eq!(fields[1].type_().kind(), &postgres_types::Kind::Array(mood));
+    }
+}
@@ -11,14 +11,14 @@ impl Connection {
     pub fn send_query(&self, command: &str) -> crate::errors::Result {
         log::trace!("Sending query '{}'", command);
 
-        let c_command = crate::ffi::to_cstr(command);
+        let c_command = crate::ffi::try_to_cstr(&self.comment(command))?;
 
         let success = unsafe { pq_sys::PQsendQuery(self.into(), c_command.as_ptr()) };
 
         if success == 1 {
             Ok(())
         } else {
-            self.error()
+            self.error_with_query(Some(command))
         }
     }
 
@@ -36,11 +36,11 @@ impl Connection {
         param_formats: &[crate::Format],
         result_format: crate::Format,
     ) -> crate::errors::Result {
-        let (values, formats, lengths) = Self::transform_params(param_values, param_formats);
+        let (values, formats, lengths) = Self::transform_params(param_values, param_formats)?;
 
         Self::trace_query("Sending", command, param_types, param_values, param_formats);
 
-        let c_command = crate::ffi::to_cstr(command);
+        let c_command = crate::ffi::try_to_cstr(&self.comment(command))?;
 
         let success = unsafe {
             pq_sys::PQsendQueryParams(
@@ -70,7 +70,7 @@ impl Connection {
         if success == 1 {
             Ok(())
         } else {
-            self.error()
+            self.error_with_query(Some(command))
         }
     }
 
@@ -90,8 +90,8 @@ impl Connection {
         let prefix = format!("Sending prepare {}", name.unwrap_or("anonymous"));
         Self::trace_query(&prefix, query, param_types, &[], &[]);
 
-        let c_name = crate::ffi::to_cstr(name.unwrap_or_default());
-        let c_query = crate::ffi::to_cstr(query);
+        let c_name = self.cached_cstr(name.unwrap_or_default())?;
+        let c_query = crate::ffi::try_to_cstr(&self.comment(query))?;
 
         let success = unsafe {
             pq_sys::PQsendPrepare(
@@ -106,7 +106,7 @@ impl Connection {
         if success == 1 {
             Ok(())
         } else {
-            self.error()
+            self.error_with_query(Some(query))
         }
     }
 
@@ -125,9 +125,9 @@ impl Connection {
         let prefix = format!("Send {} prepared query", name.unwrap_or("anonymous"));
         Self::trace_query(&prefix, "", &[], param_values, param_formats);
 
-        let (values, formats, lengths) = Self::transform_params(param_values, param_formats);
+        let (values, formats, lengths) = Self::transform_params(param_values, param_formats)?;
 
-        let c_name = crate::ffi::to_cstr(name.unwrap_or_default());
+        let c_name = self.cached_cstr(name.unwrap_or_default())?;
 
         let success = unsafe {
             pq_sys::PQsendQueryPrepared(
@@ -152,7 +152,7 @@ impl Connection {
         if success == 1 {
             Ok(())
         } else {
-            self.error()
+            self.error_with_query(name)
         }
     }
 
@@ -167,14 +167,14 @@ impl Connection {
             name.unwrap_or("anonymous")
         );
 
-        let c_name = crate::ffi::to_cstr(name.unwrap_or_default());
+        let c_name = self.cached_cstr(name.unwrap_or_default())?;
 
         let success = unsafe { pq_sys::PQsendDescribePrepared(self.into(), c_name.as_ptr()) };
 
         if success == 1 {
             Ok(())
         } else {
-            self.error()
+            self.error_with_query(name)
         }
     }
 
@@ -187,14 +187,14 @@ impl Connection {
     pub fn send_describe_portal(&self, name: Option<&str>) -> crate::errors::Result {
         log::trace!("Sending describe portal {}", name.unwrap_or("anonymous"));
 
-        let c_name = crate::ffi::to_cstr(name.unwrap_or_default());
+        let c_name = crate::ffi::try_to_cstr(name.unwrap_or_default())?;
 
         let success = unsafe { pq_sys::PQsendDescribePortal(self.into(), c_name.as_ptr()) };
 
         if success == 1 {
             Ok(())
         } else {
-            self.error()
+            self.error_with_query(name)
         }
     }
 
@@ -207,9 +207,10 @@ impl Connection {
         let raw = unsafe { pq_sys::PQgetResult(self.into()) };
 
         if raw.is_null() {
+            self.check_parameters();
             None
         } else {
-            Some(raw.into())
+            Some(self.to_result(raw))
         }
     }
 
@@ -225,6 +226,7 @@ impl Connection {
         let success = unsafe { pq_sys::PQconsumeInput(self.into()) };
 
         if success == 1 {
+            self.check_parameters();
             Ok(())
         } else {
             self.error()
@@ -256,7 +258,7 @@ impl Connection {
         let status = unsafe { pq_sys::PQsetnonblocking(self.into(), non_blocking as i32) };
 
         if status < 0 {
-            Err(crate::errors::Error::Unknow)
+            self.error()
         } else {
             Ok(())
         }
@@ -282,10 +284,13 @@ impl Connection {
 
         let status = unsafe { pq_sys::PQflush(self.into()) };
 
-        if status == 0 {
-            Ok(())
-        } else {
-            Err(crate::errors::Error::Unknow)
+        match status {
+            0 => Ok(()),
+            1 => Err(self.with_context(
+                crate::errors::Error::Backend("unable to send all the queued data yet".to_string()),
+                None,
+            )),
+            _ => self.error(),
         }
     }
 }
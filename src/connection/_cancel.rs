@@ -12,4 +12,37 @@ impl Connection {
     pub fn cancel(&self) -> crate::connection::Cancel {
         unsafe { pq_sys::PQgetCancel(self.into()) }.into()
     }
+
+    /**
+     * Cancels the command in progress, and waits for its end, when the connection is dropped.
+     *
+     * Otherwise the server keeps running the command until it notices the closed socket, which
+     * may never happen for long queries. Disabled by default.
+     */
+    pub fn set_cancel_on_drop(&self, cancel: bool) {
+        self.inner().cancel_on_drop = cancel;
+    }
+
+    fn cancel_in_flight(&self) {
+        if self.transaction_status() != crate::transaction::Status::Active {
+            return;
+        }
+
+        log::trace!("Canceling the command in progress before closing");
+
+        if let Err(err) = self.cancel().request() {
+            log::warn!("Unable to cancel the command in progress: {err}");
+            return;
+        }
+
+        // Waits for the server to acknowledge the cancellation.
+        while let Some(result) = self.result() {
+            if matches!(
+                result.status(),
+                crate::Status::CopyIn | crate::Status::CopyOut | crate::Status::CopyBoth
+            ) {
+                break;
+            }
+        }
+    }
 }
@@ -13,7 +13,7 @@ impl Connection {
     pub fn new(dsn: &str) -> crate::errors::Result<Self> {
         log::trace!("Connecting to '{}'", dsn);
 
-        let c_dsn = crate::ffi::to_cstr(dsn);
+        let c_dsn = crate::ffi::try_to_cstr(dsn)?;
 
         unsafe { pq_sys::PQconnectdb(c_dsn.as_ptr()) }.try_into()
     }
@@ -29,8 +29,8 @@ impl Connection {
     ) -> crate::errors::Result<Self> {
         log::trace!("Connecting with params {:?}", params);
 
-        let (_c_keywords, ptr_keywords) = crate::ffi::vec_to_nta(&params.keys().collect::<Vec<_>>());
-        let (_c_values, ptr_values) = crate::ffi::vec_to_nta(&params.values().collect::<Vec<_>>());
+        let (_c_keywords, ptr_keywords) = crate::ffi::vec_to_nta(&params.keys().collect::<Vec<_>>())?;
+        let (_c_values, ptr_values) = crate::ffi::vec_to_nta(&params.values().collect::<Vec<_>>())?;
 
         unsafe {
             pq_sys::PQconnectdbParams(ptr_keywords.as_ptr(), ptr_values.as_ptr(), expand_dbname as i32)
@@ -46,7 +46,7 @@ impl Connection {
     pub fn start(conninfo: &str) -> crate::errors::Result<Self> {
         log::trace!("Starting connection to '{}'", conninfo);
 
-        let c_conninfo = crate::ffi::to_cstr(conninfo);
+        let c_conninfo = crate::ffi::try_to_cstr(conninfo)?;
 
         unsafe { pq_sys::PQconnectStart(c_conninfo.as_ptr()) }.try_into()
     }
@@ -62,8 +62,8 @@ impl Connection {
     ) -> crate::errors::Result<Self> {
         log::trace!("Starting connection with params {:?}", params);
 
-        let (_c_keywords, ptr_keywords) = crate::ffi::vec_to_nta(&params.keys().collect::<Vec<_>>());
-        let (_c_values, ptr_values) = crate::ffi::vec_to_nta(&params.values().collect::<Vec<_>>());
+        let (_c_keywords, ptr_keywords) = crate::ffi::vec_to_nta(&params.keys().collect::<Vec<_>>())?;
+        let (_c_values, ptr_values) = crate::ffi::vec_to_nta(&params.values().collect::<Vec<_>>())?;
 
         unsafe {
             pq_sys::PQconnectStartParams(ptr_keywords.as_ptr(), ptr_values.as_ptr(), expand_dbname as i32)
@@ -101,13 +101,13 @@ impl Connection {
         login: Option<&str>,
         pwd: Option<&str>,
     ) -> crate::errors::Result<Self> {
-        let c_host = crate::ffi::to_cstr(host.unwrap_or_default());
-        let c_port = crate::ffi::to_cstr(port.unwrap_or_default());
-        let c_options = crate::ffi::to_cstr(options.unwrap_or_default());
-        let c_tty = crate::ffi::to_cstr(tty.unwrap_or_default());
-        let c_db_name = crate::ffi::to_cstr(db_name.unwrap_or_default());
-        let c_login = crate::ffi::to_cstr(login.unwrap_or_default());
-        let c_pwd = crate::ffi::to_cstr(pwd.unwrap_or_default());
+        let c_host = crate::ffi::try_to_cstr(host.unwrap_or_default())?;
+        let c_port = crate::ffi::try_to_cstr(port.unwrap_or_default())?;
+        let c_options = crate::ffi::try_to_cstr(options.unwrap_or_default())?;
+        let c_tty = crate::ffi::try_to_cstr(tty.unwrap_or_default())?;
+        let c_db_name = crate::ffi::try_to_cstr(db_name.unwrap_or_default())?;
+        let c_login = crate::ffi::try_to_cstr(login.unwrap_or_default())?;
+        let c_pwd = crate::ffi::try_to_cstr(pwd.unwrap_or_default())?;
 
         unsafe {
             pq_sys::PQsetdbLogin(
@@ -125,26 +125,35 @@ impl Connection {
 
     /**
      * See [PQconnectPoll](https://www.postgresql.org/docs/current/libpq-connect.html#LIBPQ-PQCONNECTPOLL).
+     *
+     * Takes `&mut self` since it may replace the strings borrowed by [`Connection::db_ref`] & co.
      */
-    pub fn poll(&self) -> crate::poll::Status {
+    pub fn poll(&mut self) -> crate::poll::Status {
         unsafe { pq_sys::PQconnectPoll(self.into()) }.into()
     }
 
     /**
      * Resets the communication channel to the server.
      *
+     * Host names are resolved again, so a DNS change is picked up, while `hostaddr` values are
+     * reused as is.
+     *
+     * Takes `&mut self` since it may free the strings borrowed by [`Connection::db_ref`] & co.
+     *
      * See [PQreset](https://www.postgresql.org/docs/current/libpq-connect.html#LIBPQ-PQRESET).
      */
-    pub fn reset(&self) {
+    pub fn reset(&mut self) {
         unsafe { pq_sys::PQreset(self.into()) };
     }
 
     /**
      * Reset the communication channel to the server, in a nonblocking manner.
      *
+     * Like [`Connection::reset`], host names are resolved again.
+     *
      * See [PQresetStart](https://www.postgresql.org/docs/current/libpq-connect.html#LIBPQ-PQRESETSTART).
      */
-    pub fn reset_start(&self) {
+    pub fn reset_start(&mut self) {
         unsafe { pq_sys::PQresetStart(self.into()) };
     }
 
@@ -152,7 +161,7 @@ impl Connection {
      * See
      * [PQresetPoll](https://www.postgresql.org/docs/current/libpq-connect.html#LIBPQ-PQRESETPOLL).
      */
-    pub fn reset_poll(&self) -> crate::poll::Status {
+    pub fn reset_poll(&mut self) -> crate::poll::Status {
         unsafe { pq_sys::PQresetPoll(self.into()) }.into()
     }
 
@@ -172,8 +181,14 @@ impl Connection {
     ) -> crate::ping::Status {
         log::trace!("Ping with params {:?}", params);
 
-        let (_c_keywords, ptr_keywords) = crate::ffi::vec_to_nta(&params.keys().collect::<Vec<_>>());
-        let (_c_values, ptr_values) = crate::ffi::vec_to_nta(&params.values().collect::<Vec<_>>());
+        let (_c_keywords, ptr_keywords) = match crate::ffi::vec_to_nta(&params.keys().collect::<Vec<_>>()) {
+            Ok(keywords) => keywords,
+            Err(_) => return crate::ping::Status::NoAttempt,
+        };
+        let (_c_values, ptr_values) = match crate::ffi::vec_to_nta(&params.values().collect::<Vec<_>>()) {
+            Ok(values) => values,
+            Err(_) => return crate::ping::Status::NoAttempt,
+        };
 
         unsafe { pq_sys::PQpingParams(ptr_keywords.as_ptr(), ptr_values.as_ptr(), expand_dbname as i32) }
             .into()
@@ -192,7 +207,10 @@ impl Connection {
     pub fn ping(dsn: &str) -> crate::ping::Status {
         log::trace!("Ping '{}'", dsn);
 
-        let c_dsn = crate::ffi::to_cstr(dsn);
+        let c_dsn = match crate::ffi::try_to_cstr(dsn) {
+            Ok(c_dsn) => c_dsn,
+            Err(_) => return crate::ping::Status::NoAttempt,
+        };
 
         unsafe { pq_sys::PQping(c_dsn.as_ptr()) }.into()
     }
@@ -12,18 +12,55 @@ impl Connection {
         unsafe { pq_sys::PQclientEncoding(self.into()) }.into()
     }
 
+    /**
+     * Decodes text received from the server, according to the client encoding.
+     *
+     * Invalid byte sequences are replaced, unless [`Connection::set_strict_utf8`] is enabled.
+     *
+     * See [`crate::Encoding::decode`].
+     */
+    pub fn decode<'a>(&self, bytes: &'a [u8]) -> crate::errors::Result<std::borrow::Cow<'a, str>> {
+        let encoding = self.client_encoding();
+
+        if self.inner().strict_utf8 {
+            encoding.decode_strict(bytes)
+        } else {
+            Ok(encoding.decode(bytes))
+        }
+    }
+
+    /**
+     * Makes [`Connection::decode`] fail with
+     * [`Error::InvalidUtf8`](crate::errors::Error::InvalidUtf8) on invalid byte sequences, instead
+     * of silently replacing them, for applications where corrupted text must be detected.
+     *
+     * Only `decode` is affected: the accessors returning strings from libpq, like
+     * [`Connection::db`], always fail with this error on invalid UTF-8, and the ones of the results,
+     * like [`Result::field_name`](crate::Result::field_name), on invalid byte sequences for the
+     * [`Result::encoding`](crate::Result::encoding).
+     *
+     * Disabled by default.
+     */
+    pub fn set_strict_utf8(&self, strict: bool) {
+        self.inner().strict_utf8 = strict;
+    }
+
     /**
      * Sets the client encoding.
      *
      * See [PQsetClientEncoding](https://www.postgresql.org/docs/current/libpq-control.html#LIBPQ-PQSETCLIENTENCODING).
      */
-    pub fn set_client_encoding(&self, encoding: crate::Encoding) {
+    pub fn set_client_encoding(&self, encoding: crate::Encoding) -> crate::errors::Result {
         log::trace!("Setting client encoding to '{:?}'", encoding);
 
         let c_encoding = crate::ffi::to_cstr(&encoding.to_string());
 
-        unsafe {
-            pq_sys::PQsetClientEncoding(self.into(), c_encoding.as_ptr());
+        let success = unsafe { pq_sys::PQsetClientEncoding(self.into(), c_encoding.as_ptr()) };
+
+        if success == 0 {
+            Ok(())
+        } else {
+            self.error()
         }
     }
 
@@ -12,20 +12,23 @@ impl Connection {
     pub fn put_copy_data(&self, buffer: &[u8]) -> crate::errors::Result {
         log::trace!("Sending copy data");
 
-        let success = unsafe {
-            pq_sys::PQputCopyData(
-                self.into(),
-                buffer.as_ptr() as *const libc::c_char,
-                buffer.len() as i32,
-            )
-        };
+        self.instrument("put_copy_data", None, &[], || {
+            let success = unsafe {
+                pq_sys::PQputCopyData(
+                    self.into(),
+                    buffer.as_ptr() as *const libc::c_char,
+                    buffer.len() as i32,
+                )
+            };
 
-        match success {
-            -1 => self.error(),
-            0 => Err(crate::errors::Error::Backend("Full buffers".to_string())),
-            1 => Ok(()),
-            _ => unreachable!(),
-        }
+            match success {
+                -1 => self.error(),
+                0 => Err(crate::errors::Error::Backend("Full buffers".to_string())),
+                1 => Ok(buffer.len()),
+                _ => self.error(),
+            }
+        })
+        .map(|_| ())
     }
 
     /**
@@ -37,21 +40,23 @@ impl Connection {
     pub fn put_copy_end(&self, errormsg: Option<&str>) -> crate::errors::Result {
         log::trace!("End of copy");
 
-        let cstr = errormsg.map(crate::ffi::to_cstr);
+        let cstr = errormsg.map(crate::ffi::try_to_cstr).transpose()?;
         let ptr = if let Some(ref cstr) = cstr {
             cstr.as_ptr()
         } else {
             std::ptr::null()
         };
 
-        let success = unsafe { pq_sys::PQputCopyEnd(self.into(), ptr) };
+        self.instrument("put_copy_end", None, &[], || {
+            let success = unsafe { pq_sys::PQputCopyEnd(self.into(), ptr) };
 
-        match success {
-            -1 => self.error(),
-            0 => Err(crate::errors::Error::Backend("Full buffers".to_string())),
-            1 => Ok(()),
-            _ => unreachable!(),
-        }
+            match success {
+                -1 => self.error(),
+                0 => Err(crate::errors::Error::Backend("Full buffers".to_string())),
+                1 => Ok(()),
+                _ => self.error(),
+            }
+        })
     }
 
     /**
@@ -63,17 +68,56 @@ impl Connection {
      * [PQgetCopyData](https://www.postgresql.org/docs/current/libpq-copy.html#LIBPQ-PQGETCOPYDATA)
      */
     pub fn copy_data(&self, r#async: bool) -> crate::errors::Result<PqBytes> {
-        let mut ptr = std::ptr::null_mut();
+        self.instrument("copy_data", None, &[], || {
+            let mut ptr = std::ptr::null_mut();
+
+            let success = unsafe { pq_sys::PQgetCopyData(self.into(), &mut ptr, r#async as i32) };
+
+            match success {
+                -2 => self.error(),
+                -1 => Err(crate::errors::Error::Backend("COPY is done".to_string())),
+                0 => Err(crate::errors::Error::Backend(
+                    "COPY still in progress".to_string(),
+                )),
+                nbytes => Ok(PqBytes::from_raw(ptr as *mut libc::c_void, nbytes as usize)),
+            }
+        })
+    }
+
+    /**
+     * Like [`Connection::copy_data`], appending the data to a buffer of the caller, which can be
+     * reused across calls. Returns the number of bytes appended.
+     */
+    pub fn copy_data_into(
+        &self,
+        r#async: bool,
+        buffer: &mut Vec<u8>,
+    ) -> crate::errors::Result<usize> {
+        self.instrument("copy_data", None, &[], || {
+            let mut ptr = std::ptr::null_mut();
+
+            let success = unsafe { pq_sys::PQgetCopyData(self.into(), &mut ptr, r#async as i32) };
+
+            match success {
+                -2 => self.error(),
+                -1 => Err(crate::errors::Error::Backend("COPY is done".to_string())),
+                0 => Err(crate::errors::Error::Backend(
+                    "COPY still in progress".to_string(),
+                )),
+                nbytes => {
+                    let nbytes = nbytes as usize;
 
-        let success = unsafe { pq_sys::PQgetCopyData(self.into(), &mut ptr, r#async as i32) };
+                    unsafe {
+                        buffer.extend_from_slice(std::slice::from_raw_parts(
+                            ptr as *const u8,
+                            nbytes,
+                        ));
+                        pq_sys::PQfreemem(ptr as *mut std::ffi::c_void);
+                    }
 
-        match success {
-            -2 => self.error(),
-            -1 => Err(crate::errors::Error::Backend("COPY is done".to_string())),
-            0 => Err(crate::errors::Error::Backend(
-                "COPY still in progress".to_string(),
-            )),
-            nbytes => Ok(PqBytes::from_raw(ptr as *mut libc::c_void, nbytes as usize)),
-        }
+                    Ok(nbytes)
+                }
+            }
+        })
     }
 }
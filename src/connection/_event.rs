@@ -0,0 +1,85 @@
+/**
+ * [Event System](https://www.postgresql.org/docs/current/libpq-events.html)
+ */
+impl Connection {
+    /**
+     * Registers `event` to be called for the lifecycle events of this connection and of its
+     * results.
+     *
+     * `name` is used in error messages. An event type can only be registered once per
+     * connection.
+     *
+     * See [PQregisterEventProc](https://www.postgresql.org/docs/current/libpq-events.html#LIBPQ-PQREGISTEREVENTPROC).
+     */
+    pub fn register_event<E: crate::event::Event>(
+        &self,
+        name: &str,
+        event: E,
+    ) -> crate::errors::Result {
+        let c_name = crate::ffi::try_to_cstr(name)?;
+        let pass_through = std::sync::Arc::into_raw(std::sync::Arc::new(event));
+
+        let success = unsafe {
+            pq_sys::PQregisterEventProc(
+                self.into(),
+                Some(crate::event::proc::<E>),
+                c_name.as_ptr(),
+                pass_through as *mut libc::c_void,
+            )
+        };
+
+        if success == 0 {
+            // libpq doesn't keep the event on failure.
+            unsafe { std::sync::Arc::decrement_strong_count(pass_through) };
+            Err(crate::errors::Error::CallFailed("PQregisterEventProc"))
+        } else {
+            Ok(())
+        }
+    }
+
+    /**
+     * Attaches `data` to this connection for the event `E`, replacing the previous one.
+     *
+     * It fails if `E` is not registered on this connection. The data is dropped with the
+     * connection.
+     *
+     * See [PQsetInstanceData](https://www.postgresql.org/docs/current/libpq-events.html#LIBPQ-PQSETINSTANCEDATA).
+     */
+    pub fn set_instance_data<E, T>(&self, data: T) -> crate::errors::Result
+    where
+        E: crate::event::Event,
+        T: std::any::Any + Send + Sync,
+    {
+        let proc: pq_sys::PGEventProc = Some(crate::event::proc::<E>);
+        let ptr = crate::event::into_raw(std::sync::Arc::new(data));
+
+        unsafe {
+            let previous = pq_sys::PQinstanceData(self.into(), proc);
+
+            if pq_sys::PQsetInstanceData(self.into(), proc, ptr) == 0 {
+                drop(crate::event::from_raw(ptr));
+                Err(crate::errors::Error::CallFailed("PQsetInstanceData"))
+            } else {
+                drop(crate::event::from_raw(previous));
+                Ok(())
+            }
+        }
+    }
+
+    /**
+     * Returns the data attached to this connection for the event `E`, if it is a `T`.
+     *
+     * See [PQinstanceData](https://www.postgresql.org/docs/current/libpq-events.html#LIBPQ-PQINSTANCEDATA).
+     */
+    pub fn instance_data<E, T>(&self) -> Option<std::sync::Arc<T>>
+    where
+        E: crate::event::Event,
+        T: std::any::Any + Send + Sync,
+    {
+        unsafe {
+            let ptr = pq_sys::PQinstanceData(self.into(), Some(crate::event::proc::<E>));
+
+            crate::event::downcast(ptr)
+        }
+    }
+}
@@ -10,8 +10,13 @@ impl Connection {
     pub fn exec(&self, query: &str) -> crate::Result {
         log::trace!("Execute query '{}'", query);
 
-        let c_query = crate::ffi::to_cstr(query);
-        unsafe { pq_sys::PQexec(self.into(), c_query.as_ptr()) }.into()
+        let c_query = match crate::ffi::try_to_cstr(&self.comment(query)) {
+            Ok(c_query) => c_query,
+            Err(err) => return Self::error_result(err),
+        };
+        self.instrument("exec", Some(query), &[], || {
+            self.to_result(unsafe { pq_sys::PQexec(self.into(), c_query.as_ptr()) })
+        })
     }
 
     /**
@@ -28,37 +33,75 @@ impl Connection {
         param_formats: &[crate::Format],
         result_format: crate::Format,
     ) -> crate::Result {
-        let (values, formats, lengths) = Self::transform_params(param_values, param_formats);
+        let (values, formats, lengths) = match Self::transform_params(param_values, param_formats) {
+            Ok(params) => params,
+            Err(err) => return Self::error_result(err),
+        };
 
         Self::trace_query("Sending", command, param_types, param_values, param_formats);
 
-        let c_command = crate::ffi::to_cstr(command);
-
-        unsafe {
-            pq_sys::PQexecParams(
-                self.into(),
-                c_command.as_ptr(),
-                values.len() as i32,
-                if param_types.is_empty() {
-                    std::ptr::null()
-                } else {
-                    param_types.as_ptr()
-                },
-                values.as_ptr(),
-                if lengths.is_empty() {
-                    std::ptr::null()
-                } else {
-                    lengths.as_ptr()
-                },
-                if formats.is_empty() {
-                    std::ptr::null()
-                } else {
-                    formats.as_ptr()
-                },
-                result_format as i32,
-            )
-        }
-        .into()
+        let c_command = match crate::ffi::try_to_cstr(&self.comment(command)) {
+            Ok(c_command) => c_command,
+            Err(err) => return Self::error_result(err),
+        };
+
+        self.instrument("exec_params", Some(command), param_values, || {
+            let raw = unsafe {
+                pq_sys::PQexecParams(
+                    self.into(),
+                    c_command.as_ptr(),
+                    values.len() as i32,
+                    if param_types.is_empty() {
+                        std::ptr::null()
+                    } else {
+                        param_types.as_ptr()
+                    },
+                    values.as_ptr(),
+                    if lengths.is_empty() {
+                        std::ptr::null()
+                    } else {
+                        lengths.as_ptr()
+                    },
+                    if formats.is_empty() {
+                        std::ptr::null()
+                    } else {
+                        formats.as_ptr()
+                    },
+                    result_format as i32,
+                )
+            };
+
+            self.to_result(raw)
+        })
+    }
+
+    /**
+     * Same as [`Connection::exec`], with a result borrowing the connection.
+     */
+    pub fn scoped_exec(&self, query: &str) -> crate::result::Scoped<'_> {
+        crate::result::Scoped::new(self, self.exec(query))
+    }
+
+    /**
+     * Same as [`Connection::exec_params`], with a result borrowing the connection.
+     */
+    pub fn scoped_exec_params(
+        &self,
+        command: &str,
+        param_types: &[crate::Oid],
+        param_values: &[Option<Vec<u8>>],
+        param_formats: &[crate::Format],
+        result_format: crate::Format,
+    ) -> crate::result::Scoped<'_> {
+        let result = self.exec_params(
+            command,
+            param_types,
+            param_values,
+            param_formats,
+            result_format,
+        );
+
+        crate::result::Scoped::new(self, result)
     }
 
     /**
@@ -75,19 +118,28 @@ impl Connection {
         let prefix = format!("Prepare {}", name.unwrap_or("anonymous"));
         Self::trace_query(&prefix, query, param_types, &[], &[]);
 
-        let c_name = crate::ffi::to_cstr(name.unwrap_or_default());
-        let c_query = crate::ffi::to_cstr(query);
-
-        unsafe {
-            pq_sys::PQprepare(
-                self.into(),
-                c_name.as_ptr(),
-                c_query.as_ptr(),
-                param_types.len() as i32,
-                param_types.as_ptr(),
-            )
-        }
-        .into()
+        let c_name = match self.cached_cstr(name.unwrap_or_default()) {
+            Ok(c_name) => c_name,
+            Err(err) => return Self::error_result(err),
+        };
+        let c_query = match crate::ffi::try_to_cstr(&self.comment(query)) {
+            Ok(c_query) => c_query,
+            Err(err) => return Self::error_result(err),
+        };
+
+        self.instrument("prepare", Some(query), &[], || {
+            let raw = unsafe {
+                pq_sys::PQprepare(
+                    self.into(),
+                    c_name.as_ptr(),
+                    c_query.as_ptr(),
+                    param_types.len() as i32,
+                    param_types.as_ptr(),
+                )
+            };
+
+            self.to_result(raw)
+        })
     }
 
     /**
@@ -106,30 +158,40 @@ impl Connection {
         let prefix = format!("Execute {} prepared query", name.unwrap_or("anonymous"));
         Self::trace_query(&prefix, "", &[], param_values, param_formats);
 
-        let (values, formats, lengths) = Self::transform_params(param_values, param_formats);
-
-        let c_name = crate::ffi::to_cstr(name.unwrap_or_default());
-
-        unsafe {
-            pq_sys::PQexecPrepared(
-                self.into(),
-                c_name.as_ptr(),
-                values.len() as i32,
-                values.as_ptr(),
-                if lengths.is_empty() {
-                    std::ptr::null()
-                } else {
-                    lengths.as_ptr()
-                },
-                if formats.is_empty() {
-                    std::ptr::null()
-                } else {
-                    formats.as_ptr()
-                },
-                result_format as i32,
-            )
-        }
-        .into()
+        let (values, formats, lengths) = match Self::transform_params(param_values, param_formats) {
+            Ok(params) => params,
+            Err(err) => return Self::error_result(err),
+        };
+
+        let name = name.unwrap_or_default();
+        let c_name = match self.cached_cstr(name) {
+            Ok(c_name) => c_name,
+            Err(err) => return Self::error_result(err),
+        };
+
+        self.instrument_prepared("exec_prepared", name, param_values, || {
+            let raw = unsafe {
+                pq_sys::PQexecPrepared(
+                    self.into(),
+                    c_name.as_ptr(),
+                    values.len() as i32,
+                    values.as_ptr(),
+                    if lengths.is_empty() {
+                        std::ptr::null()
+                    } else {
+                        lengths.as_ptr()
+                    },
+                    if formats.is_empty() {
+                        std::ptr::null()
+                    } else {
+                        formats.as_ptr()
+                    },
+                    result_format as i32,
+                )
+            };
+
+            self.to_result(raw)
+        })
     }
 
     /**
@@ -139,9 +201,12 @@ impl Connection {
      * See [PQdescribePrepared](https://www.postgresql.org/docs/current/libpq-exec.html#LIBPQ-PQDESCRIBEPREPARED).
      */
     pub fn describe_prepared(&self, name: Option<&str>) -> crate::Result {
-        let c_name = crate::ffi::to_cstr(name.unwrap_or_default());
+        let c_name = match self.cached_cstr(name.unwrap_or_default()) {
+            Ok(c_name) => c_name,
+            Err(err) => return Self::error_result(err),
+        };
 
-        unsafe { pq_sys::PQdescribePrepared(self.into(), c_name.as_ptr()) }.into()
+        self.to_result(unsafe { pq_sys::PQdescribePrepared(self.into(), c_name.as_ptr()) })
     }
 
     /**
@@ -150,9 +215,12 @@ impl Connection {
      * See [PQdescribePortal](https://www.postgresql.org/docs/current/libpq-exec.html#LIBPQ-PQDESCRIBEPORTAL).
      */
     pub fn describe_portal(&self, name: Option<&str>) -> crate::Result {
-        let c_name = crate::ffi::to_cstr(name.unwrap_or_default());
+        let c_name = match crate::ffi::try_to_cstr(name.unwrap_or_default()) {
+            Ok(c_name) => c_name,
+            Err(err) => return Self::error_result(err),
+        };
 
-        unsafe { pq_sys::PQdescribePortal(self.into(), c_name.as_ptr()) }.into()
+        self.to_result(unsafe { pq_sys::PQdescribePortal(self.into(), c_name.as_ptr()) })
     }
 
     /**
@@ -179,7 +247,8 @@ impl Connection {
     }
 
     /**
-     * Escape string literals, much like `libpq::Connection::literal`.
+     * Escapes string literals, much like [`Connection::escape_literal`] but without the
+     * surrounding quotes.
      *
      * On success, this method returns [`PqString`].
      *
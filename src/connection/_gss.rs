@@ -1,19 +1,26 @@
+/**
+ * [GSSAPI Status Functions](https://www.postgresql.org/docs/current/libpq-status.html)
+ */
 impl Connection {
     /**
-     * Return true if GSSAPI encryption is in use
+     * Returns `true` if GSSAPI encryption is in use.
+     *
+     * See [PQgssEncInUse](https://www.postgresql.org/docs/current/libpq-status.html#LIBPQ-PQGSSENCINUSE).
      */
     pub fn gss_enc_in_use(&self) -> bool {
         unsafe { pq_sys::PQgssEncInUse(self.into()) != 0 }
     }
 
     /**
-     * Returns GSSAPI context if GSSAPI is in use
+     * Returns the GSSAPI context, or null if GSSAPI is not in use.
+     *
+     * See [PQgetgssctx](https://www.postgresql.org/docs/current/libpq-status.html#LIBPQ-PQGETGSSCTX).
      *
      * # Safety
      *
      * This function returns a `void*` pointer.
      */
-    pub fn gss_context(&self) -> *const std::ffi::c_void {
-        unsafe { pq_sys::PQgetgssctx(self.into()) }
+    pub unsafe fn gss_context(&self) -> *const std::ffi::c_void {
+        pq_sys::PQgetgssctx(self.into())
     }
 }
@@ -2,12 +2,78 @@
  * [Notice Processing](https://www.postgresql.org/docs/current/libpq-notice-processing.html)
  */
 impl Connection {
+    /**
+     * Sets a closure called with the formatted message of each notice or warning.
+     *
+     * The closure replaces the default processor, which prints the message on `stderr`.
+     *
+     * See [PQsetNoticeProcessor](https://www.postgresql.org/docs/current/libpq-notice-processing.html).
+     */
+    pub fn set_notice_processor<F>(&self, processor: F)
+    where
+        F: FnMut(&str) + Send + 'static,
+    {
+        let mut processor: Box<inner::NoticeProcessor> = Box::new(Box::new(processor));
+        let arg = &mut *processor as *mut inner::NoticeProcessor as *mut libc::c_void;
+
+        unsafe {
+            self.set_raw_notice_processor(Some(notice_processor_trampoline), arg);
+        }
+
+        self.inner().notice_processor = Some(processor);
+    }
+
+    /**
+     * Sets a closure called with the [`Result`](crate::Result) of each notice or warning.
+     *
+     * The result is owned by libpq and only valid during the call. Once a receiver is set, the
+     * notice processor is no longer called.
+     *
+     * See [PQsetNoticeReceiver](https://www.postgresql.org/docs/current/libpq-notice-processing.html).
+     */
+    pub fn set_notice_receiver<F>(&self, receiver: F)
+    where
+        F: FnMut(&crate::Result) + Send + 'static,
+    {
+        let mut receiver: Box<inner::NoticeReceiver> = Box::new(Box::new(receiver));
+        let arg = &mut *receiver as *mut inner::NoticeReceiver as *mut libc::c_void;
+
+        unsafe {
+            self.set_raw_notice_receiver(Some(notice_receiver_trampoline), arg);
+        }
+
+        let mut inner = self.inner();
+        inner.notice_receiver = Some(receiver);
+        inner.notices = None;
+    }
+
+    /**
+     * Captures the notices and warnings raised by each call to [`exec`](Self::exec),
+     * [`exec_params`](Self::exec_params), [`prepare`](Self::prepare) and
+     * [`exec_prepared`](Self::exec_prepared), see [`Result::notices`](crate::Result::notices).
+     *
+     * The capture replaces the notice receiver and processor, setting a notice receiver stops
+     * it. Notices raised outside of these calls are dropped.
+     */
+    pub fn capture_notices(&self) {
+        let notices = inner::Notices::default();
+
+        let n = notices.clone();
+        self.set_notice_receiver(move |result| {
+            n.lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .push(crate::notice::Notice::from(result))
+        });
+
+        self.inner().notices = Some(notices);
+    }
+
     /**
      * # Safety
      *
      * This function takes a `void*` pointer as argument.
      */
-    pub unsafe fn set_notice_processor(
+    pub unsafe fn set_raw_notice_processor(
         &self,
         proc: NoticeProcessor,
         arg: *mut libc::c_void,
@@ -20,7 +86,7 @@ impl Connection {
      *
      * This function takes a `void*` pointer as argument.
      */
-    pub unsafe fn set_notice_receiver(
+    pub unsafe fn set_raw_notice_receiver(
         &self,
         proc: NoticeReceiver,
         arg: *mut libc::c_void,
@@ -28,3 +94,24 @@ impl Connection {
         pq_sys::PQsetNoticeReceiver(self.into(), proc, arg)
     }
 }
+
+unsafe extern "C" fn notice_processor_trampoline(
+    arg: *mut libc::c_void,
+    message: *const libc::c_char,
+) {
+    let processor = &mut *(arg as *mut inner::NoticeProcessor);
+    let message = std::ffi::CStr::from_ptr(message).to_string_lossy();
+
+    crate::ffi::catch_unwind("notice processor", || processor(&message));
+}
+
+unsafe extern "C" fn notice_receiver_trampoline(
+    arg: *mut libc::c_void,
+    result: *const pq_sys::PGresult,
+) {
+    let receiver = &mut *(arg as *mut inner::NoticeReceiver);
+    // The result is owned by libpq, it must not be cleared.
+    let result = std::mem::ManuallyDrop::new(crate::Result::from(result as *mut pq_sys::PGresult));
+
+    crate::ffi::catch_unwind("notice receiver", || receiver(&result));
+}
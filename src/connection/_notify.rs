@@ -8,6 +8,8 @@ impl Connection {
      */
     pub fn notifies(&self) -> Option<crate::connection::Notify> {
         let raw = unsafe { pq_sys::PQnotifies(self.into()) };
+        // `PQnotifies` parses the pending messages, including the parameter changes.
+        self.check_parameters();
 
         if raw.is_null() {
             None
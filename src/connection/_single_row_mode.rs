@@ -16,7 +16,9 @@ impl Connection {
         if success == 1 {
             Ok(())
         } else {
-            Err(crate::errors::Error::Unknow)
+            Err(crate::errors::Error::Backend(
+                "single-row mode must be selected right after sending a query".to_string(),
+            ))
         }
     }
 }
@@ -5,10 +5,10 @@ impl Connection {
     /**
      * Allows applications to select which security libraries to initialize.
      *
-     * See [PQinitOpenSSL](https://www.postgresql.org/docs/current/libpq-ssl.html#LIBPQ-PQINITOPENSSL).
+     * See [`crate::ssl::init`].
      */
     pub fn init_openssl(do_ssl: bool, do_crypto: bool) {
-        unsafe { pq_sys::PQinitOpenSSL(do_ssl as i32, do_crypto as i32); }
+        crate::ssl::init(do_ssl, do_crypto);
     }
 
     /**
@@ -19,4 +19,22 @@ impl Connection {
     pub fn init_ssl(do_ssl: bool) {
         unsafe { pq_sys::PQinitSSL(do_ssl as i32); }
     }
+
+    /**
+     * Returns the OpenSSL object of the connection, or `None` if SSL is not in use.
+     *
+     * See [PQsslStruct](https://www.postgresql.org/docs/current/libpq-status.html#LIBPQ-PQSSLSTRUCT).
+     */
+    #[cfg(feature = "openssl")]
+    pub fn ssl_ref(&self) -> Option<&openssl::ssl::SslRef> {
+        use foreign_types::ForeignTypeRef;
+
+        let ssl = unsafe { self.ssl_struct("OpenSSL") }.ok()?;
+
+        if ssl.is_null() {
+            None
+        } else {
+            Some(unsafe { openssl::ssl::SslRef::from_ptr(ssl as *mut _) })
+        }
+    }
 }
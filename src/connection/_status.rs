@@ -85,6 +85,61 @@ impl Connection {
         crate::ffi::to_option_string(unsafe { pq_sys::PQoptions(self.into()) })
     }
 
+    /**
+     * Like [`Connection::db`], without allocating.
+     *
+     * The string is owned by libpq, the borrow prevents a [`Connection::reset`] from freeing it.
+     */
+    pub fn db_ref(&self) -> crate::errors::Result<&str> {
+        crate::ffi::to_str(unsafe { pq_sys::PQdb(self.into()) })
+    }
+
+    /**
+     * Like [`Connection::user`], without allocating.
+     *
+     * The string is owned by libpq, the borrow prevents a [`Connection::reset`] from freeing it.
+     */
+    pub fn user_ref(&self) -> crate::errors::Result<&str> {
+        crate::ffi::to_str(unsafe { pq_sys::PQuser(self.into()) })
+    }
+
+    /**
+     * Like [`Connection::host`], without allocating.
+     *
+     * The string is owned by libpq, the borrow prevents a [`Connection::reset`] from freeing it.
+     */
+    pub fn host_ref(&self) -> crate::errors::Result<&str> {
+        crate::ffi::to_str(unsafe { pq_sys::PQhost(self.into()) })
+    }
+
+    /**
+     * Like [`Connection::hostaddr`], without allocating.
+     *
+     * The string is owned by libpq, the borrow prevents a [`Connection::reset`] from freeing it.
+     */
+    #[cfg(feature = "v12")]
+    pub fn hostaddr_ref(&self) -> crate::errors::Result<&str> {
+        crate::ffi::to_str(unsafe { pq_sys::PQhostaddr(self.into()) })
+    }
+
+    /**
+     * Like [`Connection::port`], without allocating.
+     *
+     * The string is owned by libpq, the borrow prevents a [`Connection::reset`] from freeing it.
+     */
+    pub fn port_ref(&self) -> crate::errors::Result<&str> {
+        crate::ffi::to_str(unsafe { pq_sys::PQport(self.into()) })
+    }
+
+    /**
+     * Like [`Connection::options`], without allocating.
+     *
+     * The string is owned by libpq, the borrow prevents a [`Connection::reset`] from freeing it.
+     */
+    pub fn options_ref(&self) -> crate::errors::Result<Option<&str>> {
+        crate::ffi::to_option_str(unsafe { pq_sys::PQoptions(self.into()) })
+    }
+
     /**
      * Returns the status of the connection.
      *
@@ -109,7 +164,7 @@ impl Connection {
      * See [PQparameterStatus](https://www.postgresql.org/docs/current/libpq-status.html#LIBPQ-PQPARAMETERSTATUS).
      */
     pub fn parameter_status(&self, param: &str) -> crate::errors::Result<String> {
-        let c_param = crate::ffi::to_cstr(param);
+        let c_param = self.cached_cstr(param)?;
 
         crate::ffi::to_string(unsafe { pq_sys::PQparameterStatus(self.into(), c_param.as_ptr()) })
     }
@@ -117,10 +172,29 @@ impl Connection {
     /**
      * Interrogates the frontend/backend protocol being used.
      *
+     * Without the `v18` feature, only the major version is known and is reported as
+     * [`ProtocolVersion::V3_0`].
+     *
      * See [PQprotocolVersion](https://www.postgresql.org/docs/current/libpq-status.html#LIBPQ-PQPROTOCOLVERSION).
      */
-    pub fn protocol_version(&self) -> i32 {
-        unsafe { pq_sys::PQprotocolVersion(self.into()) }
+    pub fn protocol_version(&self) -> ProtocolVersion {
+        #[cfg(feature = "v18")]
+        let version = self.full_protocol_version();
+        #[cfg(not(feature = "v18"))]
+        let version = unsafe { pq_sys::PQprotocolVersion(self.into()) } * 10_000;
+
+        version.into()
+    }
+
+    /**
+     * Interrogates the frontend/backend protocol being used, in the `major * 10000 + minor`
+     * form.
+     *
+     * See [PQfullProtocolVersion](https://www.postgresql.org/docs/current/libpq-status.html#LIBPQ-PQFULLPROTOCOLVERSION).
+     */
+    #[cfg(feature = "v18")]
+    pub fn full_protocol_version(&self) -> i32 {
+        unsafe { pq_sys::PQfullProtocolVersion(self.into()) }
     }
 
     /**
@@ -132,6 +206,16 @@ impl Connection {
         unsafe { pq_sys::PQserverVersion(self.into()) }
     }
 
+    /**
+     * Returns the server version as a [`ServerVersion`], or `None` if the connection is bad.
+     */
+    pub fn server_version_info(&self) -> Option<ServerVersion> {
+        match self.server_version() {
+            0 => None,
+            version => Some(version.into()),
+        }
+    }
+
     /**
      * Returns the error message most recently generated by an operation on the connection.
      *
@@ -161,17 +245,18 @@ impl Connection {
     }
 
     /**
-     * Obtains the file descriptor number of the connection socket to the server.
+     * Obtains the file descriptor number of the connection socket to the server, the `SOCKET`
+     * on Windows.
      *
      * See [PQsocket](https://www.postgresql.org/docs/current/libpq-status.html#LIBPQ-PQSOCKET).
      */
-    pub fn socket(&self) -> crate::errors::Result<i32> {
+    pub fn socket(&self) -> crate::errors::Result<crate::connection::Socket> {
         let socket = unsafe { pq_sys::PQsocket(self.into()) };
 
         if socket < 0 {
-            Err(crate::errors::Error::Unknow)
+            Err(crate::errors::Error::NoSocket(self.status()))
         } else {
-            Ok(socket)
+            Ok(crate::connection::socket::from_raw(socket))
         }
     }
 
@@ -213,13 +298,32 @@ impl Connection {
         unsafe { pq_sys::PQsslInUse(self.into()) == 1 }
     }
 
+    /**
+     * Returns `true` if the connection uses SSL and its handshake was started directly, without
+     * the PostgreSQL protocol negotiation.
+     *
+     * libpq never falls back to the PostgreSQL negotiation once `sslnegotiation=direct` is set, so
+     * this is derived from the connection options.
+     */
+    #[cfg(feature = "v17")]
+    pub fn ssl_direct_negotiation(&self) -> bool {
+        self.ssl_in_use()
+            && self
+                .info()
+                .ok()
+                .and_then(|mut x| x.remove("sslnegotiation"))
+                .and_then(|x| x.val)
+                .as_deref()
+                == Some(crate::ssl::Negotiation::Direct.as_str())
+    }
+
     /**
      * Returns SSL-related information about the connection.
      *
      * See [PQsslAttribute](https://www.postgresql.org/docs/current/libpq-status.html#LIBPQ-PQSSLATTRIBUTE).
      */
     pub fn ssl_attribute(&self, attribute: crate::ssl::Attribute) -> crate::errors::Result<Option<String>> {
-        let c_attribute = crate::ffi::to_cstr(&attribute.to_string());
+        let c_attribute = crate::ffi::try_to_cstr(&attribute.to_string())?;
 
         let raw = unsafe { pq_sys::PQsslAttribute(self.into(), c_attribute.as_ptr()) };
 
@@ -230,6 +334,42 @@ impl Connection {
         }
     }
 
+    /**
+     * Returns the SSL-related information about the connection, or `None` if SSL is not in use.
+     *
+     * See [PQsslAttribute](https://www.postgresql.org/docs/current/libpq-status.html#LIBPQ-PQSSLATTRIBUTE).
+     */
+    pub fn ssl_info(&self) -> Option<crate::ssl::Info> {
+        if !self.ssl_in_use() {
+            return None;
+        }
+
+        let attribute = |x| self.ssl_attribute(x).ok().flatten();
+
+        #[cfg(feature = "v16")]
+        let alpn = attribute(crate::ssl::Attribute::Alpn);
+        #[cfg(not(feature = "v16"))]
+        let alpn = None;
+
+        let info = crate::ssl::Info {
+            library: attribute(crate::ssl::Attribute::Library).unwrap_or_default(),
+            protocol: attribute(crate::ssl::Attribute::Protocol)
+                .unwrap_or_default()
+                .as_str()
+                .into(),
+            cipher: attribute(crate::ssl::Attribute::Cipher).unwrap_or_default(),
+            key_bits: attribute(crate::ssl::Attribute::KeyBits)
+                .and_then(|x| x.parse().ok())
+                .unwrap_or_default(),
+            compression: attribute(crate::ssl::Attribute::Compression)
+                .map(|x| x != "off")
+                .unwrap_or_default(),
+            alpn,
+        };
+
+        Some(info)
+    }
+
     /**
      * Return an array of SSL attribute names available.
      *
@@ -255,10 +395,13 @@ impl Connection {
      *
      * This function returns a `void*` pointer.
      */
-    pub unsafe fn ssl_struct(&self, struct_name: &str) -> *const std::ffi::c_void {
-        let c_struct_name = crate::ffi::to_cstr(struct_name);
+    pub unsafe fn ssl_struct(
+        &self,
+        struct_name: &str,
+    ) -> crate::errors::Result<*const std::ffi::c_void> {
+        let c_struct_name = crate::ffi::try_to_cstr(struct_name)?;
 
-        pq_sys::PQsslStruct(self.into(), c_struct_name.as_ptr())
+        Ok(pq_sys::PQsslStruct(self.into(), c_struct_name.as_ptr()))
     }
 
     /**
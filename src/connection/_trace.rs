@@ -6,20 +6,48 @@ impl Connection {
      */
     #[cfg(unix)]
     pub fn trace(&self, file: std::fs::File) {
-        use std::os::unix::io::IntoRawFd;
+        log::trace!("Enable trace");
 
+        match crate::stream::Stream::from_file(file) {
+            Ok(stream) => self.set_trace_stream(stream),
+            Err(err) => log::error!("Unable to open trace stream: {}", err),
+        }
+    }
+
+    /**
+     * Enables tracing of the client/server communication to any writer, like a buffer or a
+     * logger.
+     *
+     * The messages are forwarded to `writer` by a dedicated thread, until
+     * `libpq::Connection::untrace` is called or the connection is dropped.
+     *
+     * See [PQtrace](https://www.postgresql.org/docs/current/libpq-control.html#LIBPQ-PQTRACE).
+     */
+    #[cfg(unix)]
+    pub fn trace_to<W>(&self, writer: W) -> std::io::Result<()>
+    where
+        W: std::io::Write + Send + 'static,
+    {
         log::trace!("Enable trace");
 
-        let c_mode = crate::ffi::to_cstr("w");
+        let stream = crate::stream::Stream::from_writer(writer)?;
+        self.set_trace_stream(stream);
+
+        Ok(())
+    }
 
+    #[cfg(unix)]
+    fn set_trace_stream(&self, stream: crate::stream::Stream) {
         unsafe {
-            let stream = libc::fdopen(file.into_raw_fd(), c_mode.as_ptr());
-            pq_sys::PQtrace(self.into(), stream as *mut _);
+            pq_sys::PQtrace(self.into(), stream.as_ptr() as *mut _);
         }
+
+        // The previous stream, if any, is closed once libpq no longer uses it.
+        self.inner().trace = Some(stream);
     }
 
     /**
-     * Disables tracing started by `libpq::Connection::trace`.
+     * Disables tracing started by `libpq::Connection::trace` or `libpq::Connection::trace_to`.
      *
      * See [PQuntrace](https://www.postgresql.org/docs/current/libpq-control.html#LIBPQ-PQUNTRACE).
      */
@@ -30,10 +58,18 @@ impl Connection {
         unsafe {
             pq_sys::PQuntrace(self.into());
         }
+
+        let stream = self.inner().trace.take();
+        drop(stream);
     }
 
     /**
      * Controls the tracing behavior of client/server communication.
+     *
+     * Use `libpq::trace::Flags::SUPPRESS_TIMESTAMPS` and `libpq::trace::Flags::REGRESS_MODE` to
+     * get a deterministic output, suitable to be compared in tests.
+     *
+     * See [PQsetTraceFlags](https://www.postgresql.org/docs/current/libpq-control.html#LIBPQ-PQSETTRACEFLAGS).
      */
     #[cfg(feature = "v14")]
     pub fn trace_set_flags(&self, flags: crate::trace::Flags) {
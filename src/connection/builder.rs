@@ -0,0 +1,699 @@
+/**
+ * Builds a connection from typed [parameters](https://www.postgresql.org/docs/current/libpq-connect.html#LIBPQ-PARAMKEYWORDS).
+ *
+ * ```no_run
+ * let conn = libpq::connection::Builder::new()
+ *     .host("localhost")
+ *     .dbname("postgres")
+ *     .connect()?;
+ * # Ok::<(), libpq::errors::Error>(())
+ * ```
+ */
+#[derive(Clone, Default)]
+pub struct Builder {
+    params: std::collections::BTreeMap<String, String>,
+    cancel_on_drop: bool,
+    connect_deadline: Option<std::time::Duration>,
+}
+
+impl Builder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /**
+     * Creates a builder from a connection string, see [`Info::from`](super::Info::from).
+     */
+    pub fn parse(dsn: &str) -> crate::errors::Result<Self> {
+        let mut builder = Self::new();
+
+        for info in super::Info::from(dsn)? {
+            if let Some(val) = info.val {
+                builder = builder.param(&info.keyword, &val);
+            }
+        }
+
+        Ok(builder)
+    }
+
+    /**
+     * Sets any parameter, by its keyword.
+     */
+    pub fn param(mut self, keyword: &str, value: &str) -> Self {
+        self.params.insert(keyword.to_string(), value.to_string());
+        self
+    }
+
+    /**
+     * Name of host to connect to, or a comma separated list of hosts tried in turn.
+     *
+     * Names are resolved on each connection attempt, including by
+     * [`Connection::reset`](super::Connection::reset), and every address they resolve to is
+     * tried until one accepts the connection.
+     */
+    pub fn host(self, host: &str) -> Self {
+        self.param("host", host)
+    }
+
+    /** Numeric IP address of host to connect to, skipping the name resolution. */
+    pub fn hostaddr(self, addr: std::net::IpAddr) -> Self {
+        self.param("hostaddr", &addr.to_string())
+    }
+
+    /**
+     * Sets the `host` and `hostaddr` lists from pairs, tried in turn. A host with an address
+     * connects to it without resolving the name, which is still used to verify the server
+     * certificate and for authentication.
+     *
+     * ```no_run
+     * let conn = libpq::connection::Builder::new()
+     *     .hosts([
+     *         ("primary.example.com", Some("10.0.0.1".parse()?)),
+     *         ("standby.example.com", None),
+     *     ])
+     *     .connect()?;
+     * # Ok::<(), Box<dyn std::error::Error>>(())
+     * ```
+     */
+    pub fn hosts<'a, I>(self, hosts: I) -> Self
+    where
+        I: IntoIterator<Item = (&'a str, Option<std::net::IpAddr>)>,
+    {
+        let (hosts, addrs): (Vec<_>, Vec<_>) = hosts
+            .into_iter()
+            .map(|(host, addr)| {
+                (
+                    host.to_string(),
+                    addr.map(|x| x.to_string()).unwrap_or_default(),
+                )
+            })
+            .unzip();
+
+        self.param("host", &hosts.join(","))
+            .param("hostaddr", &addrs.join(","))
+    }
+
+    /**
+     * Connects through the Unix-domain socket in `dir`, instead of TCP/IP, replacing
+     * [`Builder::host`].
+     */
+    pub fn socket_dir<P: AsRef<std::path::Path>>(self, dir: P) -> Self {
+        self.param("host", &dir.as_ref().to_string_lossy())
+    }
+
+    /**
+     * Connects through the Unix-domain socket in the abstract namespace `name`, a Linux feature,
+     * replacing [`Builder::host`].
+     */
+    pub fn abstract_socket(self, name: &str) -> Self {
+        self.param("host", &format!("@{name}"))
+    }
+
+    /**
+     * Operating-system user name of the server, checked on Unix-domain socket connections, see
+     * also [`Connection::peer_credentials`](super::Connection::peer_credentials).
+     */
+    pub fn requirepeer(self, user: &str) -> Self {
+        self.param("requirepeer", user)
+    }
+
+    /** Port number to connect to at the server host. */
+    pub fn port(self, port: u16) -> Self {
+        self.param("port", &port.to_string())
+    }
+
+    /** The database name. */
+    pub fn dbname(self, dbname: &str) -> Self {
+        self.param("dbname", dbname)
+    }
+
+    /** PostgreSQL user name to connect as. */
+    pub fn user(self, user: &str) -> Self {
+        self.param("user", user)
+    }
+
+    /** Value of the `application_name` parameter, shown in `pg_stat_activity`. */
+    pub fn application_name(self, name: &str) -> Self {
+        self.param("application_name", name)
+    }
+
+    /** Value of the `application_name` parameter, if not set by `PGAPPNAME` or the builder. */
+    pub fn fallback_application_name(self, name: &str) -> Self {
+        self.param("fallback_application_name", name)
+    }
+
+    /** Password to be used if the server demands password authentication. */
+    pub fn password(self, password: &str) -> Self {
+        self.param("password", password)
+    }
+
+    /**
+     * Maximum time to wait while connecting, per host, rounded up to the second, and to at least
+     * 1s since `0` waits indefinitely.
+     */
+    pub fn connect_timeout(self, timeout: std::time::Duration) -> Self {
+        self.param("connect_timeout", &secs(timeout).max(1).to_string())
+    }
+
+    /**
+     * Order in which the hosts, and the addresses each of them resolves to, are tried.
+     * [`LoadBalanceHosts::Random`] spreads the connections over all the A/AAAA records of a name.
+     */
+    #[cfg(feature = "v16")]
+    pub fn load_balance_hosts(self, load_balance: LoadBalanceHosts) -> Self {
+        self.param("load_balance_hosts", load_balance.as_str())
+    }
+
+    /** Whether client-side TCP keepalives are used, enabled by default. */
+    pub fn keepalives(self, keepalives: bool) -> Self {
+        self.param("keepalives", if keepalives { "1" } else { "0" })
+    }
+
+    /**
+     * Inactivity after which a TCP keepalive is sent, rounded up to the second. Uses the system
+     * default when not set.
+     */
+    pub fn keepalives_idle(self, idle: std::time::Duration) -> Self {
+        self.param("keepalives_idle", &secs(idle).to_string())
+    }
+
+    /**
+     * Interval after which an unacknowledged TCP keepalive is retransmitted, rounded up to the
+     * second.
+     */
+    pub fn keepalives_interval(self, interval: std::time::Duration) -> Self {
+        self.param("keepalives_interval", &secs(interval).to_string())
+    }
+
+    /** Number of unacknowledged TCP keepalives after which the connection is considered dead. */
+    pub fn keepalives_count(self, count: u32) -> Self {
+        self.param("keepalives_count", &count.to_string())
+    }
+
+    /**
+     * Time transmitted data may remain unacknowledged before the connection is closed, rounded
+     * up to the millisecond.
+     */
+    #[cfg(feature = "v12")]
+    pub fn tcp_user_timeout(self, timeout: std::time::Duration) -> Self {
+        self.param("tcp_user_timeout", &millis(timeout).to_string())
+    }
+
+    /** Whether or with what priority a secure GSS TCP/IP connection will be negotiated. */
+    #[cfg(feature = "v12")]
+    pub fn gssencmode(self, mode: GssEncMode) -> Self {
+        self.param("gssencmode", mode.as_str())
+    }
+
+    /** Forward (delegate) GSS credentials to the server. */
+    #[cfg(feature = "v16")]
+    pub fn gssdelegation(self, delegation: bool) -> Self {
+        self.param("gssdelegation", if delegation { "1" } else { "0" })
+    }
+
+    /** Authentication methods the client accepts, or refuses, from the server. */
+    #[cfg(feature = "v16")]
+    pub fn require_auth(self, require: RequireAuth) -> Self {
+        self.param("require_auth", &require.to_string())
+    }
+
+    /**
+     * How SSL encryption is negotiated with the server, [`Direct`](crate::ssl::Negotiation::Direct)
+     * saves a round trip but requires `sslmode=require` or stronger.
+     */
+    #[cfg(feature = "v17")]
+    pub fn sslnegotiation(self, negotiation: crate::ssl::Negotiation) -> Self {
+        self.param("sslnegotiation", negotiation.as_str())
+    }
+
+    /** Opens a connection speaking the replication protocol instead of a regular one. */
+    pub fn replication(self, replication: Replication) -> Self {
+        self.param("replication", replication.as_str())
+    }
+
+    /** Kerberos service name to use when authenticating with GSSAPI. */
+    pub fn krbsrvname(self, name: &str) -> Self {
+        self.param("krbsrvname", name)
+    }
+
+    /** GSS library to use for GSSAPI authentication, only used on Windows. */
+    pub fn gsslib(self, lib: GssLib) -> Self {
+        self.param("gsslib", lib.as_str())
+    }
+
+    /** HTTPS URL of the trusted OAuth issuer. */
+    #[cfg(feature = "v18")]
+    pub fn oauth_issuer(self, issuer: &str) -> Self {
+        self.param("oauth_issuer", issuer)
+    }
+
+    /** OAuth 2.0 client identifier, as issued by the authorization server. */
+    #[cfg(feature = "v18")]
+    pub fn oauth_client_id(self, client_id: &str) -> Self {
+        self.param("oauth_client_id", client_id)
+    }
+
+    /** Client password, if any, to use when contacting the OAuth authorization server. */
+    #[cfg(feature = "v18")]
+    pub fn oauth_client_secret(self, client_secret: &str) -> Self {
+        self.param("oauth_client_secret", client_secret)
+    }
+
+    /** Scope of the access request sent to the authorization server. */
+    #[cfg(feature = "v18")]
+    pub fn oauth_scope(self, scope: &str) -> Self {
+        self.param("oauth_scope", scope)
+    }
+
+    /** See [`Connection::set_cancel_on_drop`](super::Connection::set_cancel_on_drop). */
+    pub fn cancel_on_drop(mut self, cancel: bool) -> Self {
+        self.cancel_on_drop = cancel;
+        self
+    }
+
+    /**
+     * Limits the total time spent by [`Builder::connect`], across all the hosts tried, on top of
+     * the per host [`Builder::connect_timeout`].
+     */
+    pub fn connect_deadline(mut self, deadline: std::time::Duration) -> Self {
+        self.connect_deadline = Some(deadline);
+        self
+    }
+
+    /**
+     * Makes a new connection to the database server.
+     *
+     * See [PQconnectdbParams](https://www.postgresql.org/docs/current/libpq-connect.html#LIBPQ-PQCONNECTDBPARAMS).
+     */
+    pub fn connect(&self) -> crate::errors::Result<super::Connection> {
+        match self.connect_deadline {
+            Some(deadline) => self.connect_before(std::time::Instant::now() + deadline),
+            None => self.wrap(self.connectdb()?),
+        }
+    }
+
+    /**
+     * Makes a new connection to the database server, like [`Builder::connect`], but if the
+     * server demands a password and none was given, calls `prompt` to obtain one and retries.
+     *
+     * This mimics the behavior of `psql`, `prompt` can ask on a TTY or fetch the password from a
+     * vault. Returning `None` gives up and reports the original connection error.
+     *
+     * See [PQconnectionNeedsPassword](https://www.postgresql.org/docs/current/libpq-status.html#LIBPQ-PQCONNECTIONNEEDSPASSWORD).
+     */
+    pub fn connect_with_prompt<F>(&self, prompt: F) -> crate::errors::Result<super::Connection>
+    where
+        F: FnOnce() -> Option<String>,
+    {
+        let conn = self.connectdb()?;
+
+        let needs_password = unsafe {
+            matches!(
+                pq_sys::PQstatus(conn),
+                pq_sys::ConnStatusType::CONNECTION_BAD
+            ) && pq_sys::PQconnectionNeedsPassword(conn) == 1
+        };
+
+        if needs_password && !self.params.contains_key("password") {
+            if let Some(password) = prompt() {
+                log::trace!("Retrying connection with prompted password");
+
+                unsafe { pq_sys::PQfinish(conn) };
+
+                return self.clone().password(&password).connect();
+            }
+        }
+
+        self.wrap(conn)
+    }
+
+    /**
+     * Makes a connection to the database server in a nonblocking manner.
+     *
+     * See [PQconnectStartParams](https://www.postgresql.org/docs/current/libpq-connect.html#LIBPQ-PQCONNECTSTARTPARAMS).
+     */
+    pub fn start(&self) -> crate::errors::Result<super::Connection> {
+        log::trace!("Starting connection with params {:?}", self.params.keys());
+        self.check_hosts()?;
+
+        let (_c_keywords, ptr_keywords) = crate::ffi::vec_to_nta(&self.keywords())?;
+        let (_c_values, ptr_values) = crate::ffi::vec_to_nta(&self.values())?;
+
+        self.wrap(unsafe {
+            pq_sys::PQconnectStartParams(ptr_keywords.as_ptr(), ptr_values.as_ptr(), 0)
+        })
+    }
+
+    fn connectdb(&self) -> crate::errors::Result<*mut pq_sys::PGconn> {
+        log::trace!("Connecting with params {:?}", self.params.keys());
+        self.check_hosts()?;
+
+        let (_c_keywords, ptr_keywords) = crate::ffi::vec_to_nta(&self.keywords())?;
+        let (_c_values, ptr_values) = crate::ffi::vec_to_nta(&self.values())?;
+
+        let conn =
+            unsafe { pq_sys::PQconnectdbParams(ptr_keywords.as_ptr(), ptr_values.as_ptr(), 0) };
+
+        Ok(conn)
+    }
+
+    /**
+     * Checks the `host` and `hostaddr` lists, when both are set, have the same length.
+     */
+    fn check_hosts(&self) -> crate::errors::Result {
+        let count = |keyword: &str| {
+            self.params
+                .get(keyword)
+                .filter(|x| !x.is_empty())
+                .map(|x| x.split(',').count())
+        };
+
+        match (count("host"), count("hostaddr")) {
+            (Some(hosts), Some(addrs)) if hosts != addrs => Err(crate::errors::Error::Backend(
+                format!("could not match {hosts} host names to {addrs} hostaddr values"),
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    /**
+     * Drives the nonblocking connection until `deadline`.
+     *
+     * See [PQconnectPoll](https://www.postgresql.org/docs/current/libpq-connect.html#LIBPQ-PQCONNECTSTARTPARAMS).
+     */
+    fn connect_before(
+        &self,
+        deadline: std::time::Instant,
+    ) -> crate::errors::Result<super::Connection> {
+        let mut conn = self.start()?;
+        // Before the first poll, libpq waits for the socket to be writable.
+        let mut status = crate::poll::Status::Writing;
+
+        loop {
+            let (read, write) = match status {
+                crate::poll::Status::Ok => return Ok(conn),
+                crate::poll::Status::Failed => return conn.error(),
+                crate::poll::Status::Reading => (true, false),
+                crate::poll::Status::Writing => (false, true),
+                crate::poll::Status::Active => (false, false),
+            };
+
+            if read || write {
+                let remaining = deadline.saturating_duration_since(std::time::Instant::now());
+
+                if remaining.is_zero() || !conn.wait(read, write, Some(remaining))? {
+                    return Err(crate::errors::Error::Backend(
+                        "connection deadline exceeded".to_string(),
+                    ));
+                }
+            }
+
+            status = conn.poll();
+        }
+    }
+
+    fn wrap(&self, conn: *mut pq_sys::PGconn) -> crate::errors::Result<super::Connection> {
+        let conn = super::Connection::try_from(conn)?;
+        conn.set_cancel_on_drop(self.cancel_on_drop);
+
+        Ok(conn)
+    }
+
+    fn keywords(&self) -> Vec<&String> {
+        self.params.keys().collect()
+    }
+
+    fn values(&self) -> Vec<&String> {
+        self.params.values().collect()
+    }
+}
+
+/** `duration` in seconds, rounded up so a non-zero duration never becomes `0`. */
+fn secs(duration: std::time::Duration) -> u64 {
+    duration.as_secs() + u64::from(duration.subsec_nanos() > 0)
+}
+
+/** `duration` in milliseconds, rounded up so a non-zero duration never becomes `0`. */
+#[cfg(feature = "v12")]
+fn millis(duration: std::time::Duration) -> u128 {
+    duration.as_millis() + u128::from(duration.subsec_nanos() % 1_000_000 != 0)
+}
+
+/**
+ * Value of the `gssencmode` parameter.
+ */
+#[cfg(feature = "v12")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GssEncMode {
+    /** Only try a non-GSSAPI-encrypted connection. */
+    Disable,
+    /** Try a GSSAPI-encrypted connection first, then a non-GSSAPI-encrypted one. */
+    Prefer,
+    /** Only try a GSSAPI-encrypted connection. */
+    Require,
+}
+
+#[cfg(feature = "v12")]
+impl GssEncMode {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::Disable => "disable",
+            Self::Prefer => "prefer",
+            Self::Require => "require",
+        }
+    }
+}
+
+#[cfg(feature = "v12")]
+impl std::str::FromStr for GssEncMode {
+    type Err = crate::errors::Error;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "disable" => Ok(Self::Disable),
+            "prefer" => Ok(Self::Prefer),
+            "require" => Ok(Self::Require),
+            _ => Err(crate::errors::Error::Backend(format!(
+                "invalid gssencmode value: \"{s}\""
+            ))),
+        }
+    }
+}
+
+/**
+ * Value of the `load_balance_hosts` parameter.
+ */
+#[cfg(feature = "v16")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LoadBalanceHosts {
+    /** Hosts and addresses are tried in the order they are given or resolved. */
+    Disable,
+    /** Hosts and addresses are tried in a random order. */
+    Random,
+}
+
+#[cfg(feature = "v16")]
+impl LoadBalanceHosts {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::Disable => "disable",
+            Self::Random => "random",
+        }
+    }
+}
+
+#[cfg(feature = "v16")]
+impl std::str::FromStr for LoadBalanceHosts {
+    type Err = crate::errors::Error;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "disable" => Ok(Self::Disable),
+            "random" => Ok(Self::Random),
+            _ => Err(crate::errors::Error::Backend(format!(
+                "invalid load_balance_hosts value: \"{s}\""
+            ))),
+        }
+    }
+}
+
+/**
+ * Value of the `replication` parameter, see the
+ * [streaming replication protocol](https://www.postgresql.org/docs/current/protocol-replication.html).
+ */
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Replication {
+    /** Physical replication, the connection isn't bound to a database. */
+    Physical,
+    /** Logical replication from the database of `dbname`. */
+    Database,
+}
+
+impl Replication {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::Physical => "true",
+            Self::Database => "database",
+        }
+    }
+}
+
+/**
+ * Value of the `gsslib` parameter.
+ */
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GssLib {
+    /** MIT Kerberos or Heimdal GSSAPI library. */
+    Gssapi,
+    /** Windows SSPI, authenticating against Active Directory. */
+    Sspi,
+}
+
+impl GssLib {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::Gssapi => "gssapi",
+            Self::Sspi => "sspi",
+        }
+    }
+}
+
+impl std::str::FromStr for GssLib {
+    type Err = crate::errors::Error;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        // libpq compares the value case insensitively.
+        match s.to_ascii_lowercase().as_str() {
+            "gssapi" => Ok(Self::Gssapi),
+            "sspi" => Ok(Self::Sspi),
+            _ => Err(crate::errors::Error::Backend(format!(
+                "invalid gsslib value: \"{s}\""
+            ))),
+        }
+    }
+}
+
+/**
+ * Authentication method, as named by the `require_auth` parameter.
+ */
+#[cfg(feature = "v16")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AuthMethod {
+    /** The server must request plaintext password authentication. */
+    Password,
+    /** The server must request MD5 hashed password authentication. */
+    Md5,
+    /** The server must request a GSSAPI handshake or establish a GSS-encrypted channel. */
+    Gss,
+    /** The server must request Windows SSPI authentication. */
+    Sspi,
+    /** The server must successfully complete a SCRAM-SHA-256 authentication exchange. */
+    ScramSha256,
+    /** The server must not prompt the client for an authentication exchange. */
+    None,
+}
+
+#[cfg(feature = "v16")]
+impl AuthMethod {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::Password => "password",
+            Self::Md5 => "md5",
+            Self::Gss => "gss",
+            Self::Sspi => "sspi",
+            Self::ScramSha256 => "scram-sha-256",
+            Self::None => "none",
+        }
+    }
+}
+
+#[cfg(feature = "v16")]
+impl std::str::FromStr for AuthMethod {
+    type Err = crate::errors::Error;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let method = match s {
+            "password" => Self::Password,
+            "md5" => Self::Md5,
+            "gss" => Self::Gss,
+            "sspi" => Self::Sspi,
+            "scram-sha-256" => Self::ScramSha256,
+            "none" => Self::None,
+            _ => {
+                return Err(crate::errors::Error::Backend(format!(
+                    "invalid require_auth method: \"{s}\""
+                )))
+            }
+        };
+
+        Ok(method)
+    }
+}
+
+/**
+ * Value of the `require_auth` parameter.
+ *
+ * ```
+ * # #[cfg(feature = "v16")]
+ * # {
+ * use libpq::connection::{AuthMethod, RequireAuth};
+ *
+ * let require: RequireAuth = "!password,!md5".parse()?;
+ * assert_eq!(require, RequireAuth::Deny(vec![AuthMethod::Password, AuthMethod::Md5]));
+ * assert!("scram-sha-256,!md5".parse::<RequireAuth>().is_err());
+ * # }
+ * # Ok::<(), libpq::errors::Error>(())
+ * ```
+ */
+#[cfg(feature = "v16")]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RequireAuth {
+    /** The server must use one of these methods. */
+    Allow(Vec<AuthMethod>),
+    /** The server must not use any of these methods. */
+    Deny(Vec<AuthMethod>),
+}
+
+#[cfg(feature = "v16")]
+impl std::fmt::Display for RequireAuth {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let (prefix, methods) = match self {
+            Self::Allow(methods) => ("", methods),
+            Self::Deny(methods) => ("!", methods),
+        };
+
+        let methods = methods
+            .iter()
+            .map(|x| format!("{prefix}{}", x.as_str()))
+            .collect::<Vec<_>>();
+
+        f.write_str(&methods.join(","))
+    }
+}
+
+#[cfg(feature = "v16")]
+impl std::str::FromStr for RequireAuth {
+    type Err = crate::errors::Error;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let mut allow = Vec::new();
+        let mut deny = Vec::new();
+
+        for method in s.split(',') {
+            match method.strip_prefix('!') {
+                Some(method) => deny.push(method.parse()?),
+                None => allow.push(method.parse()?),
+            }
+        }
+
+        if !allow.is_empty() && !deny.is_empty() {
+            Err(crate::errors::Error::Backend(format!(
+                "negative require_auth method cannot be mixed with non-negative methods: \"{s}\""
+            )))
+        } else if deny.is_empty() {
+            Ok(Self::Allow(allow))
+        } else {
+            Ok(Self::Deny(deny))
+        }
+    }
+}
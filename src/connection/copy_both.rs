@@ -0,0 +1,145 @@
+/**
+ * Connection in `COPY BOTH` mode, see [`Connection::copy_both`](super::Connection::copy_both).
+ *
+ * Data flows in both directions until one side ends the copy: [`CopyBoth::split`] gives a
+ * reading and a writing half, so both can be borrowed independently.
+ */
+#[derive(Debug)]
+pub struct CopyBoth<'conn> {
+    reader: CopyReader<'conn>,
+    writer: CopyWriter<'conn>,
+}
+
+impl<'conn> CopyBoth<'conn> {
+    /**
+     * Splits the handle into its reading and writing halves.
+     */
+    pub fn split(self) -> (CopyReader<'conn>, CopyWriter<'conn>) {
+        (self.reader, self.writer)
+    }
+
+    /** See [`CopyReader::read`]. */
+    pub fn read(&mut self) -> crate::errors::Result<Option<super::PqBytes>> {
+        self.reader.read()
+    }
+
+    /** See [`CopyWriter::write`]. */
+    pub fn write(&mut self, data: &[u8]) -> crate::errors::Result {
+        self.writer.write(data)
+    }
+}
+
+/**
+ * Reading half of a [`CopyBoth`].
+ */
+#[derive(Debug)]
+pub struct CopyReader<'conn> {
+    conn: &'conn super::Connection,
+}
+
+impl<'conn> CopyReader<'conn> {
+    pub(crate) fn new(conn: &'conn super::Connection) -> Self {
+        Self { conn }
+    }
+
+    /**
+     * Waits for the next message, `None` once the server ended the copy.
+     */
+    pub fn read(&mut self) -> crate::errors::Result<Option<super::PqBytes>> {
+        match self.conn.copy_data(false) {
+            Ok(data) => Ok(Some(data)),
+            Err(err) => {
+                let mut ended = false;
+
+                while let Some(result) = self.conn.result() {
+                    ended |= matches!(
+                        result.status(),
+                        crate::Status::CommandOk | crate::Status::TupplesOk
+                    );
+                }
+
+                if ended {
+                    Ok(None)
+                } else {
+                    Err(err)
+                }
+            }
+        }
+    }
+}
+
+/**
+ * Writing half of a [`CopyBoth`].
+ */
+#[derive(Debug)]
+pub struct CopyWriter<'conn> {
+    conn: &'conn super::Connection,
+}
+
+impl<'conn> CopyWriter<'conn> {
+    pub(crate) fn new(conn: &'conn super::Connection) -> Self {
+        Self { conn }
+    }
+
+    /**
+     * Sends a message and flushes it to the server.
+     */
+    pub fn write(&mut self, data: &[u8]) -> crate::errors::Result {
+        self.conn.put_copy_data(data)?;
+        self.conn.flush()
+    }
+
+    /**
+     * Ends the copy on the client side, the server may still send messages until it ends its
+     * side.
+     */
+    pub fn finish(self) -> crate::errors::Result {
+        self.conn.put_copy_end(None)?;
+        self.conn.flush()
+    }
+}
+
+impl super::Connection {
+    /**
+     * Runs `command`, which must switch the connection to `COPY BOTH` mode, like
+     * `START_REPLICATION`.
+     */
+    pub fn copy_both(&self, command: &str) -> crate::errors::Result<CopyBoth<'_>> {
+        self.exec_checked(command, &[crate::Status::CopyBoth])?;
+
+        Ok(CopyBoth {
+            reader: CopyReader::new(self),
+            writer: CopyWriter::new(self),
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #[test]
+    fn copy_both() {
+        let conn = crate::connection::Builder::parse(&crate::test::dsn())
+            .unwrap()
+            .replication(crate::connection::Replication::Physical)
+            .connect()
+            .unwrap();
+        let system = conn.identify_system().unwrap();
+
+        let (mut reader, mut writer) = conn
+            .copy_both(&format!("START_REPLICATION PHYSICAL {}", system.xlogpos))
+            .unwrap()
+            .split();
+
+        // Standby status update requesting a reply.
+        let mut message = vec![b'r'];
+        message.extend_from_slice(&[0; 32]);
+        message.push(1);
+        writer.write(&message).unwrap();
+
+        let data = reader.read().unwrap().unwrap();
+        assert!(matches!(data.first(), Some(b'k' | b'w')));
+
+        writer.finish().unwrap();
+        while reader.read().unwrap().is_some() {}
+    }
+}
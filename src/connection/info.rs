@@ -26,7 +26,7 @@ impl Info {
      * [PQconninfoParse](https://www.postgresql.org/docs/current/libpq-connect.html#LIBPQ-PQCONNINFOPARSE).
      */
     pub fn from(dsn: &str) -> crate::errors::Result<Vec<Self>> {
-        let c_dsn = crate::ffi::to_cstr(dsn);
+        let c_dsn = crate::ffi::try_to_cstr(dsn)?;
 
         unsafe {
             let mut errmsg: *mut libc::c_char = std::ptr::null_mut();
@@ -34,7 +34,7 @@ impl Info {
 
             if raw.is_null() {
                 if errmsg.is_null() {
-                    return Err(crate::errors::Error::Unknow);
+                    return Err(crate::errors::Error::CallFailed("PQconninfoParse"));
                 } else {
                     let err = crate::ffi::to_string(errmsg)?;
                     pq_sys::PQfreemem(errmsg as *mut std::ffi::c_void);
@@ -49,6 +49,13 @@ impl Info {
         }
     }
 
+    /**
+     * Whether the value is a secret, like a password, that should be hidden.
+     */
+    pub fn is_secret(&self) -> bool {
+        self.dispchar == "*"
+    }
+
     fn from_raw(raw: *mut pq_sys::_PQconninfoOption) -> crate::errors::Result<Self> {
         let info = unsafe {
             Self {
@@ -120,6 +127,57 @@ impl TryFrom<*mut pq_sys::_PQconninfoOption> for Info {
     }
 }
 
+/**
+ * Snapshot of the state of a connection, see
+ * [`Connection::connection_info`](super::Connection::connection_info).
+ */
+#[derive(Clone, Debug, PartialEq)]
+pub struct ConnectionInfo {
+    pub db: String,
+    pub user: String,
+    pub host: String,
+    #[cfg(feature = "v12")]
+    pub hostaddr: String,
+    pub port: String,
+    pub options: Option<String>,
+    pub status: super::Status,
+    pub transaction_status: crate::transaction::Status,
+    pub protocol_version: super::ProtocolVersion,
+    pub server_version: super::ServerVersion,
+    pub backend_pid: u32,
+    pub ssl_in_use: bool,
+    pub needs_password: bool,
+    pub used_password: bool,
+}
+
+impl super::Connection {
+    /**
+     * Collects the status functions of the connection in one value, for logging or debugging.
+     *
+     * The password is never included, see [`Connection::info`](super::Connection::info) for the
+     * connection options.
+     */
+    pub fn connection_info(&self) -> crate::errors::Result<ConnectionInfo> {
+        Ok(ConnectionInfo {
+            db: self.db()?,
+            user: self.user()?,
+            host: self.host()?,
+            #[cfg(feature = "v12")]
+            hostaddr: self.hostaddr()?,
+            port: self.port()?,
+            options: self.options()?,
+            status: self.status(),
+            transaction_status: self.transaction_status(),
+            protocol_version: self.protocol_version(),
+            server_version: self.server_version().into(),
+            backend_pid: self.backend_pid(),
+            ssl_in_use: self.ssl_in_use(),
+            needs_password: self.needs_password(),
+            used_password: self.used_password(),
+        })
+    }
+}
+
 #[cfg(test)]
 mod test {
     #[test]
@@ -133,6 +191,18 @@ mod test {
         );
     }
 
+    #[test]
+    fn connection_info() {
+        let conn = crate::test::new_conn();
+        let info = conn.connection_info().unwrap();
+
+        assert_eq!(info.db, conn.db().unwrap());
+        assert_eq!(info.status, crate::connection::Status::Ok);
+        assert_eq!(info.transaction_status, crate::transaction::Status::Idle);
+        assert_eq!(info.backend_pid, conn.backend_pid());
+        assert!(info.server_version.major >= 10);
+    }
+
     #[test]
     fn defaults() {
         let _ = crate::connection::Info::default();
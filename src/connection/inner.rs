@@ -0,0 +1,40 @@
+pub(crate) type NoticeProcessor = Box<dyn FnMut(&str) + Send>;
+pub(crate) type NoticeReceiver = Box<dyn FnMut(&crate::Result) + Send>;
+pub(crate) type SqlCommenter = dyn Fn() -> Vec<(String, String)> + Send + Sync;
+pub(crate) type QueryStart = dyn Fn(&str, Option<&str>) + Send + Sync;
+pub(crate) type QueryEnd = dyn Fn(std::time::Duration, crate::Status) + Send + Sync;
+pub(crate) type ParameterChange = dyn Fn(&str, &str) + Send + Sync;
+pub(crate) type SlowQuery =
+    dyn Fn(Option<&str>, Option<&str>, std::time::Duration, Option<u64>) + Send + Sync;
+pub(crate) type QueryLogger = dyn Fn(&super::QueryLog) + Send + Sync;
+pub(crate) type ParamRedactor = dyn Fn(usize, &[u8]) -> super::LoggedParam + Send + Sync;
+pub(crate) type Notices = std::sync::Arc<std::sync::Mutex<Vec<crate::notice::Notice>>>;
+
+/**
+ * Rust-side state attached to a connection.
+ */
+#[derive(Default)]
+pub(crate) struct Inner {
+    pub error_context: Option<crate::errors::QueryText>,
+    pub strict_utf8: bool,
+    pub cancel_on_drop: bool,
+    #[cfg(feature = "tracing")]
+    pub span_statement: crate::errors::QueryText,
+    pub notice_processor: Option<Box<NoticeProcessor>>,
+    pub notice_receiver: Option<Box<NoticeReceiver>>,
+    pub notices: Option<Notices>,
+    pub sql_commenter: Option<std::sync::Arc<SqlCommenter>>,
+    pub query_start: Option<std::sync::Arc<QueryStart>>,
+    pub query_end: Option<std::sync::Arc<QueryEnd>>,
+    pub slow_query: Option<(std::time::Duration, std::sync::Arc<SlowQuery>)>,
+    pub query_logger: Option<(std::sync::Arc<QueryLogger>, std::sync::Arc<ParamRedactor>)>,
+    pub parameter_change: Option<std::sync::Arc<ParameterChange>>,
+    pub parameters: std::collections::BTreeMap<&'static str, String>,
+    pub cstrings: std::collections::HashMap<Box<str>, std::sync::Arc<std::ffi::CStr>>,
+    #[cfg(feature = "with-postgres-types")]
+    pub sql_types: std::collections::HashMap<crate::Oid, postgres_types::Type>,
+    #[cfg(feature = "v12")]
+    pub result_memory: Option<std::sync::Arc<std::sync::atomic::AtomicU64>>,
+    #[cfg(unix)]
+    pub trace: Option<crate::stream::Stream>,
+}
@@ -0,0 +1,256 @@
+/**
+ * Maximum number of parameters of a query, limited by the protocol.
+ */
+const MAX_PARAMS: usize = 65_535;
+
+/**
+ * Number of rows from which [`Connection::insert_many`](super::Connection::insert_many) switches
+ * to `COPY`.
+ */
+pub const COPY_THRESHOLD: usize = 1_000;
+
+/**
+ * Size from which the rows buffered by `insert_copy` are sent.
+ */
+const COPY_BUFFER_SIZE: usize = 64 * 1024;
+
+impl super::Connection {
+    /**
+     * Inserts `rows`, with values in text format, into `columns` of `table` and returns the
+     * number of inserted rows.
+     *
+     * `table` is looked up in `schema`, or along the `search_path` when `None`: both are quoted,
+     * a dot is part of the name.
+     *
+     * Small batches are sent as multi-row `INSERT … VALUES` queries, split to respect the
+     * parameters limit, and batches of [`COPY_THRESHOLD`] rows or more with `COPY … FROM STDIN`.
+     * The `COPY` uses the text format, like the values: the binary format would require the
+     * binary encoding of each column type.
+     * The caller is responsible for the atomicity: wrap the call in a transaction if a failure
+     * must not leave a part of the rows inserted.
+     *
+     * ```no_run
+     * # let conn = libpq::Connection::new("")?;
+     * let rows = vec![libpq::params![1, "foo"], libpq::params![2, None::<&str>]];
+     * let inserted = conn.insert_many(None, "users", &["id", "name"], &rows)?;
+     * # Ok::<(), libpq::errors::Error>(())
+     * ```
+     */
+    pub fn insert_many(
+        &self,
+        schema: Option<&str>,
+        table: &str,
+        columns: &[&str],
+        rows: &[Vec<Option<Vec<u8>>>],
+    ) -> crate::errors::Result<usize> {
+        if columns.is_empty() {
+            return Err(crate::errors::Error::Backend(
+                "insert_many requires at least one column".to_string(),
+            ));
+        }
+
+        if let Some(row) = rows.iter().find(|x| x.len() != columns.len()) {
+            return Err(crate::errors::Error::Backend(format!(
+                "row has {} values, expected {}",
+                row.len(),
+                columns.len()
+            )));
+        }
+
+        if rows.is_empty() {
+            return Ok(0);
+        }
+
+        let mut table = self.escape_identifier(table)?.to_str()?.to_string();
+        if let Some(schema) = schema {
+            table = format!("{}.{table}", self.escape_identifier(schema)?.to_str()?);
+        }
+        let columns = columns
+            .iter()
+            .map(|x| Ok(self.escape_identifier(x)?.to_str()?.to_string()))
+            .collect::<crate::errors::Result<Vec<_>>>()?
+            .join(", ");
+
+        if rows.len() >= COPY_THRESHOLD {
+            self.insert_copy(&table, &columns, rows)
+        } else {
+            self.insert_values(&table, &columns, rows)
+        }
+    }
+
+    fn insert_values(
+        &self,
+        table: &str,
+        columns: &str,
+        rows: &[Vec<Option<Vec<u8>>>],
+    ) -> crate::errors::Result<usize> {
+        let ncolumns = rows[0].len();
+        let chunk_size = (MAX_PARAMS / ncolumns).max(1);
+        let mut inserted = 0;
+
+        for chunk in rows.chunks(chunk_size) {
+            let values = (0..chunk.len())
+                .map(|row| {
+                    let placeholders = (1..=ncolumns)
+                        .map(|column| format!("${}", row * ncolumns + column))
+                        .collect::<Vec<_>>();
+
+                    format!("({})", placeholders.join(", "))
+                })
+                .collect::<Vec<_>>();
+
+            let query = format!(
+                "INSERT INTO {table} ({columns}) VALUES {}",
+                values.join(", ")
+            );
+            let params = chunk.concat();
+
+            let result = self.exec_params(&query, &[], &params, &[], crate::Format::Text);
+            inserted += self.rows_inserted(result, &query)?;
+        }
+
+        Ok(inserted)
+    }
+
+    fn insert_copy(
+        &self,
+        table: &str,
+        columns: &str,
+        rows: &[Vec<Option<Vec<u8>>>],
+    ) -> crate::errors::Result<usize> {
+        let query = format!("COPY {table} ({columns}) FROM STDIN");
+
+        self.exec_checked(&query, &[crate::Status::CopyIn])?;
+
+        let mut buffer = Vec::with_capacity(COPY_BUFFER_SIZE);
+
+        for (x, row) in rows.iter().enumerate() {
+            copy_row(&mut buffer, row);
+
+            if buffer.len() < COPY_BUFFER_SIZE && x + 1 < rows.len() {
+                continue;
+            }
+
+            if let Err(err) = self.put_copy_data(&buffer) {
+                self.put_copy_end(Some("insert_many failed")).ok();
+                while self.result().is_some() {}
+
+                return Err(err);
+            }
+
+            buffer.clear();
+        }
+
+        self.put_copy_end(None)?;
+
+        let mut inserted = 0;
+
+        while let Some(result) = self.result() {
+            inserted += self.rows_inserted(result, &query)?;
+        }
+
+        Ok(inserted)
+    }
+
+    /**
+     * Returns the number of rows inserted by the `INSERT` or `COPY` of `result`.
+     */
+    fn rows_inserted(&self, result: crate::Result, query: &str) -> crate::errors::Result<usize> {
+        let result = self.check_result(result, &[crate::Status::CommandOk], query)?;
+
+        match result.rows_affected() {
+            Some(rows) => Ok(rows as usize),
+            None => {
+                let message = format!(
+                    "no row count reported by \"{}\"",
+                    result.cmd_status()?.unwrap_or_default()
+                );
+
+                Err(self.with_context(crate::errors::Error::Backend(message), Some(query)))
+            }
+        }
+    }
+}
+
+/**
+ * Encodes a row in the text format of `COPY`.
+ */
+fn copy_row(buffer: &mut Vec<u8>, row: &[Option<Vec<u8>>]) {
+    for (x, value) in row.iter().enumerate() {
+        if x > 0 {
+            buffer.push(b'\t');
+        }
+
+        match value {
+            Some(value) => {
+                // Text parameters are nul terminated, see `params!`.
+                let value = value.strip_suffix(b"\0").unwrap_or(value);
+
+                for c in value {
+                    match c {
+                        b'\\' => buffer.extend_from_slice(b"\\\\"),
+                        b'\t' => buffer.extend_from_slice(b"\\t"),
+                        b'\n' => buffer.extend_from_slice(b"\\n"),
+                        b'\r' => buffer.extend_from_slice(b"\\r"),
+                        c => buffer.push(*c),
+                    }
+                }
+            }
+            None => buffer.extend_from_slice(b"\\N"),
+        }
+    }
+
+    buffer.push(b'\n');
+}
+
+#[cfg(test)]
+mod test {
+    #[test]
+    fn copy_row() {
+        let mut buffer = Vec::new();
+        super::copy_row(
+            &mut buffer,
+            &[Some(b"a\tb\\c\n\0".to_vec()), None, Some(Vec::new())],
+        );
+
+        assert_eq!(buffer, b"a\\tb\\\\c\\n\t\\N\t\n");
+    }
+
+    #[test]
+    fn insert_many() {
+        let conn = crate::test::new_conn();
+        conn.exec("CREATE TEMPORARY TABLE insert_many (id int4, name text)");
+
+        let rows = (0..10)
+            .map(|x| crate::params![x, format!("name\t{x}")])
+            .collect::<Vec<_>>();
+        assert_eq!(
+            conn.insert_many(None, "insert_many", &["id", "name"], &rows),
+            Ok(10)
+        );
+
+        let rows = (0..super::COPY_THRESHOLD)
+            .map(|x| crate::params![x as i32, None::<&str>])
+            .collect::<Vec<_>>();
+        assert_eq!(
+            conn.insert_many(None, "insert_many", &["id", "name"], &rows),
+            Ok(super::COPY_THRESHOLD)
+        );
+
+        let result = conn.exec("SELECT count(*), count(name) FROM insert_many");
+        assert_eq!(result.value(0, 0), Some(&b"1010"[..]));
+        assert_eq!(result.value(0, 1), Some(&b"10"[..]));
+
+        assert!(conn
+            .insert_many(None, "insert_many", &["id"], &[crate::params![1, 2]])
+            .is_err());
+
+        conn.exec("CREATE SCHEMA IF NOT EXISTS \"insert.many\"");
+        conn.exec("CREATE TABLE IF NOT EXISTS \"insert.many\".items (id int4)");
+        assert_eq!(
+            conn.insert_many(Some("insert.many"), "items", &["id"], &[crate::params![1]]),
+            Ok(1)
+        );
+        conn.exec("DROP SCHEMA \"insert.many\" CASCADE");
+    }
+}
@@ -0,0 +1,241 @@
+/**
+ * Values returned by the instrumented functions, see `Connection::instrument`.
+ */
+#[cfg_attr(not(any(feature = "metrics", feature = "tracing")), allow(dead_code))]
+pub(crate) trait Instrument {
+    /** Whether the value is the result of a query, as opposed to a `COPY` transfer. */
+    const QUERY: bool = false;
+
+    /** Status of the query result. */
+    fn status(&self) -> Option<crate::Status> {
+        None
+    }
+
+    /** Number of rows returned or affected. */
+    fn rows(&self) -> Option<u64> {
+        None
+    }
+
+    /** Number of bytes transferred. */
+    fn bytes(&self) -> Option<usize> {
+        None
+    }
+
+    /** `Some` with the SQLSTATE, if known, when the operation failed. */
+    fn error(&self) -> Option<Option<String>> {
+        None
+    }
+
+    /** Attaches the notices captured during the operation. */
+    fn set_notices(&mut self, _notices: Vec<crate::notice::Notice>) {}
+}
+
+impl Instrument for crate::Result {
+    const QUERY: bool = true;
+
+    fn status(&self) -> Option<crate::Status> {
+        Some(self.status())
+    }
+
+    fn rows(&self) -> Option<u64> {
+        if self.status() == crate::Status::TupplesOk {
+            Some(self.ntuples() as u64)
+        } else {
+            self.rows_affected()
+        }
+    }
+
+    fn error(&self) -> Option<Option<String>> {
+        match self.status() {
+            crate::Status::BadResponse | crate::Status::FatalError => Some(
+                self.error_field(crate::result::ErrorField::Sqlstate)
+                    .ok()
+                    .flatten()
+                    .map(String::from),
+            ),
+            _ => None,
+        }
+    }
+
+    fn set_notices(&mut self, notices: Vec<crate::notice::Notice>) {
+        self.notices = notices;
+    }
+}
+
+impl Instrument for crate::errors::Result {
+    fn error(&self) -> Option<Option<String>> {
+        self.as_ref().err().map(|_| None)
+    }
+}
+
+impl Instrument for crate::errors::Result<usize> {
+    fn bytes(&self) -> Option<usize> {
+        self.as_ref().ok().copied()
+    }
+
+    fn error(&self) -> Option<Option<String>> {
+        self.as_ref().err().map(|_| None)
+    }
+}
+
+impl Instrument for crate::errors::Result<super::PqBytes> {
+    fn bytes(&self) -> Option<usize> {
+        self.as_ref().ok().map(|x| x.len())
+    }
+
+    fn error(&self) -> Option<Option<String>> {
+        self.as_ref().err().map(|_| None)
+    }
+}
+
+impl super::Connection {
+    /**
+     * Runs `f`, the call to libpq for `operation`, inside the instrumentation enabled by the
+     * crate features.
+     */
+    pub(crate) fn instrument<T, F>(
+        &self,
+        operation: &'static str,
+        statement: Option<&str>,
+        params: &[Option<Vec<u8>>],
+        f: F,
+    ) -> T
+    where
+        T: Instrument,
+        F: FnOnce() -> T,
+    {
+        self.instrument_with(operation, statement, None, params, f)
+    }
+
+    /**
+     * Like [`Connection::instrument`], for the execution of the prepared statement `prepared`.
+     */
+    pub(crate) fn instrument_prepared<T, F>(
+        &self,
+        operation: &'static str,
+        prepared: &str,
+        params: &[Option<Vec<u8>>],
+        f: F,
+    ) -> T
+    where
+        T: Instrument,
+        F: FnOnce() -> T,
+    {
+        self.instrument_with(operation, None, Some(prepared), params, f)
+    }
+
+    fn instrument_with<T, F>(
+        &self,
+        operation: &'static str,
+        statement: Option<&str>,
+        prepared: Option<&str>,
+        params: &[Option<Vec<u8>>],
+        f: F,
+    ) -> T
+    where
+        T: Instrument,
+        F: FnOnce() -> T,
+    {
+        #[cfg(feature = "tracing")]
+        let span = {
+            let statement = statement.and_then(|x| self.inner().span_statement.apply(x));
+            tracing::info_span!(
+                "libpq.query",
+                otel.kind = "client",
+                otel.name = operation,
+                db.system = "postgresql",
+                db.name = self.db_ref().ok(),
+                db.operation = operation,
+                db.statement = statement.as_deref(),
+                db.rows = tracing::field::Empty,
+                db.bytes = tracing::field::Empty,
+                duration = tracing::field::Empty,
+            )
+        };
+        #[cfg(feature = "tracing")]
+        let f = || span.in_scope(f);
+
+        // Don't call the user closures with the lock held.
+        let (query_start, query_end, slow_query, query_logger, notices) = {
+            let inner = self.inner();
+            (
+                inner.query_start.clone(),
+                inner.query_end.clone(),
+                inner.slow_query.clone().filter(|_| T::QUERY),
+                inner.query_logger.clone().filter(|_| T::QUERY),
+                inner.notices.clone().filter(|_| T::QUERY),
+            )
+        };
+
+        if let Some(notices) = &notices {
+            // Drops the notices raised since the previous query.
+            notices.lock().unwrap_or_else(|e| e.into_inner()).clear();
+        }
+
+        if let Some(query_start) = query_start.filter(|_| T::QUERY) {
+            query_start(operation, statement);
+        }
+
+        let start = std::time::Instant::now();
+        let mut value = f();
+        let elapsed = start.elapsed();
+
+        if let Some(notices) = notices {
+            value.set_notices(std::mem::take(
+                &mut *notices.lock().unwrap_or_else(|e| e.into_inner()),
+            ));
+        }
+
+        if let (Some(query_end), Some(status)) = (query_end, value.status()) {
+            query_end(elapsed, status);
+        }
+
+        if let Some((threshold, slow_query)) = slow_query {
+            if elapsed > threshold {
+                slow_query(statement, prepared, elapsed, value.rows());
+            }
+        }
+
+        if let Some((logger, redact)) = query_logger {
+            logger(&super::QueryLog {
+                operation,
+                statement,
+                prepared,
+                params: super::logger::redact_params(params, &*redact),
+                duration: elapsed,
+                status: value.status(),
+                outcome: match value.error() {
+                    Some(sqlstate) => super::Outcome::Failure { sqlstate },
+                    None => super::Outcome::Success { rows: value.rows() },
+                },
+            });
+        }
+
+        if T::QUERY {
+            self.check_parameters();
+        }
+
+        #[cfg(feature = "tracing")]
+        {
+            span.record("duration", elapsed.as_secs_f64());
+            if let Some(rows) = value.rows() {
+                span.record("db.rows", rows);
+            }
+            if let Some(bytes) = value.bytes() {
+                span.record("db.bytes", bytes as u64);
+            }
+        }
+
+        #[cfg(feature = "metrics")]
+        {
+            if T::QUERY {
+                crate::metrics::query(operation, elapsed, value.error());
+            }
+            if let Some(bytes) = value.bytes() {
+                crate::metrics::copy(operation, bytes);
+            }
+        }
+
+        value
+    }
+}
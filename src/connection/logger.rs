@@ -0,0 +1,250 @@
+/**
+ * Maximum number of characters of a parameter kept by [`LoggedParam::summary`].
+ */
+const SUMMARY_LENGTH: usize = 64;
+
+/**
+ * Parameter of a [`QueryLog`], as returned by the redaction closure of
+ * [`Connection::set_query_logger`](super::Connection::set_query_logger).
+ */
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum LoggedParam {
+    /** SQL `NULL`. */
+    Null,
+    /** Value, or summary of the value, to log. */
+    Value(String),
+    /** The value is hidden. */
+    Redacted,
+}
+
+impl LoggedParam {
+    /**
+     * Summarizes `value`: text truncated to 64 characters, or its size if it isn't valid UTF-8.
+     */
+    pub fn summary(value: &[u8]) -> Self {
+        // Text parameters are nul terminated, see `params!`.
+        let value = value.strip_suffix(b"\0").unwrap_or(value);
+
+        match std::str::from_utf8(value) {
+            Ok(text) if text.chars().count() > SUMMARY_LENGTH => Self::Value(format!(
+                "{}…",
+                text.chars().take(SUMMARY_LENGTH).collect::<String>()
+            )),
+            Ok(text) => Self::Value(text.to_string()),
+            Err(_) => Self::Value(format!("<{} bytes>", value.len())),
+        }
+    }
+}
+
+/**
+ * Outcome of a [`QueryLog`].
+ */
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Outcome {
+    /** The query succeeded, with the number of rows returned or affected, if any. */
+    Success { rows: Option<u64> },
+    /** The query failed, with its SQLSTATE, if known. */
+    Failure { sqlstate: Option<String> },
+}
+
+/**
+ * Query passed to the logger set by
+ * [`Connection::set_query_logger`](super::Connection::set_query_logger).
+ */
+#[derive(Clone, Debug)]
+pub struct QueryLog<'a> {
+    /** Operation, like `exec` or `exec_prepared`. */
+    pub operation: &'a str,
+    /** Statement, if any. */
+    pub statement: Option<&'a str>,
+    /** Name of the prepared statement executed, if any, empty for the unnamed one. */
+    pub prepared: Option<&'a str>,
+    /** Parameters, after redaction. */
+    pub params: Vec<LoggedParam>,
+    /** Time spent waiting for the result. */
+    pub duration: std::time::Duration,
+    /** Status of the result. */
+    pub status: Option<crate::Status>,
+    /** See [`Outcome`]. */
+    pub outcome: Outcome,
+}
+
+impl super::Connection {
+    /**
+     * Sets a closure called after each query with its statement, its parameters, its duration
+     * and its outcome, for logging or auditing.
+     *
+     * Each parameter is passed to `redact`, with its position from 1 and its value, to choose what
+     * is logged: return [`LoggedParam::Redacted`] for secrets or [`LoggedParam::summary`] to log
+     * the value.
+     *
+     * ```no_run
+     * # let conn = libpq::Connection::new("")?;
+     * use libpq::connection::LoggedParam;
+     *
+     * conn.set_query_logger(
+     *     |query| eprintln!("{:?} {:?} {:?}", query.statement, query.params, query.duration),
+     *     |position, value| match position {
+     *         2 => LoggedParam::Redacted,
+     *         _ => LoggedParam::summary(value),
+     *     },
+     * );
+     *
+     * conn.exec_params(
+     *     "UPDATE users SET password = $2 WHERE id = $1",
+     *     &[],
+     *     &libpq::params![1, "secret"],
+     *     &[],
+     *     libpq::Format::Text,
+     * );
+     * # Ok::<(), libpq::errors::Error>(())
+     * ```
+     */
+    pub fn set_query_logger<L, R>(&self, logger: L, redact: R)
+    where
+        L: Fn(&QueryLog) + Send + Sync + 'static,
+        R: Fn(usize, &[u8]) -> LoggedParam + Send + Sync + 'static,
+    {
+        self.inner().query_logger =
+            Some((std::sync::Arc::new(logger), std::sync::Arc::new(redact)));
+    }
+
+    /**
+     * Removes the closures set by
+     * [`Connection::set_query_logger`](super::Connection::set_query_logger).
+     */
+    pub fn unset_query_logger(&self) {
+        self.inner().query_logger = None;
+    }
+}
+
+/**
+ * Applies `redact` to the parameters of a query.
+ */
+pub(crate) fn redact_params(
+    params: &[Option<Vec<u8>>],
+    redact: &super::inner::ParamRedactor,
+) -> Vec<LoggedParam> {
+    params
+        .iter()
+        .enumerate()
+        .map(|(x, value)| match value {
+            Some(value) => redact(x + 1, value),
+            None => LoggedParam::Null,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::LoggedParam;
+
+    #[test]
+    fn summary() {
+        assert_eq!(
+            LoggedParam::summary(b"foo\0"),
+            LoggedParam::Value("foo".to_string())
+        );
+        assert_eq!(
+            LoggedParam::summary(&[0xff, 0xfe]),
+            LoggedParam::Value("<2 bytes>".to_string())
+        );
+
+        let LoggedParam::Value(long) = LoggedParam::summary("é".repeat(100).as_bytes()) else {
+            panic!();
+        };
+        assert_eq!(long, format!("{}…", "é".repeat(super::SUMMARY_LENGTH)));
+    }
+
+    #[test]
+    fn query_logger() {
+        let conn = crate::test::new_conn();
+        let logs = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+
+        let l = logs.clone();
+        conn.set_query_logger(
+            move |query| {
+                l.lock().unwrap().push((
+                    query.statement.map(String::from),
+                    query.params.clone(),
+                    query.outcome.clone(),
+                ))
+            },
+            |position, value| {
+                if position == 2 {
+                    LoggedParam::Redacted
+                } else {
+                    LoggedParam::summary(value)
+                }
+            },
+        );
+
+        conn.exec_params(
+            "SELECT $1::text, $2::text, $3::int",
+            &[],
+            &crate::params!["foo", "secret", None::<i32>],
+            &[],
+            crate::Format::Text,
+        );
+        conn.exec("SELECT * FROM unknown_table");
+        conn.unset_query_logger();
+        conn.exec("SELECT 2");
+
+        assert_eq!(
+            *logs.lock().unwrap(),
+            vec![
+                (
+                    Some("SELECT $1::text, $2::text, $3::int".to_string()),
+                    vec![
+                        LoggedParam::Value("foo".to_string()),
+                        LoggedParam::Redacted,
+                        LoggedParam::Null,
+                    ],
+                    super::Outcome::Success { rows: Some(1) },
+                ),
+                (
+                    Some("SELECT * FROM unknown_table".to_string()),
+                    Vec::new(),
+                    super::Outcome::Failure {
+                        sqlstate: Some("42P01".to_string())
+                    },
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn query_logger_prepared() {
+        let conn = crate::test::new_conn();
+        let logs = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+
+        conn.prepare(Some("logged"), "SELECT $1::int", &[]);
+
+        let l = logs.clone();
+        conn.set_query_logger(
+            move |query| {
+                l.lock().unwrap().push((
+                    query.operation.to_string(),
+                    query.statement.map(String::from),
+                    query.prepared.map(String::from),
+                ))
+            },
+            |_, value| LoggedParam::summary(value),
+        );
+
+        conn.exec_prepared(Some("logged"), &crate::params![1], &[], crate::Format::Text);
+        conn.exec("SELECT 1");
+
+        assert_eq!(
+            *logs.lock().unwrap(),
+            vec![
+                (
+                    "exec_prepared".to_string(),
+                    None,
+                    Some("logged".to_string())
+                ),
+                ("exec".to_string(), Some("SELECT 1".to_string()), None),
+            ]
+        );
+    }
+}
@@ -0,0 +1,66 @@
+/**
+ * Accounts the memory of the live results created by a connection.
+ */
+struct MemoryAccounting(std::sync::Arc<std::sync::atomic::AtomicU64>);
+
+impl MemoryAccounting {
+    fn add(&self, result: &mut crate::Result) -> bool {
+        let size = result.memory_size();
+        self.0.fetch_add(size, std::sync::atomic::Ordering::Relaxed);
+
+        // Results can grow, remember what to subtract on destroy.
+        result.set_instance_data::<Self, _>(size).is_ok()
+    }
+}
+
+impl crate::event::Event for MemoryAccounting {
+    fn result_create(&self, _: &crate::Connection, result: &mut crate::Result) -> bool {
+        self.add(result)
+    }
+
+    fn result_copy(&self, _: &crate::Result, dest: &mut crate::Result) -> bool {
+        self.add(dest)
+    }
+
+    fn result_destroy(&self, result: &crate::Result) {
+        if let Some(size) = result.instance_data::<Self, u64>() {
+            self.0
+                .fetch_sub(*size, std::sync::atomic::Ordering::Relaxed);
+        }
+    }
+}
+
+impl super::Connection {
+    /**
+     * Starts accounting the memory of the results created by this connection, see
+     * [`Connection::result_memory`](super::Connection::result_memory).
+     */
+    pub fn track_result_memory(&self) -> crate::errors::Result {
+        // The lock is held while registering, for concurrent calls to register the event once.
+        let mut inner = self.inner();
+
+        if inner.result_memory.is_some() {
+            return Ok(());
+        }
+
+        let total = std::sync::Arc::new(std::sync::atomic::AtomicU64::new(0));
+        self.register_event("result memory", MemoryAccounting(total.clone()))?;
+        inner.result_memory = Some(total);
+
+        Ok(())
+    }
+
+    /**
+     * Returns the number of bytes allocated by the live results of this connection, as reported
+     * by [`Result::memory_size`](crate::Result::memory_size) when they were created.
+     *
+     * `None` if [`Connection::track_result_memory`](super::Connection::track_result_memory)
+     * hasn't been called.
+     */
+    pub fn result_memory(&self) -> Option<u64> {
+        self.inner()
+            .result_memory
+            .as_ref()
+            .map(|x| x.load(std::sync::atomic::Ordering::Relaxed))
+    }
+}
@@ -0,0 +1,182 @@
+/**
+ * A [`Queryable`](super::Queryable) returning results fabricated with a
+ * [`result::Builder`](crate::result::Builder), without a server.
+ *
+ * ```
+ * use libpq::connection::Queryable;
+ *
+ * let conn = libpq::connection::MockConnection::new();
+ * conn.set_result(
+ *     "SELECT 1",
+ *     libpq::result::Builder::new(libpq::Status::TupplesOk)
+ *         .column("?column?", &libpq::types::INT4)
+ *         .row(&[Some("1")]),
+ * );
+ *
+ * assert_eq!(conn.exec("SELECT 1").value(0, 0), Some(&b"1"[..]));
+ * assert_eq!(conn.exec("SELECT 2").status(), libpq::Status::FatalError);
+ * assert_eq!(conn.queries(), vec!["SELECT 1", "SELECT 2"]);
+ * ```
+ */
+#[derive(Debug, Default)]
+pub struct MockConnection {
+    state: std::sync::Mutex<State>,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    results: std::collections::HashMap<String, crate::result::Builder>,
+    queue: std::collections::VecDeque<crate::result::Builder>,
+    prepared: std::collections::HashMap<String, String>,
+    queries: Vec<String>,
+}
+
+impl MockConnection {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /**
+     * Sets the result returned each time `query` is executed.
+     */
+    pub fn set_result(&self, query: &str, result: crate::result::Builder) {
+        self.state().results.insert(query.to_string(), result);
+    }
+
+    /**
+     * Adds a result returned, in order, by the queries without a result set by
+     * [`MockConnection::set_result`].
+     */
+    pub fn push_result(&self, result: crate::result::Builder) {
+        self.state().queue.push_back(result);
+    }
+
+    /**
+     * Returns the executed queries, in order.
+     */
+    pub fn queries(&self) -> Vec<String> {
+        self.state().queries.clone()
+    }
+
+    fn result(&self, query: &str) -> crate::Result {
+        let mut state = self.state();
+        state.queries.push(query.to_string());
+
+        let builder = match state.results.get(query) {
+            Some(builder) => Some(builder.clone()),
+            None => state.queue.pop_front(),
+        };
+
+        builder
+            .unwrap_or_else(|| crate::result::Builder::new(crate::Status::FatalError))
+            .build()
+            // libpq handles a null result as a fatal error.
+            .unwrap_or_else(|_| std::ptr::null_mut::<pq_sys::PGresult>().into())
+    }
+
+    fn state(&self) -> std::sync::MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl super::Queryable for MockConnection {
+    fn exec(&self, query: &str) -> crate::Result {
+        self.result(query)
+    }
+
+    fn exec_params(
+        &self,
+        command: &str,
+        _: &[crate::Oid],
+        _: &[Option<Vec<u8>>],
+        _: &[crate::Format],
+        _: crate::Format,
+    ) -> crate::Result {
+        self.result(command)
+    }
+
+    fn prepare(&self, name: Option<&str>, query: &str, _: &[crate::Oid]) -> crate::Result {
+        self.state()
+            .prepared
+            .insert(name.unwrap_or_default().to_string(), query.to_string());
+
+        crate::result::Builder::new(crate::Status::CommandOk)
+            .build()
+            .unwrap_or_else(|_| std::ptr::null_mut::<pq_sys::PGresult>().into())
+    }
+
+    fn exec_prepared(
+        &self,
+        name: Option<&str>,
+        _: &[Option<Vec<u8>>],
+        _: &[crate::Format],
+        _: crate::Format,
+    ) -> crate::Result {
+        let query = self
+            .state()
+            .prepared
+            .get(name.unwrap_or_default())
+            .cloned()
+            .unwrap_or_default();
+
+        self.result(&query)
+    }
+
+    fn describe_prepared(&self, name: Option<&str>) -> crate::Result {
+        let query = self
+            .state()
+            .prepared
+            .get(name.unwrap_or_default())
+            .cloned()
+            .unwrap_or_default();
+
+        self.result(&query)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::connection::Queryable;
+
+    fn count<Q: Queryable>(conn: &Q) -> usize {
+        conn.exec("SELECT generate_series(1, 3)").ntuples()
+    }
+
+    #[test]
+    fn queryable() {
+        let conn = crate::connection::MockConnection::new();
+        conn.set_result(
+            "SELECT generate_series(1, 3)",
+            crate::result::Builder::new(crate::Status::TupplesOk)
+                .column("generate_series", &crate::types::INT4)
+                .row(&[Some("1")])
+                .row(&[Some("2")])
+                .row(&[Some("3")]),
+        );
+
+        assert_eq!(count(&conn), 3);
+        assert_eq!(count(&crate::test::new_conn()), 3);
+    }
+
+    #[test]
+    fn prepared() {
+        let conn = crate::connection::MockConnection::new();
+        conn.push_result(
+            crate::result::Builder::new(crate::Status::TupplesOk)
+                .column("one", &crate::types::INT4)
+                .row(&[Some("1")]),
+        );
+
+        let result = conn.prepare(Some("one"), "SELECT 1 as one", &[]);
+        assert_eq!(result.status(), crate::Status::CommandOk);
+
+        let result = conn.exec_prepared(Some("one"), &[], &[], crate::Format::Text);
+        assert_eq!(result.value(0, 0), Some(&b"1"[..]));
+        assert_eq!(
+            conn.exec_prepared(Some("one"), &[], &[], crate::Format::Text)
+                .status(),
+            crate::Status::FatalError
+        );
+        assert_eq!(conn.queries(), vec!["SELECT 1 as one", "SELECT 1 as one"]);
+    }
+}
@@ -1,21 +1,55 @@
 mod buffer;
+mod builder;
 mod cancel;
+mod copy_both;
 mod info;
+mod inner;
+mod insert;
+mod instrument;
+mod logger;
+#[cfg(feature = "v12")]
+mod memory;
+mod mock;
 mod notify;
+mod parameters;
+mod queryable;
+mod returning;
+mod settings;
+mod shared;
+mod socket;
 mod status;
+mod timeout;
+mod version;
 
 pub use buffer::*;
+pub use builder::*;
 pub use cancel::*;
+pub use copy_both::*;
 pub use info::*;
+pub use insert::*;
+pub use logger::*;
+pub use mock::*;
 pub use notify::*;
+pub use parameters::*;
+pub use queryable::*;
+pub use shared::*;
+pub use socket::*;
 pub use status::*;
+pub use version::*;
 
 pub type NoticeProcessor = pq_sys::PQnoticeProcessor;
 pub type NoticeReceiver = pq_sys::PQnoticeReceiver;
 
-#[derive(Clone)]
+/**
+ * A connection to the database server.
+ *
+ * A connection is `Send` but not `Sync`: it can be moved to another thread, but not used by two
+ * threads at once, see [`SharedConnection`] to share one. This requires a thread safe libpq, see
+ * [`crate::is_thread_safe`].
+ */
 pub struct Connection {
     conn: *mut pq_sys::PGconn,
+    inner: std::sync::Arc<std::sync::Mutex<inner::Inner>>,
 }
 
 unsafe impl Send for Connection {}
@@ -25,6 +59,7 @@ include!("_cancel.rs");
 include!("_connect.rs");
 include!("_control.rs");
 include!("_copy.rs");
+include!("_event.rs");
 include!("_exec.rs");
 #[cfg(feature = "v12")]
 include!("_gss.rs");
@@ -38,7 +73,9 @@ include!("_trace.rs");
 
 impl Connection {
     /**
-     * Prepares the encrypted form of a PostgreSQL password.
+     * Prepares the encrypted form of a PostgreSQL password, for use in `ALTER ROLE … PASSWORD`.
+     *
+     * Without `algorithm`, the server's `password_encryption` setting is used.
      *
      * On success, this method returns [`PqString`].
      *
@@ -48,28 +85,22 @@ impl Connection {
         &self,
         passwd: &str,
         user: &str,
-        algorithm: Option<&str>,
+        algorithm: Option<crate::encrypt::Algorithm>,
     ) -> crate::errors::Result<PqString> {
-        let c_passwd = crate::ffi::to_cstr(passwd);
-        let c_user = crate::ffi::to_cstr(user);
+        let c_passwd = crate::ffi::try_to_cstr(passwd)?;
+        let c_user = crate::ffi::try_to_cstr(user)?;
+        let c_algorithm = algorithm.map(|x| crate::ffi::to_cstr(x.as_str()));
 
         unsafe {
-            let ptr = if let Some(algorithm) = algorithm {
-                let c_algorithm = crate::ffi::to_cstr(algorithm);
-                pq_sys::PQencryptPasswordConn(
-                    self.into(),
-                    c_passwd.as_ptr(),
-                    c_user.as_ptr(),
-                    c_algorithm.as_ptr(),
-                )
-            } else {
-                pq_sys::PQencryptPasswordConn(
-                    self.into(),
-                    c_passwd.as_ptr(),
-                    c_user.as_ptr(),
-                    std::ptr::null(),
-                )
-            };
+            let ptr = pq_sys::PQencryptPasswordConn(
+                self.into(),
+                c_passwd.as_ptr(),
+                c_user.as_ptr(),
+                c_algorithm
+                    .as_ref()
+                    .map(|x| x.as_ptr())
+                    .unwrap_or(std::ptr::null()),
+            );
 
             if ptr.is_null() {
                 self.error()
@@ -79,12 +110,37 @@ impl Connection {
         }
     }
 
+    /**
+     * Changes the password of a role, encrypting it on the client side with the algorithm of the
+     * server's `password_encryption` setting.
+     *
+     * See [PQchangePassword](https://www.postgresql.org/docs/current/libpq-misc.html#LIBPQ-PQCHANGEPASSWORD).
+     */
+    #[cfg(feature = "v17")]
+    pub fn change_password(&self, user: &str, passwd: &str) -> crate::Result {
+        log::trace!("Change password of '{}'", user);
+
+        let c_user = match crate::ffi::try_to_cstr(user) {
+            Ok(c_user) => c_user,
+            Err(err) => return Self::error_result(err),
+        };
+        let c_passwd = match crate::ffi::try_to_cstr(passwd) {
+            Ok(c_passwd) => c_passwd,
+            Err(err) => return Self::error_result(err),
+        };
+
+        let raw =
+            unsafe { pq_sys::PQchangePassword(self.into(), c_user.as_ptr(), c_passwd.as_ptr()) };
+
+        self.to_result(raw)
+    }
+
     fn transform_params(
         param_values: &[Option<Vec<u8>>],
         param_formats: &[crate::Format],
-    ) -> (Vec<*const libc::c_char>, Vec<i32>, Vec<i32>) {
+    ) -> crate::errors::Result<(Vec<*const libc::c_char>, Vec<i32>, Vec<i32>)> {
         if param_values.is_empty() {
-            return Default::default();
+            return Ok(Default::default());
         }
 
         let mut values = Vec::new();
@@ -97,7 +153,7 @@ impl Connection {
 
             if let Some(v) = value {
                 if format == &crate::Format::Text && v.last() != Some(&b'\0') {
-                    panic!("Param value as text should be null terminated");
+                    return Err(crate::errors::Error::NotNulTerminated(x + 1));
                 }
                 values.push(v.as_ptr() as *const libc::c_char);
                 lengths.push(v.len() as i32);
@@ -107,7 +163,7 @@ impl Connection {
             }
         }
 
-        (values, formats, lengths)
+        Ok((values, formats, lengths))
     }
 
     fn trace_query(
@@ -150,11 +206,285 @@ impl Connection {
         }
     }
 
+    /**
+     * Attaches the connection state (database, host, backend PID and optionally the query) to the
+     * errors raised by this connection.
+     *
+     * `None` disables the enrichment, which is the default.
+     */
+    pub fn set_error_context(&self, query: Option<crate::errors::QueryText>) {
+        self.inner().error_context = query;
+    }
+
+    /**
+     * Controls how the statement is recorded in the `db.statement` field of the query spans.
+     *
+     * Defaults to [`QueryText::Full`](crate::errors::QueryText::Full).
+     */
+    #[cfg(feature = "tracing")]
+    pub fn set_span_statement(&self, statement: crate::errors::QueryText) {
+        self.inner().span_statement = statement;
+    }
+
+    /**
+     * Sets a closure returning attributes, like the current `traceparent`, appended to each query
+     * as a [sqlcommenter](https://google.github.io/sqlcommenter/) comment.
+     *
+     * This lets observability tools correlate the database time with distributed traces.
+     *
+     * See [`crate::sqlcommenter::append`].
+     */
+    pub fn set_sql_commenter<F>(&self, commenter: F)
+    where
+        F: Fn() -> Vec<(String, String)> + Send + Sync + 'static,
+    {
+        self.inner().sql_commenter = Some(std::sync::Arc::new(commenter));
+    }
+
+    /**
+     * Removes the closure set by [`Connection::set_sql_commenter`].
+     */
+    pub fn unset_sql_commenter(&self) {
+        self.inner().sql_commenter = None;
+    }
+
+    /**
+     * Sets a closure called before each query, with the operation (`exec`, `prepare`…) and the
+     * statement, if any.
+     */
+    pub fn on_query_start<F>(&self, hook: F)
+    where
+        F: Fn(&str, Option<&str>) + Send + Sync + 'static,
+    {
+        self.inner().query_start = Some(std::sync::Arc::new(hook));
+    }
+
+    /**
+     * Sets a closure called after each query, with its duration and the result status.
+     *
+     * This is the place to log slow queries or to record custom metrics.
+     */
+    pub fn on_query_end<F>(&self, hook: F)
+    where
+        F: Fn(std::time::Duration, crate::Status) + Send + Sync + 'static,
+    {
+        self.inner().query_end = Some(std::sync::Arc::new(hook));
+    }
+
+    /**
+     * Sets a closure called after each query taking longer than `threshold`, with the statement
+     * or the name of the executed prepared statement, the duration and the number of rows returned
+     * or affected.
+     *
+     * It's independent of [`Connection::on_query_end`] and [`Connection::set_query_logger`].
+     *
+     * Only the synchronous functions are timed: the queries sent with the `send_*` functions, whose
+     * results are read with [`Connection::result`], never call it.
+     */
+    pub fn on_slow_query<F>(&self, threshold: std::time::Duration, hook: F)
+    where
+        F: Fn(Option<&str>, Option<&str>, std::time::Duration, Option<u64>) + Send + Sync + 'static,
+    {
+        self.inner().slow_query = Some((threshold, std::sync::Arc::new(hook)));
+    }
+
+    /**
+     * Removes the closure set by [`Connection::on_slow_query`].
+     */
+    pub fn unset_slow_query(&self) {
+        self.inner().slow_query = None;
+    }
+
+    /**
+     * Removes the closures set by [`Connection::on_query_start`] and
+     * [`Connection::on_query_end`].
+     */
+    pub fn unset_query_hooks(&self) {
+        let mut inner = self.inner();
+        inner.query_start = None;
+        inner.query_end = None;
+    }
+
+    fn comment<'a>(&self, query: &'a str) -> std::borrow::Cow<'a, str> {
+        // Don't call the user closure with the lock held.
+        let commenter = self.inner().sql_commenter.clone();
+
+        match commenter {
+            Some(commenter) => crate::sqlcommenter::append(query, &commenter()).into(),
+            None => query.into(),
+        }
+    }
+
+    /**
+     * Result returned by the synchronous functions for invalid arguments, libpq having no way to
+     * attach an error message to it: the message is kept on the Rust side and returned by
+     * [`crate::Result::error_message`].
+     */
+    fn error_result(error: crate::errors::Error) -> crate::Result {
+        log::error!("{}", error);
+
+        let mut result: crate::Result = unsafe {
+            pq_sys::PQmakeEmptyPGresult(std::ptr::null_mut(), crate::Status::FatalError.into())
+        }
+        .into();
+        result.error = Some(error.to_string());
+
+        result
+    }
+
+    /**
+     * Takes the ownership of `raw`, a result of this connection, decoded with its current client
+     * encoding.
+     */
+    pub(crate) fn to_result(&self, raw: *mut pq_sys::PGresult) -> crate::Result {
+        let mut result = crate::Result::from(raw);
+        result.encoding = self.client_encoding();
+
+        result
+    }
+
+    /**
+     * Like [`crate::ffi::try_to_cstr`], reusing the conversions of the previous calls, for the
+     * arguments repeated in tight loops like prepared statement names.
+     */
+    pub(crate) fn cached_cstr(
+        &self,
+        s: &str,
+    ) -> crate::errors::Result<std::sync::Arc<std::ffi::CStr>> {
+        let mut inner = self.inner();
+
+        if let Some(cstr) = inner.cstrings.get(s) {
+            return Ok(cstr.clone());
+        }
+
+        let cstr: std::sync::Arc<std::ffi::CStr> = crate::ffi::try_to_cstr(s)?.into();
+
+        // Names can be generated, starts over rather than growing unbounded.
+        if inner.cstrings.len() >= CSTRING_CACHE_SIZE {
+            inner.cstrings.clear();
+        }
+        inner.cstrings.insert(s.into(), cstr.clone());
+
+        Ok(cstr)
+    }
+
     pub(crate) fn error<T>(&self) -> crate::errors::Result<T> {
-        Err(self
-            .error_message()
-            .map(|x| crate::errors::Error::Backend(x.to_string()))
-            .unwrap_or(crate::errors::Error::Unknow))
+        self.error_with_query(None)
+    }
+
+    pub(crate) fn error_with_query<T>(&self, query: Option<&str>) -> crate::errors::Result<T> {
+        let raw = unsafe { pq_sys::PQerrorMessage(self.into()) };
+
+        let error = if raw.is_null() {
+            crate::errors::Error::CallFailed("PQerrorMessage")
+        } else {
+            let message = unsafe { std::ffi::CStr::from_ptr(raw) };
+
+            // The client encoding is only known once the connection is established.
+            let message = if self.status() == crate::connection::Status::Ok {
+                self.client_encoding()
+                    .decode(message.to_bytes())
+                    .into_owned()
+            } else {
+                message.to_string_lossy().into_owned()
+            };
+
+            crate::errors::Error::Backend(message)
+        };
+
+        Err(self.with_context(error, query))
+    }
+
+    /**
+     * Converts a failed `result` of this connection into an error carrying its message and the
+     * context configured with [`Connection::set_error_context`].
+     *
+     * `query` is the query text or, for prepared statements, the statement name.
+     */
+    pub fn result_error(
+        &self,
+        result: &crate::Result,
+        query: Option<&str>,
+    ) -> crate::errors::Error {
+        let error = match result.error_message() {
+            Ok(message) => crate::errors::Error::Backend(message.unwrap_or_default()),
+            Err(err) => err,
+        };
+
+        self.with_context(error, query)
+    }
+
+    pub(crate) fn with_context(
+        &self,
+        error: crate::errors::Error,
+        query: Option<&str>,
+    ) -> crate::errors::Error {
+        let mode = match self.inner().error_context {
+            Some(mode) => mode,
+            None => return error,
+        };
+
+        let context = crate::errors::Context {
+            db: self.db().ok(),
+            host: self.host().ok(),
+            backend_pid: self.backend_pid(),
+            query: query.and_then(|x| mode.apply(x)),
+        };
+
+        crate::errors::Error::Context(Box::new(error), Box::new(context))
+    }
+
+    /**
+     * Takes the ownership of `conn`, a connection created by libpq outside of this crate: it's
+     * closed with `PQfinish` when the returned value is dropped.
+     *
+     * # Safety
+     *
+     * `conn` must be a valid connection, not closed nor owned by anything else.
+     */
+    pub unsafe fn from_raw(conn: *mut pq_sys::PGconn) -> Self {
+        #[cfg(feature = "metrics")]
+        crate::metrics::connection_opened();
+
+        Self {
+            conn,
+            inner: Default::default(),
+        }
+    }
+
+    /**
+     * Releases the ownership of the connection, which must be closed with `PQfinish` by the
+     * caller.
+     *
+     * The Rust state of the connection, like the closures of
+     * [`Connection::set_notice_processor`], is leaked: libpq may still call them.
+     */
+    pub fn into_raw(self) -> *mut pq_sys::PGconn {
+        let connection = std::mem::ManuallyDrop::new(self);
+
+        #[cfg(feature = "metrics")]
+        crate::metrics::connection_closed();
+
+        connection.conn
+    }
+
+    /**
+     * Calls `f` with a `Connection` wrapping `conn`, which stays owned by libpq.
+     */
+    pub(crate) unsafe fn with_raw<R>(conn: *mut pq_sys::PGconn, f: impl FnOnce(&Self) -> R) -> R {
+        let mut connection = std::mem::ManuallyDrop::new(Self {
+            conn,
+            inner: Default::default(),
+        });
+
+        let r = f(&connection);
+        std::ptr::drop_in_place(&mut connection.inner);
+
+        r
+    }
+
+    pub(crate) fn inner(&self) -> std::sync::MutexGuard<'_, inner::Inner> {
+        self.inner.lock().unwrap_or_else(|e| e.into_inner())
     }
 }
 
@@ -184,7 +514,13 @@ impl TryFrom<*mut pq_sys::pg_conn> for Connection {
     type Error = crate::errors::Error;
 
     fn try_from(conn: *mut pq_sys::pg_conn) -> std::result::Result<Self, Self::Error> {
-        let s = Self { conn };
+        let s = Self {
+            conn,
+            inner: Default::default(),
+        };
+
+        #[cfg(feature = "metrics")]
+        crate::metrics::connection_opened();
 
         if s.status() == crate::connection::Status::Bad {
             s.error()
@@ -196,19 +532,47 @@ impl TryFrom<*mut pq_sys::pg_conn> for Connection {
 
 impl Drop for Connection {
     fn drop(&mut self) {
+        if self.inner().cancel_on_drop {
+            self.cancel_in_flight();
+        }
+
         unsafe {
             pq_sys::PQfinish(self.into());
         }
+
+        #[cfg(feature = "metrics")]
+        crate::metrics::connection_closed();
     }
 }
 
+pub(crate) const REDACTED: &str = "********";
+
+/**
+ * Maximum number of strings kept by `Connection::cached_cstr`.
+ */
+const CSTRING_CACHE_SIZE: usize = 64;
+
 impl std::fmt::Debug for Connection {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // Secrets are masked, so connections can be logged.
+        let info = self.info().map(|infos| {
+            infos
+                .into_iter()
+                .map(|(keyword, mut info)| {
+                    if info.is_secret() && info.val.is_some() {
+                        info.val = Some(REDACTED.to_string());
+                    }
+
+                    (keyword, info)
+                })
+                .collect::<std::collections::BTreeMap<_, _>>()
+        });
+
         f.debug_struct("Connection")
             .field("inner", &self.conn)
             .field("db", &self.db())
             .field("user", &self.user())
-            .field("pass", &self.pass())
+            .field("pass", &self.pass().map(|x| x.map(|_| REDACTED)))
             .field("host", &self.host())
             .field("port", &self.port())
             .field("options", &self.options())
@@ -219,7 +583,7 @@ impl std::fmt::Debug for Connection {
             .field("error_message", &self.error_message())
             .field("socket", &self.socket())
             .field("backend_pid", &self.backend_pid())
-            .field("info", &self.info())
+            .field("info", &info)
             .field("needs_password", &self.needs_password())
             .field("used_password", &self.used_password())
             .field("ssl_in_use", &self.ssl_in_use())
@@ -242,15 +606,411 @@ mod test {
     }
 
     #[test]
-    fn reset() {
+    fn builder() {
+        let builder = crate::connection::Builder::parse(&crate::test::dsn())
+            .unwrap()
+            .krbsrvname("custom");
+        #[cfg(feature = "v12")]
+        let builder = builder.gssencmode(crate::connection::GssEncMode::Disable);
+
+        let conn = builder.connect().unwrap();
+        let info = conn.info().unwrap();
+        assert_eq!(info["krbsrvname"].val.as_deref(), Some("custom"));
+        #[cfg(feature = "v12")]
+        {
+            assert_eq!(info["gssencmode"].val.as_deref(), Some("disable"));
+            assert!(!conn.gss_enc_in_use());
+        }
+    }
+
+    #[test]
+    fn cached_cstr() {
+        let conn = crate::test::new_conn();
+
+        let a = conn.cached_cstr("stmt").unwrap();
+        let b = conn.cached_cstr("stmt").unwrap();
+        assert!(std::sync::Arc::ptr_eq(&a, &b));
+        assert!(conn.cached_cstr("st\0mt").is_err());
+
+        for x in 0..super::CSTRING_CACHE_SIZE {
+            conn.cached_cstr(&format!("stmt{x}")).unwrap();
+        }
+        assert!(conn.inner().cstrings.len() <= super::CSTRING_CACHE_SIZE);
+    }
+
+    #[test]
+    #[cfg(feature = "bytes")]
+    fn value_bytes_shared() {
+        let conn = crate::test::new_conn();
+        let result = std::sync::Arc::new(conn.exec("SELECT 'foo', NULL"));
+
+        let value = result.value_bytes_shared(0, 0).unwrap();
+        assert_eq!(result.value_bytes_shared(0, 1), None);
+        drop(result);
+
+        assert_eq!(&value[..], b"foo");
+    }
+
+    #[test]
+    fn cancel_on_drop() {
+        let conn = crate::connection::Builder::parse(&crate::test::dsn())
+            .unwrap()
+            .cancel_on_drop(true)
+            .connect()
+            .unwrap();
+        let pid = conn.backend_pid();
+
+        conn.send_query("SELECT pg_sleep(60)").unwrap();
+        let start = std::time::Instant::now();
+        drop(conn);
+        assert!(start.elapsed() < std::time::Duration::from_secs(30));
+
+        let conn = crate::test::new_conn();
+        let result = conn.exec(&format!(
+            "SELECT 1 FROM pg_stat_activity WHERE pid = {pid} AND state = 'active'"
+        ));
+        assert_eq!(result.ntuples(), 0);
+    }
+
+    #[test]
+    fn raw() {
+        let conn = crate::test::new_conn();
+        let pid = conn.backend_pid();
+
+        let raw = conn.into_raw();
+        let conn = unsafe { crate::Connection::from_raw(raw) };
+        assert_eq!(conn.backend_pid(), pid);
+
+        let result = conn.exec("SELECT 1");
+        let raw = result.into_raw();
+        let result = unsafe { crate::Result::from_raw(raw) };
+        assert_eq!(result.value(0, 0), Some(&b"1"[..]));
+    }
+
+    #[test]
+    fn status_ref() {
+        let conn = crate::test::new_conn();
+
+        assert_eq!(conn.db_ref().unwrap(), conn.db().unwrap());
+        assert_eq!(conn.user_ref().unwrap(), conn.user().unwrap());
+        assert_eq!(conn.host_ref().unwrap(), conn.host().unwrap());
+        assert_eq!(conn.port_ref().unwrap(), conn.port().unwrap());
+        assert_eq!(
+            conn.options_ref().unwrap().map(String::from),
+            conn.options().unwrap()
+        );
+    }
+
+    #[test]
+    fn debug_redacted() {
+        let conn = crate::connection::Builder::parse(&crate::test::dsn())
+            .unwrap()
+            .password("s3cr3t pass")
+            .connect()
+            .unwrap();
+
+        let debug = format!("{conn:?}");
+        assert!(!debug.contains("s3cr3t"));
+        assert!(debug.contains("********"));
+    }
+
+    #[test]
+    fn application_name() {
+        let conn = crate::connection::Builder::parse(&crate::test::dsn())
+            .unwrap()
+            .application_name("libpq builder")
+            .connect()
+            .unwrap();
+        assert_eq!(
+            conn.show("application_name"),
+            Ok("libpq builder".to_string())
+        );
+
+        conn.set_application_name("libpq runtime").unwrap();
+        let result =
+            conn.exec("SELECT application_name FROM pg_stat_activity WHERE pid = pg_backend_pid()");
+        assert_eq!(result.value(0, 0), Some(&b"libpq runtime"[..]));
+    }
+
+    #[test]
+    #[cfg(feature = "v16")]
+    fn require_auth() {
+        use crate::connection::{AuthMethod, RequireAuth};
+
+        assert!("password,!md5".parse::<RequireAuth>().is_err());
+        assert!("kerberos".parse::<RequireAuth>().is_err());
+
+        let require: RequireAuth = "!password,!md5".parse().unwrap();
+        assert_eq!(require.to_string(), "!password,!md5");
+
+        let conn = crate::connection::Builder::parse(&crate::test::dsn())
+            .unwrap()
+            .require_auth(require)
+            .connect()
+            .unwrap();
+        let info = conn.info().unwrap();
+        assert_eq!(info["require_auth"].val.as_deref(), Some("!password,!md5"));
+
+        let result = crate::connection::Builder::parse(&crate::test::dsn())
+            .unwrap()
+            .require_auth(RequireAuth::Allow(vec![AuthMethod::Gss]))
+            .connect();
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn hosts() {
+        let localhost = std::net::IpAddr::from([127, 0, 0, 1]);
+
+        let conn = crate::connection::Builder::parse(&crate::test::dsn())
+            .unwrap()
+            .hosts([("localhost", Some(localhost))])
+            .connect()
+            .unwrap();
+        let info = conn.info().unwrap();
+        assert_eq!(info["host"].val.as_deref(), Some("localhost"));
+        assert_eq!(info["hostaddr"].val.as_deref(), Some("127.0.0.1"));
+
+        let builder = crate::connection::Builder::new()
+            .host("primary,standby")
+            .hostaddr(localhost);
+        assert!(builder.connect().is_err());
+        assert!(builder.start().is_err());
+    }
+
+    #[test]
+    fn connect_deadline() {
+        let conn = crate::connection::Builder::parse(&crate::test::dsn())
+            .unwrap()
+            .connect_timeout(std::time::Duration::from_millis(1))
+            .connect_deadline(std::time::Duration::from_secs(10))
+            .connect()
+            .unwrap();
+        assert_eq!(conn.exec("SELECT 1").status(), crate::Status::TupplesOk);
+        let info = conn.info().unwrap();
+        assert_eq!(info["connect_timeout"].val.as_deref(), Some("1"));
+
+        // Non routable address, the connection attempt never completes.
+        let start = std::time::Instant::now();
+        let result = crate::connection::Builder::new()
+            .host("10.255.255.1")
+            .connect_timeout(std::time::Duration::from_secs(30))
+            .connect_deadline(std::time::Duration::from_millis(200))
+            .connect();
+        assert!(result.is_err());
+        assert!(start.elapsed() < std::time::Duration::from_secs(5));
+    }
+
+    #[test]
+    fn keepalives() {
+        let builder = crate::connection::Builder::parse(&crate::test::dsn())
+            .unwrap()
+            .keepalives(true)
+            .keepalives_idle(std::time::Duration::from_secs(60))
+            .keepalives_interval(std::time::Duration::from_millis(10_500))
+            .keepalives_count(3);
+        #[cfg(feature = "v12")]
+        let builder = builder.tcp_user_timeout(std::time::Duration::from_micros(30_000_001));
+
+        let conn = builder.connect().unwrap();
+        let info = conn.info().unwrap();
+        assert_eq!(info["keepalives"].val.as_deref(), Some("1"));
+        assert_eq!(info["keepalives_idle"].val.as_deref(), Some("60"));
+        assert_eq!(info["keepalives_interval"].val.as_deref(), Some("11"));
+        assert_eq!(info["keepalives_count"].val.as_deref(), Some("3"));
+        #[cfg(feature = "v12")]
+        assert_eq!(info["tcp_user_timeout"].val.as_deref(), Some("30001"));
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn socket_dir() {
+        let dir = crate::test::socket_dir();
+        let conn = crate::connection::Builder::parse(&crate::test::dsn())
+            .unwrap()
+            .socket_dir(&dir)
+            .connect()
+            .unwrap();
+
+        assert_eq!(conn.host(), Ok(dir));
+        assert_eq!(conn.exec("SELECT 1").status(), crate::Status::TupplesOk);
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn abstract_socket() {
+        let error = crate::connection::Builder::parse(&crate::test::dsn())
+            .unwrap()
+            .abstract_socket("libpq_no_such_socket")
+            .connect()
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("@libpq_no_such_socket"), "{error}");
+    }
+
+    #[test]
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    fn requirepeer() {
+        let builder = crate::connection::Builder::parse(&crate::test::dsn())
+            .unwrap()
+            .socket_dir(crate::test::socket_dir());
+
+        let uid = builder
+            .clone()
+            .connect()
+            .unwrap()
+            .peer_credentials()
+            .unwrap()
+            .uid;
+        let user = unsafe { std::ffi::CStr::from_ptr((*libc::getpwuid(uid)).pw_name) };
+        let user = user.to_str().unwrap();
+
+        let conn = builder.clone().requirepeer(user).connect().unwrap();
+        let info = conn.info().unwrap();
+        assert_eq!(info["requirepeer"].val.as_deref(), Some(user));
+
+        let result = builder.requirepeer("libpq_no_such_user").connect();
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn gss_options() {
+        use crate::connection::GssLib;
+
+        assert_eq!("SSPI".parse::<GssLib>(), Ok(GssLib::Sspi));
+        assert!("kerberos".parse::<GssLib>().is_err());
+
+        let conn = crate::connection::Builder::parse(&crate::test::dsn())
+            .unwrap()
+            .gsslib(GssLib::Gssapi)
+            .krbsrvname("postgres")
+            .connect()
+            .unwrap();
+        let info = conn.info().unwrap();
+        assert_eq!(info["gsslib"].val.as_deref(), Some("gssapi"));
+        assert_eq!(info["krbsrvname"].val.as_deref(), Some("postgres"));
+    }
+
+    #[test]
+    #[cfg(feature = "v17")]
+    fn sslnegotiation() {
+        let conn = crate::connection::Builder::parse(&crate::test::dsn())
+            .unwrap()
+            .sslnegotiation(crate::ssl::Negotiation::Postgres)
+            .connect()
+            .unwrap();
+        let info = conn.info().unwrap();
+        assert_eq!(info["sslnegotiation"].val.as_deref(), Some("postgres"));
+        assert!(!conn.ssl_direct_negotiation());
+    }
+
+    #[test]
+    #[cfg(feature = "v18")]
+    fn oauth() {
+        let conn = crate::connection::Builder::parse(&crate::test::dsn())
+            .unwrap()
+            .oauth_issuer("https://example.org")
+            .oauth_client_id("libpq")
+            .connect()
+            .unwrap();
+        let info = conn.info().unwrap();
+        assert_eq!(
+            info["oauth_issuer"].val.as_deref(),
+            Some("https://example.org")
+        );
+        assert_eq!(info["oauth_client_id"].val.as_deref(), Some("libpq"));
+
+        crate::oauth::set_token_hook(|_, _| Some("token".to_string()));
+        crate::oauth::unset_token_hook();
+    }
+
+    #[test]
+    fn connect_with_prompt() {
+        let builder = crate::connection::Builder::parse(&crate::test::dsn()).unwrap();
+        let mut prompted = false;
+
+        let conn = builder.connect_with_prompt(|| {
+            prompted = true;
+            None
+        });
+
+        assert!(conn.is_ok());
+        assert!(!prompted);
+    }
+
+    #[test]
+    fn nul_byte() {
+        let conn = crate::test::new_conn();
+
+        let result = conn.exec("SELECT 1\0; DROP TABLE t");
+        assert_eq!(result.status(), crate::Status::FatalError);
+        assert_eq!(
+            result.error_message(),
+            Ok(Some("unexpected nul byte at position 8".to_string()))
+        );
+
+        assert!(matches!(
+            conn.send_query("SELECT 1\0"),
+            Err(crate::errors::Error::NulByte { position: 8 })
+        ));
+        assert!(crate::Connection::new("host=localhost\0").is_err());
+        assert_eq!(
+            unsafe { conn.ssl_struct("Open\0SSL") },
+            Err(crate::errors::Error::NulByte { position: 4 })
+        );
+        assert_eq!(
+            conn.put_copy_end(Some("fo\0o")),
+            Err(crate::errors::Error::NulByte { position: 2 })
+        );
+    }
+
+    #[test]
+    fn bytea() {
         let conn = crate::test::new_conn();
+
+        let result = conn.exec("SELECT '\\x00ff'::bytea, NULL::bytea");
+        assert_eq!(result.bytea(0, 0).unwrap(), Some(vec![0, 255]));
+        assert_eq!(result.bytea(0, 1).unwrap(), None);
+
+        conn.exec("SET bytea_output = 'escape'");
+        let result = conn.exec("SELECT '\\x00ff'::bytea");
+        assert_eq!(result.bytea(0, 0).unwrap(), Some(vec![0, 255]));
+
+        let mut buffer = vec![1];
+        assert_eq!(result.bytea_into(0, 0, &mut buffer), Ok(true));
+        assert_eq!(buffer, [1, 0, 255]);
+    }
+
+    #[test]
+    fn reset() {
+        let mut conn = crate::test::new_conn();
         conn.reset();
     }
 
+    #[test]
+    #[cfg(feature = "v16")]
+    fn load_balance_hosts() {
+        use crate::connection::LoadBalanceHosts;
+
+        assert_eq!("random".parse(), Ok(LoadBalanceHosts::Random));
+        assert!("round-robin".parse::<LoadBalanceHosts>().is_err());
+
+        let mut conn = crate::connection::Builder::parse(&crate::test::dsn())
+            .unwrap()
+            .load_balance_hosts(LoadBalanceHosts::Random)
+            .connect()
+            .unwrap();
+        let info = conn.info().unwrap();
+        assert_eq!(info["load_balance_hosts"].val.as_deref(), Some("random"));
+
+        conn.reset();
+        assert_eq!(conn.status(), crate::connection::Status::Ok);
+    }
+
     #[test]
     fn poll() {
         let dsn = std::env::var("PQ_DSN").unwrap_or_else(|_| "host=localhost".to_string());
-        let conn = crate::Connection::start(&dsn).unwrap();
+        let mut conn = crate::Connection::start(&dsn).unwrap();
         assert_eq!(conn.poll(), crate::poll::Status::Writing);
         conn.reset_start();
         assert_eq!(conn.reset_poll(), crate::poll::Status::Writing);
@@ -264,8 +1024,72 @@ mod test {
         assert_eq!(results.ntuples(), 2);
         assert_eq!(results.nfields(), 2);
 
-        assert_eq!(results.value(0, 0), Some(&b"1"[..]));
-        assert_eq!(results.value(0, 1), Some(&b"2"[..]));
+        assert_eq!(results.value(0, 0), Some(&b"1"[..]));
+        assert_eq!(results.value(0, 1), Some(&b"2"[..]));
+    }
+
+    #[test]
+    fn copy_result() {
+        let conn = crate::test::new_conn();
+        let results = conn.exec("SELECT 1 as one");
+
+        let attrs = results.copy(crate::result::CopyFlags::ATTRS).unwrap();
+        assert_eq!(attrs.nfields(), 1);
+        assert_eq!(attrs.ntuples(), 0);
+
+        let mut copy = results.copy(crate::result::CopyFlags::TUPLES).unwrap();
+        assert_eq!(copy.value(0, 0), Some(&b"1"[..]));
+
+        copy.set_value(0, 0, Some("2")).unwrap();
+        assert_eq!(copy.value(0, 0), Some(&b"2"[..]));
+        assert_eq!(results.value(0, 0), Some(&b"1"[..]));
+    }
+
+    #[test]
+    fn columns() {
+        let conn = crate::test::new_conn();
+        let results = conn.exec("SELECT 1 as one, 'foo'::varchar(10) as two");
+
+        let columns = results.columns().unwrap();
+        assert_eq!(columns.len(), 2);
+        assert_eq!(columns[0].name, "one");
+        assert_eq!(columns[0].type_oid, crate::types::INT4.oid);
+        assert_eq!(columns[0].ty, Some(crate::types::INT4));
+        assert_eq!(columns[0].table, None);
+        assert_eq!(columns[0].format, crate::Format::Text);
+        assert_eq!(columns[0].fsize, Some(4));
+        assert_eq!(columns[1].name, "two");
+        assert_eq!(columns[1].ty, Some(crate::types::VARCHAR));
+        assert_eq!(columns[1].fmod, Some(14));
+        assert_eq!(columns[1].fsize, None);
+    }
+
+    #[test]
+    fn rows_affected() {
+        let conn = crate::test::new_conn();
+
+        let results = conn.exec("CREATE TEMPORARY TABLE rows_affected (id int)");
+        assert_eq!(results.rows_affected(), None);
+
+        let results = conn.exec("INSERT INTO rows_affected SELECT generate_series(1, 3)");
+        assert_eq!(results.rows_affected(), Some(3));
+
+        let results = conn.exec("UPDATE rows_affected SET id = id + 1 WHERE id > 1");
+        assert_eq!(results.rows_affected(), Some(2));
+
+        let results = conn.exec("DELETE FROM rows_affected WHERE id = 0");
+        assert_eq!(results.rows_affected(), Some(0));
+
+        let results = conn.exec("SELECT * FROM rows_affected");
+        assert_eq!(results.rows_affected(), Some(3));
+
+        if conn.server_version() >= 150000 {
+            let results = conn.exec(
+                "MERGE INTO rows_affected USING (SELECT 2 AS id) s ON rows_affected.id = s.id
+                 WHEN NOT MATCHED THEN INSERT VALUES (s.id)",
+            );
+            assert_eq!(results.rows_affected(), Some(1));
+        }
     }
 
     #[test]
@@ -305,16 +1129,27 @@ mod test {
     }
 
     #[test]
-    #[should_panic]
     fn exec_text() {
         let conn = crate::test::new_conn();
-        let _ = conn.exec_params(
+        let results = conn.exec_params(
             "SELECT $1",
             &[],
             &[Some(b"foo".to_vec())],
             &[],
             crate::Format::Text,
         );
+        assert_eq!(results.status(), crate::Status::FatalError);
+
+        assert_eq!(
+            conn.send_query_params(
+                "SELECT $1",
+                &[],
+                &[Some(b"foo".to_vec())],
+                &[],
+                crate::Format::Text
+            ),
+            Err(crate::errors::Error::NotNulTerminated(1))
+        );
     }
 
     #[test]
@@ -325,6 +1160,16 @@ mod test {
 
         let results = conn.describe_prepared(Some("test1"));
         assert_eq!(results.nfields(), 1);
+        assert_eq!(results.param_types(), [crate::types::TEXT]);
+
+        conn.exec("CREATE TYPE pg_temp.mood AS ENUM ('happy')");
+        conn.prepare(Some("test_mood"), "SELECT $1::pg_temp.mood, $2::int8", &[]);
+        let results = conn.describe_prepared(Some("test_mood"));
+        assert_eq!(results.param_type(1), Some(crate::types::INT8.oid));
+        let types = results.param_types();
+        assert_eq!(types[0].oid, results.param_type(0).unwrap());
+        assert_eq!(types[0].kind, crate::types::Kind::Unknow);
+        assert_eq!(types[1], crate::types::INT8);
 
         let results = conn.exec_prepared(
             Some("test1"),
@@ -384,7 +1229,7 @@ mod test {
                 field_name: Vec::new(),
             };
 
-            result.print(&std::io::stdout(), &options);
+            result.print(std::io::stdout(), &options).unwrap();
         }
     }
 
@@ -418,17 +1263,188 @@ mod test {
         );
     }
 
+    #[test]
+    fn error_context() {
+        let conn = crate::test::new_conn();
+        conn.set_error_context(Some(crate::errors::QueryText::Full));
+
+        conn.send_query("SELECT 1").unwrap();
+        let error = conn.send_query("SELECT 2").unwrap_err();
+
+        assert_eq!(
+            error.inner(),
+            &crate::errors::Error::Backend("another command is already in progress\n".to_string())
+        );
+
+        let context = error.context().unwrap();
+        assert_eq!(context.db, conn.db().ok());
+        assert_eq!(context.backend_pid, conn.backend_pid());
+        assert_eq!(context.query, Some("SELECT 2".to_string()));
+
+        let error = conn.send_query_prepared(Some("stmt"), &[], &[], crate::Format::Text);
+        assert_eq!(
+            error.unwrap_err().context().unwrap().query,
+            Some("stmt".to_string())
+        );
+
+        while conn.result().is_some() {}
+
+        let result = conn.exec("SELECT 1/0");
+        let error = conn.result_error(&result, Some("SELECT 1/0"));
+        assert_eq!(
+            error.inner(),
+            &crate::errors::Error::Backend("ERROR:  division by zero\n".to_string())
+        );
+        assert_eq!(
+            error.context().unwrap().query,
+            Some("SELECT 1/0".to_string())
+        );
+    }
+
+    #[test]
+    fn server_version_info() {
+        let conn = crate::test::new_conn();
+        let version = conn.server_version_info().unwrap();
+
+        assert_eq!(version, conn.server_version().into());
+        assert!(version.at_least(9, 0));
+    }
+
+    #[test]
+    fn protocol_version() {
+        let conn = crate::test::new_conn();
+
+        assert!(matches!(
+            conn.protocol_version(),
+            crate::connection::ProtocolVersion::V3_0 | crate::connection::ProtocolVersion::V3_2
+        ));
+    }
+
+    #[test]
+    fn server_parameters() {
+        let conn = crate::test::new_conn();
+        let parameters = conn.server_parameters();
+
+        assert_eq!(parameters.server_version, conn.server_version_info());
+        assert_eq!(parameters.client_encoding, Some(conn.client_encoding()));
+        assert_eq!(parameters.integer_datetimes, Some(true));
+        assert_eq!(parameters.standard_conforming_strings, Some(true));
+        assert!(parameters.time_zone.is_some());
+        assert!(parameters.date_style.is_some());
+    }
+
+    #[test]
+    fn is_in_recovery() {
+        let conn = crate::test::new_conn();
+
+        assert_eq!(conn.is_in_recovery(), Ok(false));
+    }
+
+    #[test]
+    fn parameter_change() {
+        let conn = crate::test::new_conn();
+        let changes = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+
+        conn.exec("SET TimeZone = 'UTC'");
+
+        let c = changes.clone();
+        conn.on_parameter_change(move |name, value| {
+            c.lock()
+                .unwrap()
+                .push((name.to_string(), value.to_string()));
+        });
+
+        conn.exec("SELECT 1");
+        conn.exec("SET TimeZone = 'Europe/Paris'");
+        conn.send_query("SET application_name = 'libpq test'")
+            .unwrap();
+        while conn.result().is_some() {}
+
+        // Reported by consume_input, before the result is read.
+        conn.send_query("SET DateStyle = 'ISO, DMY'").unwrap();
+        while conn.is_busy() {
+            conn.wait(true, false, None).unwrap();
+            conn.consume_input().unwrap();
+        }
+        assert_eq!(
+            changes.lock().unwrap().last(),
+            Some(&("DateStyle".to_string(), "ISO, DMY".to_string()))
+        );
+        while conn.result().is_some() {}
+
+        conn.unset_parameter_change();
+        conn.exec("SET TimeZone = 'UTC'");
+
+        assert_eq!(
+            *changes.lock().unwrap(),
+            vec![
+                ("TimeZone".to_string(), "Europe/Paris".to_string()),
+                ("application_name".to_string(), "libpq test".to_string()),
+                ("DateStyle".to_string(), "ISO, DMY".to_string()),
+            ]
+        );
+    }
+
     #[test]
     fn client_encoding() {
         let conn = crate::test::new_conn();
         assert_eq!(conn.client_encoding(), crate::Encoding::UTF8);
     }
 
+    #[test]
+    #[cfg(feature = "encoding_rs")]
+    fn decode() {
+        let conn = crate::test::new_conn();
+        conn.set_client_encoding(crate::Encoding::LATIN1).unwrap();
+
+        let result = conn.exec("SELECT 'caf' || chr(233)");
+        assert_eq!(conn.decode(result.value(0, 0).unwrap()).unwrap(), "café");
+    }
+
+    #[test]
+    #[cfg(feature = "encoding_rs")]
+    fn result_encoding() {
+        let conn = crate::test::new_conn();
+        conn.set_client_encoding(crate::Encoding::LATIN1).unwrap();
+
+        let result = conn.exec("SELECT 'caf' || chr(233) AS U&\"caf\\00e9\"");
+        assert_eq!(result.encoding(), crate::Encoding::LATIN1);
+        assert_eq!(result.field_name(0), Ok(Some("café".to_string())));
+        assert_eq!(
+            result.rows().next().unwrap().text(0),
+            Ok(Some("café".into()))
+        );
+
+        let query = "DO $$ BEGIN RAISE EXCEPTION 'caf%', chr(233); END $$";
+        let error = conn
+            .exec_checked(query, &[crate::Status::CommandOk])
+            .unwrap_err();
+        assert!(error.inner().to_string().starts_with("ERROR:  café\n"));
+    }
+
+    #[test]
+    fn strict_utf8() {
+        let conn = crate::test::new_conn();
+        assert_eq!(conn.decode(b"caf\xe9").unwrap(), "caf\u{fffd}");
+
+        conn.set_strict_utf8(true);
+        assert_eq!(
+            conn.decode(b"caf\xe9"),
+            Err(crate::errors::Error::InvalidUtf8(crate::Encoding::UTF8))
+        );
+        assert_eq!(conn.decode("café".as_bytes()).unwrap(), "café");
+    }
+
     #[test]
     fn set_client_encoding() {
         let conn = crate::test::new_conn();
-        conn.set_client_encoding(crate::Encoding::SQL_ASCII);
+        conn.set_client_encoding(crate::Encoding::SQL_ASCII)
+            .unwrap();
         assert_eq!(conn.client_encoding(), crate::Encoding::SQL_ASCII);
+
+        conn.set_client_encoding(crate::Encoding::SHIFT_JIS_2004)
+            .unwrap();
+        assert_eq!(conn.client_encoding(), crate::Encoding::SHIFT_JIS_2004);
     }
 
     #[test]
@@ -449,16 +1465,66 @@ mod test {
     fn ssl_attribute_names() {
         let conn = crate::test::new_conn();
 
-        assert_eq!(
-            conn.ssl_attribute_names(),
-            Ok(vec![
-                crate::ssl::Attribute::Library,
-                crate::ssl::Attribute::KeyBits,
-                crate::ssl::Attribute::Cipher,
-                crate::ssl::Attribute::Compression,
-                crate::ssl::Attribute::Protocol,
-            ])
-        );
+        #[allow(unused_mut)]
+        let mut names = vec![
+            crate::ssl::Attribute::Library,
+            crate::ssl::Attribute::KeyBits,
+            crate::ssl::Attribute::Cipher,
+            crate::ssl::Attribute::Compression,
+            crate::ssl::Attribute::Protocol,
+        ];
+        #[cfg(feature = "v16")]
+        names.push(crate::ssl::Attribute::Alpn);
+        #[cfg(not(feature = "v16"))]
+        if crate::version() >= 160000 {
+            names.push(crate::ssl::Attribute::Unknown("alpn".to_string()));
+        }
+
+        assert_eq!(conn.ssl_attribute_names(), Ok(names));
+    }
+
+    #[test]
+    fn ssl_info() {
+        let conn = crate::test::new_conn();
+
+        match conn.ssl_info() {
+            Some(info) => {
+                assert_eq!(info.library, "OpenSSL");
+                assert!(info.key_bits > 0);
+            }
+            None => assert!(!conn.ssl_in_use()),
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "openssl")]
+    fn ssl_ref() {
+        let conn = crate::test::new_conn();
+
+        match conn.ssl_ref() {
+            Some(ssl) => assert!(ssl.current_cipher().is_some()),
+            None => assert!(!conn.ssl_in_use()),
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "v12")]
+    fn gss() {
+        let conn = crate::test::new_conn();
+
+        if conn.gss_enc_in_use() {
+            assert!(!unsafe { conn.gss_context() }.is_null());
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "v13")]
+    fn key_pass_hook() {
+        crate::ssl::set_key_pass_hook(|_| Some("secret".to_string()));
+        assert!(unsafe { pq_sys::PQgetSSLKeyPassHook_OpenSSL() }.is_some());
+
+        crate::ssl::unset_key_pass_hook();
+        assert!(unsafe { pq_sys::PQgetSSLKeyPassHook_OpenSSL() }.is_none());
     }
 
     #[test]
@@ -509,6 +1575,11 @@ mod test {
         let result = conn.exec("copy tmp to stdout");
         assert_eq!(result.status(), crate::Status::CopyOut);
         assert_eq!(&*conn.copy_data(false).unwrap(), b"1\n");
+
+        let mut buffer = Vec::new();
+        assert_eq!(conn.copy_data_into(false, &mut buffer), Ok(2));
+        assert_eq!(conn.copy_data_into(false, &mut buffer), Ok(2));
+        assert_eq!(buffer, b"2\n3\n");
     }
 
     #[test]
@@ -610,12 +1681,290 @@ B	5	ReadyForQuery	 I
         );
     }
 
+    #[test]
+    fn trace_to() {
+        #[derive(Clone, Default)]
+        struct Buffer(std::sync::Arc<std::sync::Mutex<Vec<u8>>>);
+
+        impl std::io::Write for Buffer {
+            fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+                self.0.lock().unwrap().write(buf)
+            }
+
+            fn flush(&mut self) -> std::io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let conn = crate::test::new_conn();
+        let buffer = Buffer::default();
+
+        conn.trace_to(buffer.clone()).unwrap();
+        conn.exec("SELECT 1");
+        conn.untrace();
+        conn.exec("SELECT 2");
+
+        let trace = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+        assert!(trace.contains("SELECT 1"));
+        assert!(!trace.contains("SELECT 2"));
+    }
+
+    #[test]
+    #[cfg(feature = "v14")]
+    fn trace_set_flags() {
+        let conn = crate::test::new_conn();
+        let buffer = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+
+        struct Buffer(std::sync::Arc<std::sync::Mutex<Vec<u8>>>);
+
+        impl std::io::Write for Buffer {
+            fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+                self.0.lock().unwrap().write(buf)
+            }
+
+            fn flush(&mut self) -> std::io::Result<()> {
+                Ok(())
+            }
+        }
+
+        conn.trace_to(Buffer(buffer.clone())).unwrap();
+        conn.trace_set_flags(
+            crate::trace::Flags::SUPPRESS_TIMESTAMPS | crate::trace::Flags::REGRESS_MODE,
+        );
+        conn.exec("SELECT 1");
+        conn.untrace();
+
+        let trace = String::from_utf8(buffer.lock().unwrap().clone()).unwrap();
+        assert!(!trace.is_empty());
+        assert!(trace
+            .lines()
+            .all(|line| line.starts_with("F\t") || line.starts_with("B\t")));
+    }
+
+    #[test]
+    fn slow_query() {
+        let conn = crate::test::new_conn();
+        let queries = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+
+        let q = queries.clone();
+        conn.on_slow_query(
+            std::time::Duration::from_millis(50),
+            move |statement, prepared, duration, rows| {
+                assert!(duration >= std::time::Duration::from_millis(50));
+                q.lock().unwrap().push((
+                    statement.map(String::from),
+                    prepared.map(String::from),
+                    rows,
+                ));
+            },
+        );
+
+        conn.exec("SELECT 1");
+        conn.exec("SELECT pg_sleep(0.1) FROM generate_series(1, 2)");
+        conn.prepare(Some("slow"), "SELECT pg_sleep(0.1)", &[]);
+        conn.exec_prepared(Some("slow"), &[], &[], crate::Format::Text);
+        conn.unset_slow_query();
+        conn.exec("SELECT pg_sleep(0.1)");
+
+        assert_eq!(
+            *queries.lock().unwrap(),
+            vec![
+                (
+                    Some("SELECT pg_sleep(0.1) FROM generate_series(1, 2)".to_string()),
+                    None,
+                    Some(2)
+                ),
+                (None, Some("slow".to_string()), Some(1)),
+            ]
+        );
+    }
+
+    #[test]
+    fn query_hooks() {
+        let conn = crate::test::new_conn();
+        let queries = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let statuses = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+
+        let q = queries.clone();
+        conn.on_query_start(move |operation, statement| {
+            q.lock()
+                .unwrap()
+                .push((operation.to_string(), statement.map(String::from)));
+        });
+        let s = statuses.clone();
+        conn.on_query_end(move |_, status| s.lock().unwrap().push(status));
+
+        conn.exec("SELECT 1");
+        conn.exec("SELECT * FROM unknown_table");
+        conn.unset_query_hooks();
+        conn.exec("SELECT 2");
+
+        assert_eq!(
+            *queries.lock().unwrap(),
+            vec![
+                ("exec".to_string(), Some("SELECT 1".to_string())),
+                (
+                    "exec".to_string(),
+                    Some("SELECT * FROM unknown_table".to_string())
+                ),
+            ]
+        );
+        assert_eq!(
+            *statuses.lock().unwrap(),
+            vec![crate::Status::TupplesOk, crate::Status::FatalError]
+        );
+    }
+
+    #[test]
+    fn register_event() {
+        #[derive(Default)]
+        struct Counter {
+            register: std::sync::atomic::AtomicUsize,
+            result_create: std::sync::atomic::AtomicUsize,
+            result_destroy: std::sync::atomic::AtomicUsize,
+            conn_destroy: std::sync::atomic::AtomicUsize,
+        }
+
+        struct Event(std::sync::Arc<Counter>);
+
+        impl crate::event::Event for Event {
+            fn register(&self, _: &crate::Connection) -> bool {
+                self.0
+                    .register
+                    .fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                true
+            }
+
+            fn conn_destroy(&self, _: &crate::Connection) {
+                self.0
+                    .conn_destroy
+                    .fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            }
+
+            fn result_create(&self, _: &crate::Connection, _: &mut crate::Result) -> bool {
+                self.0
+                    .result_create
+                    .fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                true
+            }
+
+            fn result_destroy(&self, _: &crate::Result) {
+                self.0
+                    .result_destroy
+                    .fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            }
+        }
+
+        let counter = std::sync::Arc::new(Counter::default());
+        let conn = crate::test::new_conn();
+
+        conn.register_event("counter", Event(counter.clone()))
+            .unwrap();
+        assert!(conn
+            .register_event("counter", Event(counter.clone()))
+            .is_err());
+
+        let result = conn.exec("SELECT 1");
+        drop(conn);
+
+        let load = |x: &std::sync::atomic::AtomicUsize| x.load(std::sync::atomic::Ordering::SeqCst);
+        assert_eq!(load(&counter.register), 1);
+        assert_eq!(load(&counter.result_create), 1);
+        assert_eq!(load(&counter.conn_destroy), 1);
+        assert_eq!(load(&counter.result_destroy), 0);
+
+        drop(result);
+        assert_eq!(load(&counter.result_destroy), 1);
+        assert_eq!(std::sync::Arc::strong_count(&counter), 1);
+    }
+
+    #[test]
+    fn fire_create_events() {
+        struct Event(std::sync::Arc<std::sync::atomic::AtomicUsize>);
+
+        impl crate::event::Event for Event {
+            fn result_create(&self, _: &crate::Connection, _: &mut crate::Result) -> bool {
+                self.0.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                true
+            }
+        }
+
+        let counter = std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let conn = crate::test::new_conn();
+        conn.register_event("counter", Event(counter.clone()))
+            .unwrap();
+
+        let mut result = crate::Result::new(&conn, crate::Status::TupplesOk);
+        assert_eq!(counter.load(std::sync::atomic::Ordering::SeqCst), 0);
+
+        result.fire_create_events(&conn).unwrap();
+        assert_eq!(counter.load(std::sync::atomic::Ordering::SeqCst), 1);
+
+        result.fire_create_events(&conn).unwrap();
+        assert_eq!(counter.load(std::sync::atomic::Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn instance_data() {
+        struct Event;
+
+        impl crate::event::Event for Event {
+            fn register(&self, conn: &crate::Connection) -> bool {
+                conn.set_instance_data::<Self, _>(String::from("conn"))
+                    .is_ok()
+            }
+
+            fn result_create(&self, _: &crate::Connection, result: &mut crate::Result) -> bool {
+                let nfields = result.nfields();
+                result.set_instance_data::<Self, _>(nfields).is_ok()
+            }
+        }
+
+        let conn = crate::test::new_conn();
+        assert!(conn.set_instance_data::<Event, _>(0).is_err());
+
+        conn.register_event("instance_data", Event).unwrap();
+        assert_eq!(
+            conn.instance_data::<Event, String>().as_deref(),
+            Some(&"conn".to_string())
+        );
+        assert!(conn.instance_data::<Event, usize>().is_none());
+
+        let result = conn.exec("SELECT 1, 2");
+        assert_eq!(result.instance_data::<Event, usize>().as_deref(), Some(&2));
+    }
+
+    #[test]
+    #[cfg(feature = "v12")]
+    fn result_memory() {
+        let conn = crate::test::new_conn();
+        assert_eq!(conn.result_memory(), None);
+
+        conn.track_result_memory().unwrap();
+        conn.track_result_memory().unwrap();
+        assert_eq!(conn.result_memory(), Some(0));
+
+        let result = conn.exec("SELECT generate_series(1, 1000)");
+        assert_eq!(conn.result_memory(), Some(result.memory_size()));
+
+        let flags = crate::result::CopyFlags::TUPLES | crate::result::CopyFlags::EVENTS;
+        let copy = result.copy(flags).unwrap();
+        assert_eq!(
+            conn.result_memory(),
+            Some(result.memory_size() + copy.memory_size())
+        );
+
+        drop(result);
+        drop(copy);
+        assert_eq!(conn.result_memory(), Some(0));
+    }
+
     #[test]
     fn encrypt_password() {
         let conn = crate::test::new_conn();
 
         assert_eq!(
-            conn.encrypt_password("1234", "postgres", Some("md5"))
+            conn.encrypt_password("1234", "postgres", Some(crate::encrypt::Algorithm::Md5))
                 .unwrap()
                 .to_string_lossy(),
             "md524bb002702969490e41e26e1a454036c"
@@ -623,15 +1972,82 @@ B	5	ReadyForQuery	 I
     }
 
     #[test]
-    fn invalid_encrypt_password() {
+    fn encrypt_password_scram() {
+        let conn = crate::test::new_conn();
+
+        let verifier = conn
+            .encrypt_password(
+                "1234",
+                "postgres",
+                Some(crate::encrypt::Algorithm::ScramSha256),
+            )
+            .unwrap();
+        assert!(verifier.to_string_lossy().starts_with("SCRAM-SHA-256$"));
+
+        assert!(conn.encrypt_password("1234", "postgres", None).is_ok());
+    }
+
+    #[test]
+    #[cfg(feature = "v17")]
+    fn change_password() {
+        let conn = crate::test::new_conn();
+        conn.exec("CREATE ROLE libpq_change_password");
+
+        let result = conn.change_password("libpq_change_password", "1234");
+        conn.exec("DROP ROLE libpq_change_password");
+
+        assert_eq!(result.status(), crate::Status::CommandOk);
+    }
+
+    #[test]
+    fn notice_processor() {
         let conn = crate::test::new_conn();
+        let messages = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+
+        let m = messages.clone();
+        conn.set_notice_processor(move |message| m.lock().unwrap().push(message.to_string()));
+        conn.exec("DO $$ BEGIN RAISE NOTICE 'foo'; END $$");
 
         assert_eq!(
-            conn.encrypt_password("1234", "postgres", Some("test"))
-                .unwrap_err(),
-            crate::errors::Error::Backend(
-                "unrecognized password encryption algorithm \"test\"\n".to_string()
-            ),
+            *messages.lock().unwrap(),
+            vec!["NOTICE:  foo\n".to_string()]
         );
     }
+
+    #[test]
+    fn notice_receiver() {
+        let conn = crate::test::new_conn();
+        let messages = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+
+        let m = messages.clone();
+        conn.set_notice_receiver(move |result| {
+            m.lock().unwrap().push(
+                result
+                    .error_field(crate::result::ErrorField::MessagePrimary)
+                    .unwrap()
+                    .map(String::from),
+            )
+        });
+        conn.exec("DO $$ BEGIN RAISE WARNING 'bar'; END $$");
+
+        assert_eq!(*messages.lock().unwrap(), vec![Some("bar".to_string())]);
+    }
+
+    #[test]
+    fn capture_notices() {
+        let conn = crate::test::new_conn();
+        conn.capture_notices();
+
+        let result = conn
+            .exec("DO $$ BEGIN RAISE NOTICE 'foo'; RAISE WARNING 'bar' USING HINT = 'baz'; END $$");
+        let notices = result.notices();
+        assert_eq!(notices.len(), 2);
+        assert_eq!(notices[0].severity, crate::notice::Severity::Notice);
+        assert_eq!(notices[0].message, "foo");
+        assert_eq!(notices[1].severity, crate::notice::Severity::Warning);
+        assert_eq!(notices[1].hint.as_deref(), Some("baz"));
+
+        let result = conn.exec_params("SELECT 1", &[], &[], &[], crate::Format::Text);
+        assert!(result.notices().is_empty());
+    }
 }
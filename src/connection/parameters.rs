@@ -0,0 +1,169 @@
+/**
+ * The parameters reported by the server, see
+ * [`Connection::server_parameters`](super::Connection::server_parameters).
+ *
+ * Fields are `None` when the server doesn't report the parameter, or it can't be parsed.
+ */
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ServerParameters {
+    pub application_name: Option<String>,
+    pub client_encoding: Option<crate::Encoding>,
+    pub date_style: Option<String>,
+    pub default_transaction_read_only: Option<bool>,
+    pub in_hot_standby: Option<bool>,
+    pub integer_datetimes: Option<bool>,
+    pub interval_style: Option<String>,
+    pub is_superuser: Option<bool>,
+    pub scram_iterations: Option<u32>,
+    pub server_encoding: Option<crate::Encoding>,
+    pub server_version: Option<super::ServerVersion>,
+    pub session_authorization: Option<String>,
+    pub standard_conforming_strings: Option<bool>,
+    pub time_zone: Option<String>,
+}
+
+/**
+ * The parameters reported by the server when their value changes.
+ */
+const REPORTED: &[&str] = &[
+    "application_name",
+    "client_encoding",
+    "DateStyle",
+    "default_transaction_read_only",
+    "in_hot_standby",
+    "integer_datetimes",
+    "IntervalStyle",
+    "is_superuser",
+    "scram_iterations",
+    "search_path",
+    "server_encoding",
+    "server_version",
+    "session_authorization",
+    "standard_conforming_strings",
+    "TimeZone",
+];
+
+impl super::Connection {
+    /**
+     * Returns the parameters reported by the server, with their current values.
+     *
+     * See [PQparameterStatus](https://www.postgresql.org/docs/current/libpq-status.html#LIBPQ-PQPARAMETERSTATUS).
+     */
+    pub fn server_parameters(&self) -> ServerParameters {
+        ServerParameters {
+            application_name: self.parameter("application_name"),
+            client_encoding: self.parameter("client_encoding").and_then(encoding),
+            date_style: self.parameter("DateStyle"),
+            default_transaction_read_only: self
+                .parameter("default_transaction_read_only")
+                .and_then(boolean),
+            in_hot_standby: self.parameter("in_hot_standby").and_then(boolean),
+            integer_datetimes: self.parameter("integer_datetimes").and_then(boolean),
+            interval_style: self.parameter("IntervalStyle"),
+            is_superuser: self.parameter("is_superuser").and_then(boolean),
+            scram_iterations: self
+                .parameter("scram_iterations")
+                .and_then(|x| x.parse().ok()),
+            server_encoding: self.parameter("server_encoding").and_then(encoding),
+            server_version: self.server_version_info(),
+            session_authorization: self.parameter("session_authorization"),
+            standard_conforming_strings: self
+                .parameter("standard_conforming_strings")
+                .and_then(boolean),
+            time_zone: self.parameter("TimeZone"),
+        }
+    }
+
+    /**
+     * Sets a closure called with the name and the new value of the parameters changed by the
+     * server, for example by a `SET TimeZone` query.
+     *
+     * libpq only keeps the last value of each parameter, so the values are polled rather than
+     * reported as the messages arrive: the changes are detected at the end of each query executed
+     * with the synchronous functions, when [`Connection::result`](super::Connection::result)
+     * returns `None`, after [`Connection::consume_input`](super::Connection::consume_input) and
+     * when [`Connection::notifies`](super::Connection::notifies) returns. A parameter changed,
+     * then restored, between two polls isn't reported.
+     */
+    pub fn on_parameter_change<F>(&self, hook: F)
+    where
+        F: Fn(&str, &str) + Send + Sync + 'static,
+    {
+        let parameters = self.reported_parameters();
+
+        let mut inner = self.inner();
+        inner.parameter_change = Some(std::sync::Arc::new(hook));
+        inner.parameters = parameters;
+    }
+
+    /**
+     * Removes the closure set by [`Connection::on_parameter_change`](super::Connection::on_parameter_change).
+     */
+    pub fn unset_parameter_change(&self) {
+        let mut inner = self.inner();
+        inner.parameter_change = None;
+        inner.parameters.clear();
+    }
+
+    pub(crate) fn check_parameters(&self) {
+        // Don't call the user closure with the lock held.
+        let hook = match self.inner().parameter_change.clone() {
+            Some(hook) => hook,
+            None => return,
+        };
+
+        let parameters = self.reported_parameters();
+        let previous = std::mem::replace(&mut self.inner().parameters, parameters.clone());
+
+        for (name, value) in &parameters {
+            if previous.get(name) != Some(value) {
+                hook(name, value);
+            }
+        }
+    }
+
+    /**
+     * Returns `true` if the server is a standby, still replaying the WAL.
+     *
+     * Uses the `in_hot_standby` parameter reported by PostgreSQL 14 and later servers, without
+     * round trip, and falls back to `pg_is_in_recovery()`.
+     */
+    pub fn is_in_recovery(&self) -> crate::errors::Result<bool> {
+        if let Some(in_hot_standby) = self.parameter("in_hot_standby").and_then(boolean) {
+            return Ok(in_hot_standby);
+        }
+
+        let result = self.exec_checked(
+            "SELECT pg_catalog.pg_is_in_recovery()",
+            &[crate::Status::TupplesOk],
+        )?;
+
+        Ok(result.value(0, 0) == Some(b"t"))
+    }
+
+    fn reported_parameters(&self) -> std::collections::BTreeMap<&'static str, String> {
+        REPORTED
+            .iter()
+            .filter_map(|name| self.parameter(name).map(|value| (*name, value)))
+            .collect()
+    }
+
+    fn parameter(&self, name: &str) -> Option<String> {
+        let c_name = crate::ffi::to_cstr(name);
+        let raw = unsafe { pq_sys::PQparameterStatus(self.into(), c_name.as_ptr()) };
+
+        crate::ffi::to_option_string(raw).ok().flatten()
+    }
+}
+
+fn boolean(value: String) -> Option<bool> {
+    match value.as_str() {
+        "on" => Some(true),
+        "off" => Some(false),
+        _ => None,
+    }
+}
+
+fn encoding(value: String) -> Option<crate::Encoding> {
+    value.parse().ok()
+}
@@ -0,0 +1,111 @@
+/**
+ * The query execution functions of a [`Connection`](super::Connection).
+ *
+ * Code written against this trait can be tested offline with a [`MockConnection`](super::MockConnection).
+ */
+pub trait Queryable {
+    /**
+     * See [`Connection::exec`](super::Connection::exec).
+     */
+    fn exec(&self, query: &str) -> crate::Result;
+
+    /**
+     * See [`Connection::exec_params`](super::Connection::exec_params).
+     */
+    fn exec_params(
+        &self,
+        command: &str,
+        param_types: &[crate::Oid],
+        param_values: &[Option<Vec<u8>>],
+        param_formats: &[crate::Format],
+        result_format: crate::Format,
+    ) -> crate::Result;
+
+    /**
+     * See [`Connection::prepare`](super::Connection::prepare).
+     */
+    fn prepare(&self, name: Option<&str>, query: &str, param_types: &[crate::Oid])
+        -> crate::Result;
+
+    /**
+     * See [`Connection::exec_prepared`](super::Connection::exec_prepared).
+     */
+    fn exec_prepared(
+        &self,
+        name: Option<&str>,
+        param_values: &[Option<Vec<u8>>],
+        param_formats: &[crate::Format],
+        result_format: crate::Format,
+    ) -> crate::Result;
+
+    /**
+     * See [`Connection::describe_prepared`](super::Connection::describe_prepared).
+     */
+    fn describe_prepared(&self, name: Option<&str>) -> crate::Result;
+
+    /**
+     * Identifies the server, database and user the queries run against, to tell apart the
+     * results of different connections, like in a [`QueryCache`](crate::cache::QueryCache).
+     * `None` if unknown.
+     */
+    fn identity(&self) -> Option<String> {
+        None
+    }
+}
+
+impl Queryable for super::Connection {
+    fn exec(&self, query: &str) -> crate::Result {
+        super::Connection::exec(self, query)
+    }
+
+    fn exec_params(
+        &self,
+        command: &str,
+        param_types: &[crate::Oid],
+        param_values: &[Option<Vec<u8>>],
+        param_formats: &[crate::Format],
+        result_format: crate::Format,
+    ) -> crate::Result {
+        super::Connection::exec_params(
+            self,
+            command,
+            param_types,
+            param_values,
+            param_formats,
+            result_format,
+        )
+    }
+
+    fn prepare(
+        &self,
+        name: Option<&str>,
+        query: &str,
+        param_types: &[crate::Oid],
+    ) -> crate::Result {
+        super::Connection::prepare(self, name, query, param_types)
+    }
+
+    fn exec_prepared(
+        &self,
+        name: Option<&str>,
+        param_values: &[Option<Vec<u8>>],
+        param_formats: &[crate::Format],
+        result_format: crate::Format,
+    ) -> crate::Result {
+        super::Connection::exec_prepared(self, name, param_values, param_formats, result_format)
+    }
+
+    fn describe_prepared(&self, name: Option<&str>) -> crate::Result {
+        super::Connection::describe_prepared(self, name)
+    }
+
+    fn identity(&self) -> Option<String> {
+        Some(format!(
+            "host={} port={} dbname={} user={}",
+            self.host().ok()?,
+            self.port().ok()?,
+            self.db().ok()?,
+            self.user().ok()?,
+        ))
+    }
+}
@@ -0,0 +1,175 @@
+impl super::Connection {
+    /**
+     * Executes `sql`, an `INSERT`, `UPDATE`, `DELETE` or `MERGE` with a `RETURNING` clause, with
+     * `params` in text format, and decodes the single row it returns.
+     *
+     * Fails if the query returns no row or several ones.
+     *
+     * ```no_run
+     * struct User {
+     *     id: i32,
+     *     created_at: String,
+     * }
+     *
+     * impl libpq::result::FromRow for User {
+     *     fn from_row(row: &libpq::result::Row) -> libpq::errors::Result<Self> {
+     *         Ok(Self {
+     *             id: row.parse(0)?.unwrap_or_default(),
+     *             created_at: row.text(1)?.unwrap_or_default().to_string(),
+     *         })
+     *     }
+     * }
+     *
+     * # let conn = libpq::Connection::new("")?;
+     * let user = conn.insert_returning::<User>(
+     *     "INSERT INTO users (name) VALUES ($1) RETURNING id, created_at",
+     *     &libpq::params!["foo"],
+     * )?;
+     * # Ok::<(), libpq::errors::Error>(())
+     * ```
+     */
+    pub fn insert_returning<T: crate::result::FromRow>(
+        &self,
+        sql: &str,
+        params: &[Option<Vec<u8>>],
+    ) -> crate::errors::Result<T> {
+        let result = self.returning(sql, params)?;
+
+        if result.ntuples() != 1 {
+            let error = crate::errors::Error::Backend(format!(
+                "query returned {} rows, expected 1",
+                result.ntuples()
+            ));
+
+            return Err(self.with_context(error, Some(sql)));
+        }
+
+        T::from_row(&crate::result::Row::new(&result, 0))
+    }
+
+    /**
+     * Like [`Connection::insert_returning`], `None` if no row is returned, like for an
+     * `INSERT … ON CONFLICT DO NOTHING` skipping the row.
+     */
+    pub fn insert_returning_opt<T: crate::result::FromRow>(
+        &self,
+        sql: &str,
+        params: &[Option<Vec<u8>>],
+    ) -> crate::errors::Result<Option<T>> {
+        let result = self.returning(sql, params)?;
+
+        if result.ntuples() > 1 {
+            let error = crate::errors::Error::Backend(format!(
+                "query returned {} rows, expected at most 1",
+                result.ntuples()
+            ));
+
+            return Err(self.with_context(error, Some(sql)));
+        }
+
+        result.rows().next().map(|x| T::from_row(&x)).transpose()
+    }
+
+    /**
+     * Like [`Connection::insert_returning`], for any number of rows, like a multi-row `INSERT`
+     * or an `UPDATE`.
+     */
+    pub fn exec_returning<T: crate::result::FromRow>(
+        &self,
+        sql: &str,
+        params: &[Option<Vec<u8>>],
+    ) -> crate::errors::Result<Vec<T>> {
+        self.returning(sql, params)?.to_vec()
+    }
+
+    fn returning(
+        &self,
+        sql: &str,
+        params: &[Option<Vec<u8>>],
+    ) -> crate::errors::Result<crate::Result> {
+        let result = self.check_result(
+            self.exec_params(sql, &[], params, &[], crate::Format::Text),
+            &[crate::Status::TupplesOk, crate::Status::CommandOk],
+            sql,
+        )?;
+
+        if result.status() == crate::Status::CommandOk {
+            let error = crate::errors::Error::Backend("query has no RETURNING clause".to_string());
+
+            return Err(self.with_context(error, Some(sql)));
+        }
+
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #[derive(Debug, PartialEq)]
+    struct Item {
+        id: i32,
+        name: String,
+    }
+
+    impl crate::result::FromRow for Item {
+        fn from_row(row: &crate::result::Row) -> crate::errors::Result<Self> {
+            Ok(Self {
+                id: row.parse(0)?.unwrap_or_default(),
+                name: row.text(1)?.unwrap_or_default().to_string(),
+            })
+        }
+    }
+
+    #[test]
+    fn returning() {
+        let conn = crate::test::new_conn();
+        conn.exec("CREATE TEMPORARY TABLE items (id serial PRIMARY KEY, name text)");
+
+        let item = conn
+            .insert_returning::<Item>(
+                "INSERT INTO items (name) VALUES ($1) RETURNING id, name",
+                &crate::params!["foo"],
+            )
+            .unwrap();
+        assert_eq!(
+            item,
+            Item {
+                id: 1,
+                name: "foo".to_string()
+            }
+        );
+
+        let item = conn
+            .insert_returning_opt::<Item>(
+                "INSERT INTO items VALUES (1, 'bar') ON CONFLICT DO NOTHING RETURNING id, name",
+                &[],
+            )
+            .unwrap();
+        assert_eq!(item, None);
+
+        let items = conn
+            .exec_returning::<Item>(
+                "INSERT INTO items (name) VALUES ('bar'), ('baz') RETURNING id, name",
+                &[],
+            )
+            .unwrap();
+        assert_eq!(items.len(), 2);
+
+        let items = conn
+            .exec_returning::<Item>("DELETE FROM items WHERE id > 1 RETURNING id, name", &[])
+            .unwrap();
+        let mut ids = items.iter().map(|x| x.id).collect::<Vec<_>>();
+        ids.sort();
+        assert_eq!(ids, [2, 3]);
+
+        assert!(conn
+            .insert_returning::<Item>("UPDATE items SET name = 'qux' RETURNING id, name", &[])
+            .is_ok());
+        assert!(conn
+            .insert_returning::<Item>("DELETE FROM items WHERE false RETURNING id, name", &[])
+            .is_err());
+        assert!(conn
+            .insert_returning::<Item>("INSERT INTO items (name) VALUES ('foo')", &[])
+            .is_err());
+    }
+}
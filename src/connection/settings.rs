@@ -0,0 +1,215 @@
+impl super::Connection {
+    /**
+     * Sets the `name` run-time parameter to `value`, for the current transaction only when
+     * `local` is `true`.
+     *
+     * See [SET](https://www.postgresql.org/docs/current/sql-set.html).
+     */
+    pub fn set_parameter(&self, name: &str, value: &str, local: bool) -> crate::errors::Result {
+        let query = format!(
+            "SET {}{} TO {}",
+            if local { "LOCAL " } else { "" },
+            self.escape_identifier(name)?.to_str()?,
+            self.escape_literal(value)?.to_str()?,
+        );
+
+        self.exec_checked(&query, &[crate::Status::CommandOk])?;
+
+        Ok(())
+    }
+
+    /**
+     * Returns the current value of the `name` run-time parameter.
+     *
+     * See [SHOW](https://www.postgresql.org/docs/current/sql-show.html).
+     */
+    pub fn show(&self, name: &str) -> crate::errors::Result<String> {
+        let query = format!("SHOW {}", self.escape_identifier(name)?.to_str()?);
+        let result = self.exec_checked(&query, &[crate::Status::TupplesOk])?;
+
+        let value = result.value(0, 0).unwrap_or_default();
+
+        Ok(std::str::from_utf8(value)?.to_string())
+    }
+
+    /**
+     * Restores the `name` run-time parameter to its default value.
+     *
+     * See [RESET](https://www.postgresql.org/docs/current/sql-reset.html).
+     */
+    pub fn reset_parameter(&self, name: &str) -> crate::errors::Result {
+        let query = format!("RESET {}", self.escape_identifier(name)?.to_str()?);
+
+        self.exec_checked(&query, &[crate::Status::CommandOk])?;
+
+        Ok(())
+    }
+
+    /**
+     * Sets the `application_name` of the session, shown in `pg_stat_activity`.
+     */
+    pub fn set_application_name(&self, name: &str) -> crate::errors::Result {
+        self.set_parameter("application_name", name, false)
+    }
+
+    /**
+     * Returns the schemas of the `search_path`, unquoted.
+     *
+     * The `"$user"` placeholder is returned as `$user`.
+     */
+    pub fn search_path(&self) -> crate::errors::Result<Vec<String>> {
+        Ok(parse_search_path(&self.show("search_path")?))
+    }
+
+    /**
+     * Sets the `search_path` of the session to `schemas`, quoting them as identifiers.
+     */
+    pub fn set_search_path(&self, schemas: &[&str]) -> crate::errors::Result {
+        let schemas = schemas
+            .iter()
+            .map(|x| Ok(self.escape_identifier(x)?.to_str()?.to_string()))
+            .collect::<crate::errors::Result<Vec<_>>>()?;
+
+        // An empty string literal is the only way to set an empty list.
+        let query = if schemas.is_empty() {
+            "SET search_path TO ''".to_string()
+        } else {
+            format!("SET search_path TO {}", schemas.join(", "))
+        };
+
+        self.exec_checked(&query, &[crate::Status::CommandOk])?;
+
+        Ok(())
+    }
+
+    pub(crate) fn exec_checked(
+        &self,
+        query: &str,
+        accepted: &[crate::Status],
+    ) -> crate::errors::Result<crate::Result> {
+        self.check_result(self.exec(query), accepted, query)
+    }
+
+    /**
+     * Returns `result` if its status is one of `accepted`, its error message otherwise.
+     */
+    pub(crate) fn check_result(
+        &self,
+        result: crate::Result,
+        accepted: &[crate::Status],
+        query: &str,
+    ) -> crate::errors::Result<crate::Result> {
+        if accepted.contains(&result.status()) {
+            Ok(result)
+        } else {
+            Err(self.result_error(&result, Some(query)))
+        }
+    }
+}
+
+/**
+ * Splits a list of identifiers, as reported by `SHOW search_path`.
+ */
+fn parse_search_path(value: &str) -> Vec<String> {
+    let mut schemas = Vec::new();
+    let mut chars = value.chars().peekable();
+
+    loop {
+        while chars.next_if(|c| c.is_whitespace()).is_some() {}
+
+        let mut schema = String::new();
+
+        if chars.next_if_eq(&'"').is_some() {
+            while let Some(c) = chars.next() {
+                if c == '"' {
+                    // A doubled quote is an escaped quote.
+                    if chars.next_if_eq(&'"').is_none() {
+                        break;
+                    }
+                }
+
+                schema.push(c);
+            }
+        } else {
+            while let Some(c) = chars.next_if(|c| *c != ',') {
+                schema.push(c);
+            }
+
+            // Unquoted identifiers are case insensitive.
+            schema = schema.trim_end().to_lowercase();
+        }
+
+        while chars.next_if(|c| *c != ',').is_some() {}
+
+        if !schema.is_empty() {
+            schemas.push(schema);
+        }
+
+        if chars.next().is_none() {
+            break;
+        }
+    }
+
+    schemas
+}
+
+#[cfg(test)]
+mod test {
+    #[test]
+    fn parse_search_path() {
+        assert_eq!(
+            super::parse_search_path(r#""$user", public"#),
+            vec!["$user", "public"]
+        );
+        assert_eq!(
+            super::parse_search_path(r#"Foo , "a,""b""#),
+            vec!["foo", "a,\"b"]
+        );
+        assert!(super::parse_search_path("").is_empty());
+        assert!(super::parse_search_path(r#""""#).is_empty());
+    }
+
+    #[test]
+    fn search_path() {
+        let conn = crate::test::new_conn();
+
+        conn.set_search_path(&["tenant \"1\"", "Public"]).unwrap();
+        assert_eq!(
+            conn.search_path(),
+            Ok(vec!["tenant \"1\"".to_string(), "Public".to_string()])
+        );
+
+        conn.set_search_path(&["$user", "public"]).unwrap();
+        assert_eq!(
+            conn.show("search_path"),
+            Ok("\"$user\", public".to_string())
+        );
+
+        conn.set_search_path(&[]).unwrap();
+        assert_eq!(conn.search_path(), Ok(Vec::new()));
+    }
+
+    #[test]
+    fn parameters() {
+        let conn = crate::test::new_conn();
+        let application_name = conn.show("application_name").unwrap();
+
+        conn.set_parameter("application_name", "it's me", false)
+            .unwrap();
+        assert_eq!(conn.show("application_name"), Ok("it's me".to_string()));
+
+        conn.set_parameter("my.custom", "x\"y", false).unwrap();
+        assert_eq!(conn.show("my.custom"), Ok("x\"y".to_string()));
+
+        conn.reset_parameter("application_name").unwrap();
+        assert_eq!(conn.show("application_name"), Ok(application_name));
+
+        conn.exec("BEGIN");
+        conn.set_parameter("work_mem", "1MB", true).unwrap();
+        assert_eq!(conn.show("work_mem"), Ok("1MB".to_string()));
+        conn.exec("COMMIT");
+        assert_ne!(conn.show("work_mem"), Ok("1MB".to_string()));
+
+        assert!(conn.show("no_such_parameter").is_err());
+    }
+}
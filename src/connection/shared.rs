@@ -0,0 +1,92 @@
+/**
+ * A [`Connection`](super::Connection) shareable across threads.
+ *
+ * `Connection` is `Send` but neither `Sync` nor `Clone`: libpq connections may move between
+ * threads, but must not be used by two threads at once. This wrapper serializes the accesses with
+ * a mutex, and its clones share the same connection.
+ *
+ * ```no_run
+ * let conn = libpq::connection::SharedConnection::new(libpq::Connection::new("")?);
+ *
+ * let handle = {
+ *     let conn = conn.clone();
+ *     std::thread::spawn(move || conn.lock().exec("SELECT 1").status())
+ * };
+ *
+ * conn.lock().exec("SELECT 2");
+ * handle.join().unwrap();
+ * # Ok::<(), libpq::errors::Error>(())
+ * ```
+ */
+#[derive(Clone, Debug)]
+pub struct SharedConnection(std::sync::Arc<std::sync::Mutex<super::Connection>>);
+
+impl SharedConnection {
+    pub fn new(conn: super::Connection) -> Self {
+        Self(std::sync::Arc::new(std::sync::Mutex::new(conn)))
+    }
+
+    /**
+     * Waits for the exclusive access to the connection.
+     *
+     * A panic while the connection was locked doesn't poison it.
+     */
+    pub fn lock(&self) -> std::sync::MutexGuard<'_, super::Connection> {
+        self.0.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /**
+     * Returns the exclusive access to the connection if it's not locked.
+     */
+    pub fn try_lock(&self) -> Option<std::sync::MutexGuard<'_, super::Connection>> {
+        match self.0.try_lock() {
+            Ok(guard) => Some(guard),
+            Err(std::sync::TryLockError::Poisoned(e)) => Some(e.into_inner()),
+            Err(std::sync::TryLockError::WouldBlock) => None,
+        }
+    }
+
+    /**
+     * Returns the connection if this is the last clone.
+     */
+    pub fn into_inner(self) -> Result<super::Connection, Self> {
+        std::sync::Arc::try_unwrap(self.0)
+            .map(|x| x.into_inner().unwrap_or_else(|e| e.into_inner()))
+            .map_err(Self)
+    }
+}
+
+impl From<super::Connection> for SharedConnection {
+    fn from(conn: super::Connection) -> Self {
+        Self::new(conn)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #[test]
+    fn shared() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<super::SharedConnection>();
+
+        let conn = super::SharedConnection::new(crate::test::new_conn());
+
+        let threads = (0..4)
+            .map(|x| {
+                let conn = conn.clone();
+
+                std::thread::spawn(move || {
+                    let result = conn.lock().exec(&format!("SELECT {x}"));
+                    result.value(0, 0).map(|x| x.to_vec())
+                })
+            })
+            .collect::<Vec<_>>();
+
+        for (x, thread) in threads.into_iter().enumerate() {
+            assert_eq!(thread.join().unwrap(), Some(x.to_string().into_bytes()));
+        }
+
+        assert!(conn.try_lock().is_some());
+        assert!(conn.into_inner().is_ok());
+    }
+}
@@ -0,0 +1,320 @@
+/**
+ * The socket of a connection, as returned by [`Connection::socket`](super::Connection::socket):
+ * a file descriptor on Unix, a `SOCKET` on Windows.
+ */
+#[cfg(unix)]
+pub type Socket = std::os::unix::io::RawFd;
+#[cfg(windows)]
+pub type Socket = std::os::windows::io::RawSocket;
+
+/**
+ * Converts the value returned by `PQsocket`: libpq casts the Windows `SOCKET` to an `int`.
+ */
+#[cfg(unix)]
+pub(crate) fn from_raw(socket: libc::c_int) -> Socket {
+    socket
+}
+
+#[cfg(windows)]
+pub(crate) fn from_raw(socket: libc::c_int) -> Socket {
+    // `-1` becomes `INVALID_SOCKET`.
+    socket as isize as Socket
+}
+
+/**
+ * Credentials of the server process, see
+ * [`Connection::peer_credentials`](super::Connection::peer_credentials).
+ */
+#[cfg(unix)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PeerCredentials {
+    pub uid: u32,
+    pub gid: u32,
+    /** Process ID, only known on Linux. */
+    pub pid: Option<i32>,
+}
+
+#[cfg(windows)]
+mod wsa {
+    pub const POLLRDNORM: i16 = 0x0100;
+    pub const POLLWRNORM: i16 = 0x0010;
+
+    #[repr(C)]
+    pub struct WSAPOLLFD {
+        pub fd: usize,
+        pub events: i16,
+        pub revents: i16,
+    }
+
+    #[link(name = "ws2_32")]
+    extern "system" {
+        pub fn WSAPoll(fds: *mut WSAPOLLFD, nfds: u32, timeout: i32) -> i32;
+        pub fn WSAGetLastError() -> i32;
+    }
+}
+
+impl super::Connection {
+    /**
+     * Borrows the connection socket, to register it in an event loop.
+     *
+     * The socket changes when the connection is reset.
+     */
+    #[cfg(unix)]
+    pub fn borrow_socket(&self) -> crate::errors::Result<std::os::unix::io::BorrowedFd<'_>> {
+        let socket = self.socket()?;
+
+        Ok(unsafe { std::os::unix::io::BorrowedFd::borrow_raw(socket) })
+    }
+
+    /**
+     * Borrows the connection socket, to register it in an event loop.
+     *
+     * The socket changes when the connection is reset.
+     */
+    #[cfg(windows)]
+    pub fn borrow_socket(&self) -> crate::errors::Result<std::os::windows::io::BorrowedSocket<'_>> {
+        let socket = self.socket()?;
+
+        Ok(unsafe { std::os::windows::io::BorrowedSocket::borrow_raw(socket) })
+    }
+
+    /**
+     * Returns the credentials of the server process on a Unix-domain socket connection, to
+     * verify who runs the server without relying on `requirepeer`. Fails on the other
+     * connections.
+     */
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    pub fn peer_credentials(&self) -> crate::errors::Result<PeerCredentials> {
+        let mut credentials = libc::ucred {
+            pid: 0,
+            uid: 0,
+            gid: 0,
+        };
+        let mut len = std::mem::size_of::<libc::ucred>() as libc::socklen_t;
+
+        let success = unsafe {
+            libc::getsockopt(
+                self.unix_socket()?,
+                libc::SOL_SOCKET,
+                libc::SO_PEERCRED,
+                &mut credentials as *mut libc::ucred as *mut libc::c_void,
+                &mut len,
+            )
+        };
+
+        if success != 0 {
+            return Err(crate::errors::Error::Backend(
+                std::io::Error::last_os_error().to_string(),
+            ));
+        }
+
+        Ok(PeerCredentials {
+            uid: credentials.uid,
+            gid: credentials.gid,
+            pid: Some(credentials.pid),
+        })
+    }
+
+    /**
+     * Returns the credentials of the server process on a Unix-domain socket connection, to
+     * verify who runs the server without relying on `requirepeer`. Fails on the other
+     * connections.
+     */
+    #[cfg(any(
+        target_os = "macos",
+        target_os = "ios",
+        target_os = "freebsd",
+        target_os = "openbsd",
+        target_os = "netbsd",
+        target_os = "dragonfly"
+    ))]
+    pub fn peer_credentials(&self) -> crate::errors::Result<PeerCredentials> {
+        let mut uid = 0;
+        let mut gid = 0;
+
+        if unsafe { libc::getpeereid(self.unix_socket()?, &mut uid, &mut gid) } != 0 {
+            return Err(crate::errors::Error::Backend(
+                std::io::Error::last_os_error().to_string(),
+            ));
+        }
+
+        Ok(PeerCredentials {
+            uid,
+            gid,
+            pid: None,
+        })
+    }
+
+    /**
+     * Returns the socket, if it's a Unix-domain one.
+     */
+    #[cfg(any(
+        target_os = "linux",
+        target_os = "android",
+        target_os = "macos",
+        target_os = "ios",
+        target_os = "freebsd",
+        target_os = "openbsd",
+        target_os = "netbsd",
+        target_os = "dragonfly"
+    ))]
+    fn unix_socket(&self) -> crate::errors::Result<Socket> {
+        let socket = self.socket()?;
+        let mut address = unsafe { std::mem::zeroed::<libc::sockaddr_storage>() };
+        let mut len = std::mem::size_of::<libc::sockaddr_storage>() as libc::socklen_t;
+
+        let success = unsafe {
+            libc::getsockname(
+                socket,
+                &mut address as *mut libc::sockaddr_storage as *mut libc::sockaddr,
+                &mut len,
+            )
+        };
+
+        if success != 0 {
+            return Err(crate::errors::Error::Backend(
+                std::io::Error::last_os_error().to_string(),
+            ));
+        }
+
+        if libc::c_int::from(address.ss_family) != libc::AF_UNIX {
+            return Err(crate::errors::Error::Backend(
+                "not a Unix-domain socket connection".to_string(),
+            ));
+        }
+
+        Ok(socket)
+    }
+
+    /**
+     * Waits until the socket is ready for reading if `read` is set, writing if `write` is, or
+     * `timeout` elapsed. Returns `false` on timeout.
+     *
+     * Useful with the nonblocking functions, like [`Connection::poll`](super::Connection::poll)
+     * or [`Connection::consume_input`](super::Connection::consume_input).
+     */
+    pub fn wait(
+        &self,
+        read: bool,
+        write: bool,
+        timeout: Option<std::time::Duration>,
+    ) -> crate::errors::Result<bool> {
+        let socket = self.socket()?;
+        let timeout = timeout.map_or(-1, |x| x.as_millis().min(i32::MAX as u128) as i32);
+
+        loop {
+            match poll(socket, read, write, timeout) {
+                Err(err) if err.kind() == std::io::ErrorKind::Interrupted => continue,
+                Err(err) => return Err(crate::errors::Error::Backend(err.to_string())),
+                Ok(ready) => return Ok(ready),
+            }
+        }
+    }
+}
+
+#[cfg(unix)]
+fn poll(socket: Socket, read: bool, write: bool, timeout: i32) -> std::io::Result<bool> {
+    let mut events = 0;
+
+    if read {
+        events |= libc::POLLIN;
+    }
+
+    if write {
+        events |= libc::POLLOUT;
+    }
+
+    let mut fd = libc::pollfd {
+        fd: socket,
+        events,
+        revents: 0,
+    };
+
+    match unsafe { libc::poll(&mut fd, 1, timeout) } {
+        -1 => Err(std::io::Error::last_os_error()),
+        n => Ok(n > 0),
+    }
+}
+
+#[cfg(windows)]
+fn poll(socket: Socket, read: bool, write: bool, timeout: i32) -> std::io::Result<bool> {
+    let mut events = 0;
+
+    if read {
+        events |= wsa::POLLRDNORM;
+    }
+
+    if write {
+        events |= wsa::POLLWRNORM;
+    }
+
+    let mut fd = wsa::WSAPOLLFD {
+        fd: socket as usize,
+        events,
+        revents: 0,
+    };
+
+    match unsafe { wsa::WSAPoll(&mut fd, 1, timeout) } {
+        -1 => Err(std::io::Error::from_raw_os_error(unsafe {
+            wsa::WSAGetLastError()
+        })),
+        n => Ok(n > 0),
+    }
+}
+
+/**
+ * Returns `-1` when the connection has no socket.
+ */
+#[cfg(unix)]
+impl std::os::unix::io::AsRawFd for super::Connection {
+    fn as_raw_fd(&self) -> std::os::unix::io::RawFd {
+        from_raw(unsafe { pq_sys::PQsocket(self.into()) })
+    }
+}
+
+/**
+ * Returns `INVALID_SOCKET` when the connection has no socket.
+ */
+#[cfg(windows)]
+impl std::os::windows::io::AsRawSocket for super::Connection {
+    fn as_raw_socket(&self) -> std::os::windows::io::RawSocket {
+        from_raw(unsafe { pq_sys::PQsocket(self.into()) })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #[test]
+    fn wait() {
+        let conn = crate::test::new_conn();
+
+        assert_eq!(
+            conn.wait(true, false, Some(std::time::Duration::from_millis(10))),
+            Ok(false)
+        );
+        assert_eq!(conn.wait(false, true, None), Ok(true));
+
+        conn.send_query("SELECT 1").unwrap();
+        assert_eq!(conn.wait(true, false, None), Ok(true));
+        while conn.result().is_some() {}
+    }
+
+    #[test]
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    fn peer_credentials() {
+        let conn = crate::connection::Builder::parse(&crate::test::dsn())
+            .unwrap()
+            .socket_dir(crate::test::socket_dir())
+            .connect()
+            .unwrap();
+        let credentials = conn.peer_credentials().unwrap();
+        assert!(credentials.pid.is_some());
+
+        let conn = crate::connection::Builder::parse(&crate::test::dsn())
+            .unwrap()
+            .host("localhost")
+            .connect()
+            .unwrap();
+        assert!(conn.peer_credentials().is_err());
+    }
+}
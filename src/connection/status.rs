@@ -26,6 +26,8 @@ pub enum Status {
     /** Check if we have a proper target connection */
     #[cfg(feature = "v11")]
     CheckTarget,
+    /** A status unknown to this crate, returned by a newer libpq. */
+    Unknown(i32),
 }
 
 impl From<pq_sys::ConnStatusType> for Status {
@@ -47,7 +49,32 @@ impl From<pq_sys::ConnStatusType> for Status {
             #[cfg(feature = "v11")]
             pq_sys::ConnStatusType::CONNECTION_CHECK_TARGET => Self::CheckTarget,
             #[allow(unreachable_patterns)]
-            _ => unreachable!(),
+            _ => Self::Unknown(status as i32),
         }
     }
 }
+
+impl std::fmt::Display for Status {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            Self::Ok => "CONNECTION_OK",
+            Self::Bad => "CONNECTION_BAD",
+            Self::Started => "CONNECTION_STARTED",
+            Self::Made => "CONNECTION_MADE",
+            Self::AwaitingResponse => "CONNECTION_AWAITING_RESPONSE",
+            Self::AuthOk => "CONNECTION_AUTH_OK",
+            Self::Setenv => "CONNECTION_SETENV",
+            Self::SslStartup => "CONNECTION_SSL_STARTUP",
+            Self::Needed => "CONNECTION_NEEDED",
+            Self::CheckWritable => "CONNECTION_CHECK_WRITABLE",
+            Self::Consume => "CONNECTION_CONSUME",
+            #[cfg(feature = "v11")]
+            Self::GssStartup => "CONNECTION_GSS_STARTUP",
+            #[cfg(feature = "v11")]
+            Self::CheckTarget => "CONNECTION_CHECK_TARGET",
+            Self::Unknown(status) => return write!(f, "unknown ConnStatusType code {status}"),
+        };
+
+        f.write_str(s)
+    }
+}
@@ -0,0 +1,113 @@
+/**
+ * Restores `statement_timeout` when dropped, see
+ * [`Connection::with_statement_timeout`](super::Connection::with_statement_timeout).
+ */
+struct TimeoutGuard<'a> {
+    conn: &'a super::Connection,
+    previous: String,
+    local: bool,
+}
+
+impl Drop for TimeoutGuard<'_> {
+    fn drop(&mut self) {
+        // A failed transaction block reverts `SET LOCAL` on rollback.
+        if self.local && self.conn.transaction_status() != crate::transaction::Status::InTrans {
+            return;
+        }
+
+        if let Err(err) = self
+            .conn
+            .set_parameter("statement_timeout", &self.previous, self.local)
+        {
+            log::warn!("Unable to restore statement_timeout: {err}");
+        }
+    }
+}
+
+impl super::Connection {
+    /**
+     * Runs `f` with `statement_timeout` set to `timeout`, then restores the previous value, even
+     * if `f` fails or panics.
+     *
+     * Inside a transaction block the timeout is set with `SET LOCAL`, so it never outlives the
+     * transaction.
+     *
+     * `timeout` is rounded up to the millisecond, and to at least 1ms since `0` disables the
+     * timeout.
+     *
+     * ```no_run
+     * # let conn = libpq::Connection::new("")?;
+     * let result = conn.with_statement_timeout(std::time::Duration::from_secs(1), || {
+     *     conn.exec("SELECT pg_sleep(2)")
+     * })?;
+     * assert_eq!(result.status(), libpq::Status::FatalError);
+     * # Ok::<(), libpq::errors::Error>(())
+     * ```
+     */
+    pub fn with_statement_timeout<T, F>(
+        &self,
+        timeout: std::time::Duration,
+        f: F,
+    ) -> crate::errors::Result<T>
+    where
+        F: FnOnce() -> T,
+    {
+        let local = self.transaction_status() == crate::transaction::Status::InTrans;
+        let previous = self.show("statement_timeout")?;
+        let millis = timeout.as_millis() + u128::from(timeout.subsec_nanos() % 1_000_000 != 0);
+        self.set_parameter("statement_timeout", &format!("{}ms", millis.max(1)), local)?;
+
+        let _guard = TimeoutGuard {
+            conn: self,
+            previous,
+            local,
+        };
+
+        Ok(f())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    fn statement_timeout(conn: &crate::Connection) -> String {
+        conn.show("statement_timeout").unwrap()
+    }
+
+    #[test]
+    fn with_statement_timeout() {
+        let conn = crate::test::new_conn();
+        conn.exec("SET statement_timeout = '1min'");
+
+        let result = conn
+            .with_statement_timeout(std::time::Duration::from_millis(10), || {
+                conn.exec("SELECT pg_sleep(1)")
+            })
+            .unwrap();
+        assert_eq!(result.status(), crate::Status::FatalError);
+        assert_eq!(statement_timeout(&conn), "1min");
+
+        conn.exec("BEGIN");
+        let timeout = conn
+            .with_statement_timeout(std::time::Duration::from_secs(2), || {
+                statement_timeout(&conn)
+            })
+            .unwrap();
+        assert_eq!(timeout, "2s");
+        assert_eq!(statement_timeout(&conn), "1min");
+        conn.exec("ROLLBACK");
+
+        let timeout = conn
+            .with_statement_timeout(std::time::Duration::from_micros(1500), || {
+                statement_timeout(&conn)
+            })
+            .unwrap();
+        assert_eq!(timeout, "2ms");
+
+        let timeout = conn
+            .with_statement_timeout(std::time::Duration::ZERO, || statement_timeout(&conn))
+            .unwrap();
+        assert_eq!(timeout, "1ms");
+
+        assert_eq!(statement_timeout(&conn), "1min");
+    }
+}
@@ -0,0 +1,108 @@
+/**
+ * A server version, as returned by
+ * [`Connection::server_version_info`](super::Connection::server_version_info).
+ *
+ * Before PostgreSQL 10, the major version is made of the two first numbers: `major` and `minor`
+ * are `9` and `6` for 9.6.x.
+ *
+ * ```
+ * let version = libpq::connection::ServerVersion::from(140005);
+ *
+ * assert_eq!(version.major, 14);
+ * assert_eq!(version.minor, 5);
+ * assert!(version.at_least(13, 0));
+ * assert_eq!(version.to_string(), "14.5");
+ * ```
+ */
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct ServerVersion {
+    pub major: u32,
+    pub minor: u32,
+}
+
+impl ServerVersion {
+    pub fn new(major: u32, minor: u32) -> Self {
+        Self { major, minor }
+    }
+
+    /**
+     * Returns `true` if this version is `major.minor` or newer.
+     */
+    pub fn at_least(&self, major: u32, minor: u32) -> bool {
+        *self >= Self::new(major, minor)
+    }
+}
+
+impl From<i32> for ServerVersion {
+    fn from(version: i32) -> Self {
+        let version = version.max(0) as u32;
+
+        if version >= 100_000 {
+            Self::new(version / 10_000, version % 10_000)
+        } else {
+            Self::new(version / 10_000, version / 100 % 100)
+        }
+    }
+}
+
+impl std::fmt::Display for ServerVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}.{}", self.major, self.minor)
+    }
+}
+
+/**
+ * The frontend/backend protocol version, as returned by
+ * [`Connection::protocol_version`](super::Connection::protocol_version).
+ */
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ProtocolVersion {
+    V3_0,
+    /** Introduced in PostgreSQL 18. */
+    V3_2,
+    /** Unknown version, in the `major * 10000 + minor` form. `0` for a bad connection. */
+    Unknown(i32),
+}
+
+impl From<i32> for ProtocolVersion {
+    fn from(version: i32) -> Self {
+        match version {
+            30_000 => Self::V3_0,
+            30_002 => Self::V3_2,
+            version => Self::Unknown(version),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #[test]
+    fn from() {
+        let version = super::ServerVersion::from(90605);
+        assert_eq!(version, super::ServerVersion::new(9, 6));
+        assert_eq!(version.to_string(), "9.6");
+
+        let version = super::ServerVersion::from(170002);
+        assert_eq!(version, super::ServerVersion::new(17, 2));
+        assert!(version.at_least(17, 0));
+        assert!(version.at_least(17, 2));
+        assert!(!version.at_least(17, 3));
+        assert!(!version.at_least(18, 0));
+    }
+
+    #[test]
+    fn protocol_version() {
+        assert_eq!(
+            super::ProtocolVersion::from(30_000),
+            super::ProtocolVersion::V3_0
+        );
+        assert_eq!(
+            super::ProtocolVersion::from(30_002),
+            super::ProtocolVersion::V3_2
+        );
+        assert_eq!(
+            super::ProtocolVersion::from(0),
+            super::ProtocolVersion::Unknown(0)
+        );
+    }
+}
@@ -47,14 +47,327 @@ pub enum Encoding {
     SHIFT_JIS_2004,
 }
 
+impl Encoding {
+    /**
+     * Decodes `bytes` from this encoding.
+     *
+     * Without the `encoding_rs` feature, or for encodings it doesn't support, this is a lossy
+     * UTF-8 conversion.
+     */
+    pub fn decode<'a>(&self, bytes: &'a [u8]) -> std::borrow::Cow<'a, str> {
+        #[cfg(feature = "encoding_rs")]
+        if let Some(encoding) = self.to_encoding_rs() {
+            return encoding.decode_without_bom_handling(bytes).0;
+        }
+
+        String::from_utf8_lossy(bytes)
+    }
+
+    /**
+     * Decodes `bytes` from this encoding, failing with [`Error::InvalidUtf8`] on invalid byte
+     * sequences instead of replacing them.
+     *
+     * [`Error::InvalidUtf8`]: crate::errors::Error::InvalidUtf8
+     */
+    pub fn decode_strict<'a>(
+        &self,
+        bytes: &'a [u8],
+    ) -> crate::errors::Result<std::borrow::Cow<'a, str>> {
+        #[cfg(feature = "encoding_rs")]
+        if *self != Self::UTF8 {
+            if let Some(encoding) = self.to_encoding_rs() {
+                return encoding
+                    .decode_without_bom_handling_and_without_replacement(bytes)
+                    .ok_or(crate::errors::Error::InvalidUtf8(*self));
+            }
+        }
+
+        std::str::from_utf8(bytes)
+            .map(Into::into)
+            .map_err(|_| crate::errors::Error::InvalidUtf8(*self))
+    }
+
+    #[cfg(feature = "encoding_rs")]
+    fn to_encoding_rs(self) -> Option<&'static encoding_rs::Encoding> {
+        let encoding = match self {
+            Self::UTF8 => encoding_rs::UTF_8,
+            Self::EUC_JP => encoding_rs::EUC_JP,
+            Self::EUC_KR => encoding_rs::EUC_KR,
+            // encoding_rs decodes ISO-8859-1 and ISO-8859-9 as their Windows supersets.
+            Self::LATIN1 => encoding_rs::WINDOWS_1252,
+            Self::LATIN2 => encoding_rs::ISO_8859_2,
+            Self::LATIN3 => encoding_rs::ISO_8859_3,
+            Self::LATIN4 => encoding_rs::ISO_8859_4,
+            Self::LATIN5 => encoding_rs::WINDOWS_1254,
+            Self::LATIN6 => encoding_rs::ISO_8859_10,
+            Self::LATIN7 => encoding_rs::ISO_8859_13,
+            Self::LATIN8 => encoding_rs::ISO_8859_14,
+            Self::LATIN9 => encoding_rs::ISO_8859_15,
+            Self::LATIN10 => encoding_rs::ISO_8859_16,
+            Self::WIN1256 => encoding_rs::WINDOWS_1256,
+            Self::WIN1258 => encoding_rs::WINDOWS_1258,
+            Self::WIN866 => encoding_rs::IBM866,
+            Self::WIN874 => encoding_rs::WINDOWS_874,
+            Self::KOI8R => encoding_rs::KOI8_R,
+            Self::WIN1251 => encoding_rs::WINDOWS_1251,
+            Self::WIN1252 => encoding_rs::WINDOWS_1252,
+            Self::ISO_8859_5 => encoding_rs::ISO_8859_5,
+            Self::ISO_8859_6 => encoding_rs::ISO_8859_6,
+            Self::ISO_8859_7 => encoding_rs::ISO_8859_7,
+            Self::ISO_8859_8 => encoding_rs::ISO_8859_8,
+            Self::WIN1250 => encoding_rs::WINDOWS_1250,
+            Self::WIN1253 => encoding_rs::WINDOWS_1253,
+            Self::WIN1254 => encoding_rs::WINDOWS_1254,
+            Self::WIN1255 => encoding_rs::WINDOWS_1255,
+            Self::WIN1257 => encoding_rs::WINDOWS_1257,
+            Self::KOI8U => encoding_rs::KOI8_U,
+            Self::SJIS => encoding_rs::SHIFT_JIS,
+            Self::BIG5 => encoding_rs::BIG5,
+            Self::GBK => encoding_rs::GBK,
+            Self::GB18030 => encoding_rs::GB18030,
+            _ => return None,
+        };
+
+        Some(encoding)
+    }
+}
+
+impl Encoding {
+    /**
+     * Returns `true` if the encoding can be used as a server encoding.
+     */
+    pub fn is_valid_server_encoding(&self) -> bool {
+        unsafe { pq_sys::pg_valid_server_encoding_id(*self as i32) != 0 }
+    }
+
+    /**
+     * Returns the encoding set by the `PGCLIENTENCODING` environment variable, `SQL_ASCII` if
+     * unset or invalid.
+     *
+     * See [PQenv2encoding](https://www.postgresql.org/docs/current/libpq-misc.html#LIBPQ-PQENV2ENCODING).
+     */
+    pub fn from_env() -> Self {
+        unsafe { pq_sys::PQenv2encoding() }.into()
+    }
+}
+
+impl std::str::FromStr for Encoding {
+    type Err = crate::errors::Error;
+
+    /**
+     * Parses an encoding name, case-insensitively and including aliases such as `UNICODE`.
+     */
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let c_name = crate::ffi::try_to_cstr(s)?;
+        let encoding = unsafe { pq_sys::pg_char_to_encoding(c_name.as_ptr()) };
+
+        if encoding < 0 {
+            Err(crate::errors::Error::Backend(format!(
+                "invalid encoding name: \"{s}\""
+            )))
+        } else {
+            Ok(encoding.into())
+        }
+    }
+}
+
 impl From<i32> for Encoding {
+    /**
+     * Converts an encoding identifier, falling back to `SQL_ASCII` for unknown ones, like the -1
+     * returned by `PQclientEncoding` for a connection that isn't established.
+     */
     fn from(encoding: i32) -> Self {
-        unsafe { std::mem::transmute(encoding) }
+        match encoding {
+            0 => Self::SQL_ASCII,
+            1 => Self::EUC_JP,
+            2 => Self::EUC_CN,
+            3 => Self::EUC_KR,
+            4 => Self::EUC_TW,
+            5 => Self::EUC_JIS_2004,
+            6 => Self::UTF8,
+            7 => Self::MULE_INTERNAL,
+            8 => Self::LATIN1,
+            9 => Self::LATIN2,
+            10 => Self::LATIN3,
+            11 => Self::LATIN4,
+            12 => Self::LATIN5,
+            13 => Self::LATIN6,
+            14 => Self::LATIN7,
+            15 => Self::LATIN8,
+            16 => Self::LATIN9,
+            17 => Self::LATIN10,
+            18 => Self::WIN1256,
+            19 => Self::WIN1258,
+            20 => Self::WIN866,
+            21 => Self::WIN874,
+            22 => Self::KOI8R,
+            23 => Self::WIN1251,
+            24 => Self::WIN1252,
+            25 => Self::ISO_8859_5,
+            26 => Self::ISO_8859_6,
+            27 => Self::ISO_8859_7,
+            28 => Self::ISO_8859_8,
+            29 => Self::WIN1250,
+            30 => Self::WIN1253,
+            31 => Self::WIN1254,
+            32 => Self::WIN1255,
+            33 => Self::WIN1257,
+            34 => Self::KOI8U,
+            35 => Self::SJIS,
+            36 => Self::BIG5,
+            37 => Self::GBK,
+            38 => Self::UHC,
+            39 => Self::GB18030,
+            40 => Self::JOHAB,
+            41 => Self::SHIFT_JIS_2004,
+            _ => Self::SQL_ASCII,
+        }
     }
 }
 
 impl ToString for Encoding {
+    /**
+     * Returns the server name of the encoding.
+     *
+     * See [pg_encoding_to_char](https://www.postgresql.org/docs/current/multibyte.html).
+     */
     fn to_string(&self) -> String {
-        format!("{:?}", self)
+        let name = unsafe { pq_sys::pg_encoding_to_char(*self as i32) };
+
+        crate::ffi::to_string(name).unwrap_or_else(|_| format!("{:?}", self))
+    }
+}
+
+/**
+ * Returns the length, in bytes, of the first character of `s` in the given encoding, or `None`
+ * if `s` is empty.
+ *
+ * See [PQmblen](https://www.postgresql.org/docs/current/libpq-misc.html#LIBPQ-PQMBLEN).
+ */
+pub fn mblen(s: &[u8], encoding: Encoding) -> Option<usize> {
+    let buf = first_char(s)?;
+
+    Some(unsafe { pq_sys::PQmblen(buf.as_ptr(), encoding as i32) } as usize)
+}
+
+/**
+ * Like [`mblen`], but never reports more bytes than available in `s`.
+ *
+ * See [PQmblenBounded](https://www.postgresql.org/docs/current/libpq-misc.html#LIBPQ-PQMBLENBOUNDED).
+ */
+#[cfg(feature = "v14")]
+pub fn mblen_bounded(s: &[u8], encoding: Encoding) -> Option<usize> {
+    let buf = first_char(s)?;
+
+    Some(unsafe { pq_sys::PQmblenBounded(buf.as_ptr(), encoding as i32) } as usize)
+}
+
+/**
+ * Returns the display length of the first character of `s` in the given encoding, `None` if `s`
+ * is empty or the character isn't printable.
+ *
+ * See [PQdsplen](https://www.postgresql.org/docs/current/libpq-misc.html#LIBPQ-PQDSPLEN).
+ */
+pub fn dsplen(s: &[u8], encoding: Encoding) -> Option<usize> {
+    let buf = first_char(s)?;
+    let len = unsafe { pq_sys::PQdsplen(buf.as_ptr(), encoding as i32) };
+
+    if len < 0 {
+        None
+    } else {
+        Some(len as usize)
+    }
+}
+
+/**
+ * Copies the first character of `s` in a nul terminated buffer, libpq may read up to 4 bytes.
+ */
+fn first_char(s: &[u8]) -> Option<[libc::c_char; 5]> {
+    if s.is_empty() {
+        return None;
+    }
+
+    let mut buf = [0; 5];
+
+    for (x, c) in s.iter().take(4).enumerate() {
+        buf[x] = *c as libc::c_char;
+    }
+
+    Some(buf)
+}
+
+#[cfg(test)]
+mod test {
+    #[test]
+    fn decode() {
+        assert_eq!(crate::Encoding::UTF8.decode("é".as_bytes()), "é");
+        assert_eq!(crate::Encoding::UTF8.decode(b"caf\xe9"), "caf\u{fffd}");
+        assert_eq!(
+            crate::Encoding::UTF8.decode_strict(b"caf\xe9"),
+            Err(crate::errors::Error::InvalidUtf8(crate::Encoding::UTF8))
+        );
+
+        #[cfg(feature = "encoding_rs")]
+        {
+            assert_eq!(crate::Encoding::LATIN1.decode(b"caf\xe9"), "café");
+            assert_eq!(crate::Encoding::SJIS.decode(b"\x83\x65"), "テ");
+        }
+    }
+
+    #[test]
+    fn from_i32() {
+        assert_eq!(crate::Encoding::from(6), crate::Encoding::UTF8);
+        assert_eq!(crate::Encoding::from(41), crate::Encoding::SHIFT_JIS_2004);
+        assert_eq!(crate::Encoding::from(-1), crate::Encoding::SQL_ASCII);
+        assert_eq!(crate::Encoding::from(1000), crate::Encoding::SQL_ASCII);
+    }
+
+    #[test]
+    fn mblen() {
+        use crate::Encoding;
+
+        assert_eq!(crate::encoding::mblen(b"", Encoding::UTF8), None);
+        assert_eq!(crate::encoding::mblen(b"a", Encoding::UTF8), Some(1));
+        assert_eq!(
+            crate::encoding::mblen("é".as_bytes(), Encoding::UTF8),
+            Some(2)
+        );
+        assert_eq!(
+            crate::encoding::mblen("é".as_bytes(), Encoding::LATIN1),
+            Some(1)
+        );
+
+        #[cfg(feature = "v14")]
+        assert_eq!(
+            crate::encoding::mblen_bounded(&"日".as_bytes()[..2], Encoding::UTF8),
+            Some(2)
+        );
+    }
+
+    #[test]
+    fn dsplen() {
+        use crate::Encoding;
+
+        assert_eq!(crate::encoding::dsplen(b"a", Encoding::UTF8), Some(1));
+        assert_eq!(
+            crate::encoding::dsplen("日".as_bytes(), Encoding::UTF8),
+            Some(2)
+        );
+        assert_eq!(crate::encoding::dsplen(b"\x07", Encoding::UTF8), None);
+    }
+
+    #[test]
+    fn names() {
+        use crate::Encoding;
+
+        assert_eq!("utf8".parse::<Encoding>(), Ok(Encoding::UTF8));
+        assert_eq!("UNICODE".parse::<Encoding>(), Ok(Encoding::UTF8));
+        assert_eq!("latin1".parse::<Encoding>(), Ok(Encoding::LATIN1));
+        assert!("unknown".parse::<Encoding>().is_err());
+
+        assert_eq!(Encoding::SHIFT_JIS_2004.to_string(), "SHIFT_JIS_2004");
+
+        assert!(Encoding::UTF8.is_valid_server_encoding());
+        assert!(!Encoding::SJIS.is_valid_server_encoding());
     }
 }
@@ -1,5 +1,24 @@
 use crate::connection::PqString;
 
+/**
+ * Password encryption algorithm, see
+ * [`Connection::encrypt_password`](crate::Connection::encrypt_password).
+ */
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Algorithm {
+    Md5,
+    ScramSha256,
+}
+
+impl Algorithm {
+    pub(crate) fn as_str(&self) -> &'static str {
+        match self {
+            Self::Md5 => "md5",
+            Self::ScramSha256 => "scram-sha-256",
+        }
+    }
+}
+
 /**
  * Prepares the md5-encrypted form of a PostgreSQL password.
  *
@@ -9,8 +28,8 @@ use crate::connection::PqString;
  */
 #[deprecated(note = "Use libpq::Connection::encrypt_password instead")]
 pub fn password(passwd: &str, user: &str) -> crate::errors::Result<PqString> {
-    let c_passwd = crate::ffi::to_cstr(passwd);
-    let c_user = crate::ffi::to_cstr(user);
+    let c_passwd = crate::ffi::try_to_cstr(passwd)?;
+    let c_user = crate::ffi::try_to_cstr(user)?;
 
     unsafe {
         let ptr = pq_sys::PQencryptPassword(c_passwd.as_ptr(), c_user.as_ptr());
@@ -6,12 +6,158 @@ pub type Result<T = ()> = std::result::Result<T, Error>;
 pub enum Error {
     #[error("{0}")]
     ParseIntError(#[from] ParseIntError),
+    /** No longer returned, nul bytes are reported as [`Error::NulByte`]. */
     #[error("{0}")]
-    NulError(#[from] std::ffi::NulError),
+    NulError(std::ffi::NulError),
     #[error("{0}")]
     Backend(String),
+    /** No longer returned, failures are reported as [`Error::CallFailed`]. */
     #[error("Unknow error")]
     Unknow,
     #[error("{0}")]
     Utf8(#[from] std::str::Utf8Error),
+    /**
+     * Text received from libpq isn't valid in its encoding, see
+     * [`Connection::set_strict_utf8`](crate::Connection::set_strict_utf8).
+     */
+    #[error("invalid byte sequence for encoding \"{}\"", .0.to_string())]
+    InvalidUtf8(crate::Encoding),
+    /** The connection has no socket, with the status of the connection. */
+    #[error("no server connection is currently open ({0})")]
+    NoSocket(crate::connection::Status),
+    /** A text parameter, numbered from 1, doesn't end with a nul byte. */
+    #[error("text parameter ${0} must be nul terminated")]
+    NotNulTerminated(usize),
+    /** A string passed to libpq contains a nul byte, at the given byte position. */
+    #[error("unexpected nul byte at position {position}")]
+    NulByte { position: usize },
+    /**
+     * A libpq function, named, failed without an error message, usually because it ran out of
+     * memory or was given invalid arguments.
+     */
+    #[error("{0} failed")]
+    CallFailed(&'static str),
+    /** An error enriched with the state of the connection that raised it. */
+    #[error("{0} ({1})")]
+    Context(Box<Error>, Box<Context>),
+}
+
+impl From<std::ffi::NulError> for Error {
+    fn from(error: std::ffi::NulError) -> Self {
+        Self::NulByte {
+            position: error.nul_position(),
+        }
+    }
+}
+
+impl Error {
+    /**
+     * Returns the connection context attached to this error, if any.
+     *
+     * See [`crate::Connection::set_error_context`].
+     */
+    pub fn context(&self) -> Option<&Context> {
+        match self {
+            Self::Context(_, context) => Some(context),
+            _ => None,
+        }
+    }
+
+    /**
+     * Returns the underlying error, without its context.
+     */
+    pub fn inner(&self) -> &Self {
+        match self {
+            Self::Context(error, _) => error.inner(),
+            error => error,
+        }
+    }
+}
+
+/**
+ * Connection state captured when an error is raised.
+ */
+#[derive(Clone, Debug, PartialEq)]
+pub struct Context {
+    pub db: Option<String>,
+    pub host: Option<String>,
+    pub backend_pid: u32,
+    pub query: Option<String>,
+}
+
+impl std::fmt::Display for Context {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "db: {}, host: {}, backend pid: {}",
+            self.db.as_deref().unwrap_or("?"),
+            self.host.as_deref().unwrap_or("?"),
+            self.backend_pid,
+        )?;
+
+        if let Some(query) = &self.query {
+            write!(f, ", query: '{}'", query)?;
+        }
+
+        Ok(())
+    }
+}
+
+/**
+ * Controls how the query text is attached to an error [`Context`].
+ */
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum QueryText {
+    /** The query is not attached. */
+    Omit,
+    /** The query is attached as is. */
+    #[default]
+    Full,
+    /** The query is attached, truncated to the given number of characters. */
+    Truncate(usize),
+    /** The query is replaced by a placeholder, useful when it may contain secrets. */
+    Redact,
+}
+
+impl QueryText {
+    pub(crate) fn apply(&self, query: &str) -> Option<String> {
+        match self {
+            Self::Omit => None,
+            Self::Full => Some(query.to_string()),
+            Self::Truncate(len) => {
+                if query.chars().count() > *len {
+                    Some(format!("{}…", query.chars().take(*len).collect::<String>()))
+                } else {
+                    Some(query.to_string())
+                }
+            }
+            Self::Redact => Some("<redacted>".to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #[test]
+    fn query_text() {
+        use crate::errors::QueryText;
+
+        assert_eq!(QueryText::Omit.apply("SELECT 1"), None);
+        assert_eq!(
+            QueryText::Full.apply("SELECT 1"),
+            Some("SELECT 1".to_string())
+        );
+        assert_eq!(
+            QueryText::Truncate(6).apply("SELECT 1"),
+            Some("SELECT…".to_string())
+        );
+        assert_eq!(
+            QueryText::Truncate(8).apply("SELECT 1"),
+            Some("SELECT 1".to_string())
+        );
+        assert_eq!(
+            QueryText::Redact.apply("SELECT 1"),
+            Some("<redacted>".to_string())
+        );
+    }
 }
@@ -1,7 +1,14 @@
 use crate::connection::{PqBytes, PqString};
 
-pub(crate) fn literal(conn: &crate::Connection, str: &str) -> crate::errors::Result<PqString> {
-    let c_str = crate::ffi::to_cstr(str);
+/**
+ * Escapes a string for use within an SQL command, including the surrounding single quotes.
+ *
+ * On success, this method returns [`PqString`].
+ *
+ * See [PQescapeLiteral](https://www.postgresql.org/docs/current/libpq-exec.html#LIBPQ-PQESCAPELITERAL).
+ */
+pub fn literal(conn: &crate::Connection, str: &str) -> crate::errors::Result<PqString> {
+    let c_str = crate::ffi::try_to_cstr(str)?;
     unsafe {
         let raw = pq_sys::PQescapeLiteral(conn.into(), c_str.as_ptr(), str.len() as pq_sys::size_t);
 
@@ -21,7 +28,7 @@ pub(crate) fn literal(conn: &crate::Connection, str: &str) -> crate::errors::Res
  * See [PQescapeIdentifier](https://www.postgresql.org/docs/current/libpq-exec.html#LIBPQ-PQESCAPEIDENTIFIER).
  */
 pub fn identifier(conn: &crate::Connection, str: &str) -> crate::errors::Result<PqString> {
-    let c_str = crate::ffi::to_cstr(str);
+    let c_str = crate::ffi::try_to_cstr(str)?;
     unsafe {
         let raw =
             pq_sys::PQescapeIdentifier(conn.into(), c_str.as_ptr(), str.len() as pq_sys::size_t);
@@ -37,11 +44,15 @@ pub fn identifier(conn: &crate::Connection, str: &str) -> crate::errors::Result<
 pub(crate) fn string_conn(conn: &crate::Connection, from: &str) -> crate::errors::Result<PqString> {
     let mut error = 0;
 
+    let c_from = crate::ffi::try_to_cstr(from)?;
+
+    // The buffer is released by `PqString` with `PQfreemem`, so it must come from `malloc`.
     // @see https://github.com/postgres/postgres/blob/REL_12_2/src/interfaces/libpq/fe-exec.c#L3329
-    let cstring = crate::ffi::new_cstring(2 * from.len() + 1);
-    let raw = cstring.into_raw();
+    let raw = unsafe { libc::malloc(2 * from.len() + 1) } as *mut libc::c_char;
 
-    let c_from = crate::ffi::to_cstr(from);
+    if raw.is_null() {
+        return Err(crate::errors::Error::Backend("out of memory\n".to_string()));
+    }
 
     unsafe {
         pq_sys::PQescapeStringConn(
@@ -53,6 +64,7 @@ pub(crate) fn string_conn(conn: &crate::Connection, from: &str) -> crate::errors
         );
 
         if error != 0 {
+            libc::free(raw as *mut libc::c_void);
             return conn.error();
         }
     };
@@ -62,7 +74,7 @@ pub(crate) fn string_conn(conn: &crate::Connection, from: &str) -> crate::errors
 
 #[deprecated(note = "Use libpq::Connection::escape_string instead")]
 pub fn string(from: &str) -> crate::errors::Result<String> {
-    let c_from = crate::ffi::to_cstr(from);
+    let c_from = crate::ffi::try_to_cstr(from)?;
     // @see https://github.com/postgres/postgres/blob/REL_12_2/src/interfaces/libpq/fe-exec.c#L3329
     let cstring = crate::ffi::new_cstring(2 * from.len() + 1);
     let raw = cstring.into_raw();
@@ -130,17 +142,152 @@ pub fn bytea(from: &[u8]) -> crate::errors::Result<PqBytes> {
  * [PQunescapeBytea](https://www.postgresql.org/docs/current/libpq-exec.html#LIBPQ-PQUNESCAPEBYTEA).
  */
 pub fn unescape_bytea(from: &[u8]) -> crate::errors::Result<PqBytes> {
+    // Accepts the output of `escape_bytea` as is, which includes the nul terminator.
+    let from = from.strip_suffix(b"\0").unwrap_or(from);
+    let c_from = std::ffi::CString::new(from)?;
+
     unsafe {
         let mut len = 0;
-        let tmp = pq_sys::PQunescapeBytea(from.as_ptr(), &mut len);
+        let tmp = pq_sys::PQunescapeBytea(c_from.as_ptr() as *const u8, &mut len);
         if tmp.is_null() {
-            Err(crate::errors::Error::Unknow)
+            Err(crate::errors::Error::CallFailed("PQunescapeBytea"))
         } else {
             Ok(PqBytes::from_raw(tmp as *mut libc::c_void, len as usize))
         }
     }
 }
 
+/**
+ * Decodes the hex format of bytea (`\x` followed by hexadecimal digits), the default text output
+ * of the server.
+ *
+ * This doesn't go through libpq and is much faster than [`unescape_bytea`] for large values.
+ */
+pub fn decode_hex_bytea(from: &[u8]) -> crate::errors::Result<Vec<u8>> {
+    let mut to = Vec::new();
+    decode_hex_bytea_into(from, &mut to)?;
+
+    Ok(to)
+}
+
+/**
+ * Like [`decode_hex_bytea`], appending to a buffer of the caller, which can be reused across
+ * values.
+ */
+pub fn decode_hex_bytea_into(from: &[u8], to: &mut Vec<u8>) -> crate::errors::Result {
+    let hex = match from.strip_prefix(b"\\x") {
+        Some(hex) => hex,
+        None => {
+            return Err(crate::errors::Error::Backend(
+                "invalid hex bytea: missing \\x prefix".to_string(),
+            ))
+        }
+    };
+
+    if hex.len() % 2 != 0 {
+        return Err(crate::errors::Error::Backend(
+            "invalid hex bytea: odd number of digits".to_string(),
+        ));
+    }
+
+    to.reserve(hex.len() / 2);
+
+    for pair in hex.chunks_exact(2) {
+        let high = HEX[pair[0] as usize];
+        let low = HEX[pair[1] as usize];
+
+        if high == INVALID || low == INVALID {
+            return Err(crate::errors::Error::Backend(format!(
+                "invalid hexadecimal digit: \"{}\"",
+                String::from_utf8_lossy(pair)
+            )));
+        }
+
+        to.push((high << 4) | low);
+    }
+
+    Ok(())
+}
+
+/**
+ * Decodes the escape format of bytea (`\\` and `\ooo` octal sequences), the text output of the
+ * server when `bytea_output` is `escape`.
+ *
+ * Unlike [`unescape_bytea`], this decodes straight from the value of the result, without an
+ * intermediate copy.
+ */
+pub fn decode_escape_bytea(from: &[u8]) -> crate::errors::Result<Vec<u8>> {
+    let mut to = Vec::new();
+    decode_escape_bytea_into(from, &mut to)?;
+
+    Ok(to)
+}
+
+/**
+ * Like [`decode_escape_bytea`], appending to a buffer of the caller, which can be reused across
+ * values.
+ */
+pub fn decode_escape_bytea_into(from: &[u8], to: &mut Vec<u8>) -> crate::errors::Result {
+    to.reserve(from.len());
+
+    let mut x = 0;
+
+    while x < from.len() {
+        if from[x] != b'\\' {
+            // Copies the run of literal bytes at once.
+            let end = from[x..]
+                .iter()
+                .position(|c| *c == b'\\')
+                .map_or(from.len(), |n| x + n);
+            to.extend_from_slice(&from[x..end]);
+            x = end;
+            continue;
+        }
+
+        if from.get(x + 1) == Some(&b'\\') {
+            to.push(b'\\');
+            x += 2;
+            continue;
+        }
+
+        match from.get(x + 1..x + 4) {
+            Some(&[a @ b'0'..=b'3', b @ b'0'..=b'7', c @ b'0'..=b'7']) => {
+                to.push(((a - b'0') << 6) | ((b - b'0') << 3) | (c - b'0'));
+                x += 4;
+            }
+            _ => {
+                return Err(crate::errors::Error::Backend(
+                    "invalid escape bytea: \\ must be followed by \\ or 3 octal digits".to_string(),
+                ))
+            }
+        }
+    }
+
+    Ok(())
+}
+
+const INVALID: u8 = 0xff;
+
+static HEX: [u8; 256] = {
+    let mut table = [INVALID; 256];
+    let mut x = 0;
+
+    while x < 10 {
+        table[b'0' as usize + x] = x as u8;
+        x += 1;
+    }
+
+    let mut x = 0;
+
+    while x < 6 {
+        table[b'a' as usize + x] = 10 + x as u8;
+        table[b'A' as usize + x] = 10 + x as u8;
+        x += 1;
+    }
+
+    table
+};
+
 #[cfg(test)]
 mod test {
     #[test]
@@ -153,6 +300,31 @@ mod test {
         );
     }
 
+    #[test]
+    fn literal_quotes() {
+        let conn = crate::test::new_conn();
+
+        assert_eq!(
+            conn.escape_literal("it's a \\ test")
+                .unwrap()
+                .to_string_lossy(),
+            " E'it''s a \\\\ test'"
+        );
+        assert!(conn.escape_literal("foo\0bar").is_err());
+    }
+
+    #[test]
+    fn identifier_quotes() {
+        let conn = crate::test::new_conn();
+
+        assert_eq!(
+            conn.escape_identifier("My \"Table\"")
+                .unwrap()
+                .to_string_lossy(),
+            "\"My \"\"Table\"\"\""
+        );
+    }
+
     #[test]
     fn identifier() {
         let conn = crate::test::new_conn();
@@ -177,6 +349,15 @@ mod test {
         );
     }
 
+    #[test]
+    fn string_conn_invalid() {
+        let conn = crate::test::new_conn();
+        conn.set_client_encoding(crate::Encoding::SJIS).unwrap();
+
+        // U+0081 is encoded as 0xC2 0x81, an incomplete double-byte character in SJIS.
+        assert!(crate::escape::string_conn(&conn, "\u{81}").is_err());
+    }
+
     #[test]
     fn string() {
         #![allow(deprecated)]
@@ -210,4 +391,46 @@ mod test {
             b"''foo''\0"
         );
     }
+
+    #[test]
+    fn bytea_round_trip() {
+        let conn = crate::test::new_conn();
+        let data = b"\0'foo'\\\xff";
+
+        let escaped = conn.escape_bytea(data).unwrap();
+        assert_eq!(
+            crate::escape::unescape_bytea(escaped.as_ref())
+                .unwrap()
+                .as_ref(),
+            data
+        );
+        assert_eq!(
+            crate::escape::unescape_bytea(b"\\x00ff").unwrap().as_ref(),
+            b"\0\xff"
+        );
+    }
+
+    #[test]
+    fn decode_hex_bytea() {
+        assert_eq!(
+            crate::escape::decode_hex_bytea(b"\\x00ffAb").unwrap(),
+            b"\0\xff\xab"
+        );
+        assert_eq!(crate::escape::decode_hex_bytea(b"\\x").unwrap(), b"");
+        assert!(crate::escape::decode_hex_bytea(b"00ff").is_err());
+        assert!(crate::escape::decode_hex_bytea(b"\\x0").is_err());
+        assert!(crate::escape::decode_hex_bytea(b"\\x0g").is_err());
+    }
+
+    #[test]
+    fn decode_escape_bytea() {
+        assert_eq!(
+            crate::escape::decode_escape_bytea(b"a\\000\\\\b\\377").unwrap(),
+            b"a\0\\b\xff"
+        );
+        assert_eq!(crate::escape::decode_escape_bytea(b"\\\\").unwrap(), b"\\");
+        assert!(crate::escape::decode_escape_bytea(b"\\").is_err());
+        assert!(crate::escape::decode_escape_bytea(b"\\400").is_err());
+        assert!(crate::escape::decode_escape_bytea(b"\\0x").is_err());
+    }
 }
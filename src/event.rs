@@ -0,0 +1,181 @@
+/**
+ * Callbacks of the [event system](https://www.postgresql.org/docs/current/libpq-events.html).
+ *
+ * Each method is called for the corresponding `PGEVT_*` event of the connection the event is
+ * registered on, see [`Connection::register_event`](crate::Connection::register_event), and of
+ * the results it creates. Returning `false` reports a failure to libpq.
+ *
+ * The connection and results given to the callbacks are owned by libpq and only valid during the
+ * call. Results can be dropped from any thread, hence the `Sync` bound.
+ *
+ * State specific to a connection or a result can be attached with
+ * [`Connection::set_instance_data`](crate::Connection::set_instance_data) and
+ * [`Result::set_instance_data`](crate::Result::set_instance_data).
+ */
+pub trait Event: Send + Sync + 'static {
+    /**
+     * The event is registered on `conn`.
+     *
+     * Returning `false` cancels the registration.
+     */
+    fn register(&self, conn: &crate::Connection) -> bool {
+        let _ = conn;
+        true
+    }
+
+    /**
+     * `conn` has been successfully reset.
+     *
+     * Returning `false` makes the reset fail.
+     */
+    fn conn_reset(&self, conn: &crate::Connection) -> bool {
+        let _ = conn;
+        true
+    }
+
+    /**
+     * `conn` is about to be closed.
+     */
+    fn conn_destroy(&self, conn: &crate::Connection) {
+        let _ = conn;
+    }
+
+    /**
+     * `result` has been created by a query executed on `conn`.
+     *
+     * Returning `false` prevents the next events of this result to be fired.
+     */
+    fn result_create(&self, conn: &crate::Connection, result: &mut crate::Result) -> bool {
+        let _ = (conn, result);
+        true
+    }
+
+    /**
+     * `src` has been copied to `dest`, see [`Result::copy`](crate::Result::copy).
+     *
+     * Returning `false` prevents the next events of `dest` to be fired.
+     */
+    fn result_copy(&self, src: &crate::Result, dest: &mut crate::Result) -> bool {
+        let _ = (src, dest);
+        true
+    }
+
+    /**
+     * `result` is about to be cleared.
+     */
+    fn result_destroy(&self, result: &crate::Result) {
+        let _ = result;
+    }
+}
+
+/**
+ * The `PGEventProc` of `E`.
+ *
+ * `pass_through` is a reference counted `E`: a reference is owned by the connection and one by
+ * each result for which `PGEVT_RESULTCREATE` or `PGEVT_RESULTCOPY` succeeded, they are released by
+ * `PGEVT_CONNDESTROY` and `PGEVT_RESULTDESTROY`, which also free the instance data.
+ */
+pub(crate) unsafe extern "C" fn proc<E: Event>(
+    id: pq_sys::PGEventId,
+    info: *mut libc::c_void,
+    pass_through: *mut libc::c_void,
+) -> libc::c_int {
+    let event = &*(pass_through as *const E);
+
+    let success =
+        crate::ffi::catch_unwind("event proc", || dispatch(event, id, info)).unwrap_or(false);
+
+    match id {
+        pq_sys::PGEventId::PGEVT_RESULTCREATE | pq_sys::PGEventId::PGEVT_RESULTCOPY if success => {
+            std::sync::Arc::increment_strong_count(pass_through as *const E)
+        }
+        pq_sys::PGEventId::PGEVT_CONNDESTROY => {
+            let info = &*(info as *const pq_sys::PGEventConnDestroy);
+            let data = from_raw(pq_sys::PQinstanceData(info.conn, Some(proc::<E>)));
+            crate::ffi::catch_unwind("instance data drop", || drop(data));
+
+            std::sync::Arc::decrement_strong_count(pass_through as *const E)
+        }
+        pq_sys::PGEventId::PGEVT_RESULTDESTROY => {
+            let info = &*(info as *const pq_sys::PGEventResultDestroy);
+            let data = from_raw(pq_sys::PQresultInstanceData(info.result, Some(proc::<E>)));
+            crate::ffi::catch_unwind("instance data drop", || drop(data));
+
+            std::sync::Arc::decrement_strong_count(pass_through as *const E)
+        }
+        _ => (),
+    }
+
+    success as libc::c_int
+}
+
+unsafe fn dispatch<E: Event>(event: &E, id: pq_sys::PGEventId, info: *mut libc::c_void) -> bool {
+    use pq_sys::PGEventId::*;
+
+    match id {
+        PGEVT_REGISTER => {
+            let info = &*(info as *const pq_sys::PGEventRegister);
+            crate::Connection::with_raw(info.conn, |conn| event.register(conn))
+        }
+        PGEVT_CONNRESET => {
+            let info = &*(info as *const pq_sys::PGEventConnReset);
+            crate::Connection::with_raw(info.conn, |conn| event.conn_reset(conn))
+        }
+        PGEVT_CONNDESTROY => {
+            let info = &*(info as *const pq_sys::PGEventConnDestroy);
+            crate::Connection::with_raw(info.conn, |conn| event.conn_destroy(conn));
+            true
+        }
+        PGEVT_RESULTCREATE => {
+            let info = &*(info as *const pq_sys::PGEventResultCreate);
+            let mut result = borrowed_result(info.result);
+            crate::Connection::with_raw(info.conn, |conn| event.result_create(conn, &mut result))
+        }
+        PGEVT_RESULTCOPY => {
+            let info = &*(info as *const pq_sys::PGEventResultCopy);
+            let src = borrowed_result(info.src as *mut pq_sys::PGresult);
+            let mut dest = borrowed_result(info.dest);
+            event.result_copy(&src, &mut dest)
+        }
+        PGEVT_RESULTDESTROY => {
+            let info = &*(info as *const pq_sys::PGEventResultDestroy);
+            event.result_destroy(&borrowed_result(info.result));
+            true
+        }
+    }
+}
+
+fn borrowed_result(result: *mut pq_sys::PGresult) -> std::mem::ManuallyDrop<crate::Result> {
+    // The result is owned by libpq, it must not be cleared.
+    std::mem::ManuallyDrop::new(crate::Result::from(result))
+}
+
+/**
+ * Instance data, as stored by libpq.
+ */
+pub(crate) type InstanceData = std::sync::Arc<dyn std::any::Any + Send + Sync>;
+
+pub(crate) fn into_raw(data: InstanceData) -> *mut libc::c_void {
+    Box::into_raw(Box::new(data)) as *mut libc::c_void
+}
+
+pub(crate) unsafe fn from_raw(ptr: *mut libc::c_void) -> Option<Box<InstanceData>> {
+    if ptr.is_null() {
+        None
+    } else {
+        Some(Box::from_raw(ptr as *mut InstanceData))
+    }
+}
+
+pub(crate) unsafe fn downcast<T>(ptr: *mut libc::c_void) -> Option<std::sync::Arc<T>>
+where
+    T: std::any::Any + Send + Sync,
+{
+    if ptr.is_null() {
+        return None;
+    }
+
+    let data = &*(ptr as *const InstanceData);
+
+    data.clone().downcast().ok()
+}
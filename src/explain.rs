@@ -0,0 +1,176 @@
+/**
+ * Options of the `EXPLAIN` command, see [`Connection::explain`](crate::Connection::explain).
+ */
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Options {
+    /** Executes the statement to show the actual run times and row counts. */
+    pub analyze: bool,
+    /** Includes information on buffer usage, usually with `analyze`. */
+    pub buffers: bool,
+    /** Displays additional information, such as the output columns of each node. */
+    pub verbose: bool,
+    /** Includes information on settings modified from their built-in default values. */
+    pub settings: bool,
+}
+
+impl Options {
+    pub(crate) fn to_sql(self) -> String {
+        let mut options = vec!["FORMAT JSON"];
+
+        if self.analyze {
+            options.push("ANALYZE");
+        }
+
+        if self.buffers {
+            options.push("BUFFERS");
+        }
+
+        if self.verbose {
+            options.push("VERBOSE");
+        }
+
+        if self.settings {
+            options.push("SETTINGS");
+        }
+
+        format!("EXPLAIN ({})", options.join(", "))
+    }
+}
+
+/**
+ * Output of `EXPLAIN (FORMAT JSON)`.
+ */
+#[derive(Clone, Debug, PartialEq, serde::Deserialize)]
+pub struct Output {
+    #[serde(rename = "Plan")]
+    pub plan: Plan,
+    #[serde(rename = "Planning Time")]
+    pub planning_time: Option<f64>,
+    #[serde(rename = "Execution Time")]
+    pub execution_time: Option<f64>,
+    #[serde(rename = "Settings", default)]
+    pub settings: std::collections::BTreeMap<String, String>,
+}
+
+/**
+ * A node of a query plan.
+ *
+ * Fields specific to a node type are kept in `extra`.
+ */
+#[derive(Clone, Debug, PartialEq, serde::Deserialize)]
+pub struct Plan {
+    #[serde(rename = "Node Type")]
+    pub node_type: String,
+    #[serde(rename = "Relation Name")]
+    pub relation_name: Option<String>,
+    #[serde(rename = "Alias")]
+    pub alias: Option<String>,
+    #[serde(rename = "Startup Cost")]
+    pub startup_cost: f64,
+    #[serde(rename = "Total Cost")]
+    pub total_cost: f64,
+    #[serde(rename = "Plan Rows")]
+    pub plan_rows: f64,
+    #[serde(rename = "Plan Width")]
+    pub plan_width: u64,
+    #[serde(rename = "Actual Startup Time")]
+    pub actual_startup_time: Option<f64>,
+    #[serde(rename = "Actual Total Time")]
+    pub actual_total_time: Option<f64>,
+    #[serde(rename = "Actual Rows")]
+    pub actual_rows: Option<f64>,
+    #[serde(rename = "Actual Loops")]
+    pub actual_loops: Option<u64>,
+    #[serde(rename = "Shared Hit Blocks")]
+    pub shared_hit_blocks: Option<u64>,
+    #[serde(rename = "Shared Read Blocks")]
+    pub shared_read_blocks: Option<u64>,
+    #[serde(rename = "Plans", default)]
+    pub plans: Vec<Plan>,
+    #[serde(flatten)]
+    pub extra: std::collections::BTreeMap<String, serde_json::Value>,
+}
+
+impl Plan {
+    /**
+     * Iterates over this node and all its descendants, depth first.
+     */
+    pub fn iter(&self) -> impl Iterator<Item = &Plan> {
+        let mut stack = vec![self];
+
+        std::iter::from_fn(move || {
+            let plan = stack.pop()?;
+            stack.extend(plan.plans.iter().rev());
+
+            Some(plan)
+        })
+    }
+}
+
+impl crate::Connection {
+    /**
+     * Explains the plan of `sql`, with text format parameters.
+     *
+     * With [`Options::analyze`], the statement is executed: wrap it in a transaction rolled back
+     * afterwards to explain a data-modifying statement without side effects.
+     *
+     * See [EXPLAIN](https://www.postgresql.org/docs/current/sql-explain.html).
+     */
+    pub fn explain(
+        &self,
+        sql: &str,
+        params: &[Option<Vec<u8>>],
+        options: Options,
+    ) -> crate::errors::Result<Output> {
+        let query = format!("{} {sql}", options.to_sql());
+        let result = self.check_result(
+            self.exec_params(&query, &[], params, &[], crate::Format::Text),
+            &[crate::Status::TupplesOk],
+            &query,
+        )?;
+
+        let json = result.value(0, 0).unwrap_or_default();
+
+        serde_json::from_slice::<Vec<Output>>(json)
+            .map_err(|err| crate::errors::Error::Backend(err.to_string()))?
+            .pop()
+            .ok_or_else(|| crate::errors::Error::Backend("EXPLAIN returned no plan".to_string()))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #[test]
+    fn options() {
+        let options = crate::explain::Options {
+            analyze: true,
+            buffers: true,
+            ..Default::default()
+        };
+
+        assert_eq!(options.to_sql(), "EXPLAIN (FORMAT JSON, ANALYZE, BUFFERS)");
+    }
+
+    #[test]
+    fn explain() {
+        let conn = crate::test::new_conn();
+
+        let output = conn
+            .explain(
+                "SELECT * FROM generate_series(1, $1::int4) ORDER BY 1",
+                &crate::params![10],
+                crate::explain::Options {
+                    analyze: true,
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+
+        assert_eq!(output.plan.node_type, "Sort");
+        assert!(output.execution_time.is_some());
+        assert!(output
+            .plan
+            .iter()
+            .any(|x| x.node_type == "Function Scan" && x.actual_rows == Some(10.)));
+    }
+}
@@ -4,10 +4,20 @@ pub(crate) fn to_cstr(s: &str) -> std::ffi::CString {
     unsafe { std::ffi::CString::from_vec_unchecked(s.as_bytes().to_vec()) }
 }
 
+/**
+ * Like [`to_cstr`], for strings coming from the user: fails instead of truncating on an interior
+ * nul byte.
+ */
+pub(crate) fn try_to_cstr(s: &str) -> crate::errors::Result<std::ffi::CString> {
+    Ok(std::ffi::CString::new(s)?)
+}
+
 pub(crate) fn to_str(s: *const c_char) -> crate::errors::Result<&'static str> {
     let buffer = unsafe { std::ffi::CStr::from_ptr(s) };
 
-    Ok(buffer.to_str()?)
+    buffer
+        .to_str()
+        .map_err(|_| crate::errors::Error::InvalidUtf8(crate::Encoding::UTF8))
 }
 
 pub(crate) fn to_string(s: *const c_char) -> crate::errors::Result<String> {
@@ -59,13 +69,56 @@ pub(crate) fn new_cstring(size: usize) -> std::ffi::CString {
     unsafe { std::ffi::CString::from_vec_unchecked(vec![0; size]) }
 }
 
-pub(crate) fn vec_to_nta<S: ToString>(v: &[S]) -> (Vec<std::ffi::CString>, Vec<*const c_char>) {
+pub(crate) fn vec_to_nta<S: ToString>(
+    v: &[S],
+) -> crate::errors::Result<(Vec<std::ffi::CString>, Vec<*const c_char>)> {
     let c = v
         .iter()
-        .map(|x| crate::ffi::to_cstr(&x.to_string()))
-        .collect::<Vec<_>>();
+        .map(|x| crate::ffi::try_to_cstr(&x.to_string()))
+        .collect::<crate::errors::Result<Vec<_>>>()?;
     let mut ptr = c.iter().map(|x| x.as_ptr()).collect::<Vec<_>>();
     ptr.push(std::ptr::null());
 
-    (c, ptr)
+    Ok((c, ptr))
+}
+
+/**
+ * Runs `f`, called from libpq, without letting a panic unwind into C.
+ *
+ * A panic is logged and `None` returned. The process is aborted only if the panic can't be
+ * handled, when logging or freeing its payload panics too.
+ */
+pub(crate) fn catch_unwind<T, F: FnOnce() -> T>(callback: &str, f: F) -> Option<T> {
+    let payload = match std::panic::catch_unwind(std::panic::AssertUnwindSafe(f)) {
+        Ok(r) => return Some(r),
+        Err(payload) => payload,
+    };
+
+    let handled = std::panic::catch_unwind(std::panic::AssertUnwindSafe(move || {
+        let message = payload
+            .downcast_ref::<&str>()
+            .copied()
+            .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+            .unwrap_or("Box<dyn Any>");
+
+        log::error!("{callback} panicked: {message}");
+    }));
+
+    if handled.is_err() {
+        std::process::abort();
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod test {
+    #[test]
+    fn catch_unwind() {
+        assert_eq!(super::catch_unwind("test", || 1), Some(1));
+        assert_eq!(
+            super::catch_unwind("test", || -> i32 { panic!("oops") }),
+            None
+        );
+    }
 }
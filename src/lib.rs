@@ -4,31 +4,54 @@
 #[macro_use]
 mod ffi;
 
+pub mod cache;
+#[cfg(feature = "with-postgres-types")]
+pub mod compat;
 pub mod connection;
+pub mod conninfo;
+pub mod encoding;
 pub mod encrypt;
 pub mod errors;
 pub mod escape;
+pub mod event;
+#[cfg(feature = "explain")]
+pub mod explain;
+#[cfg(feature = "metrics")]
+pub mod metrics;
+pub mod notice;
+#[cfg(feature = "v18")]
+pub mod oauth;
+pub mod params;
 pub mod ping;
 pub mod poll;
 #[cfg(unix)]
 pub mod print;
+pub mod replication;
 pub mod result;
+pub mod sql;
+pub mod sqlcommenter;
 pub mod ssl;
 pub mod state;
+pub mod testing;
+#[cfg(feature = "v14")]
+pub mod trace;
 pub mod transaction;
 pub mod types;
 
-mod encoding;
+mod capabilities;
 mod format;
 mod oid;
 mod status;
-#[cfg(feature = "v14")]
-mod trace;
+#[cfg(unix)]
+mod stream;
 mod verbosity;
 
+pub use capabilities::*;
 pub use connection::Connection;
-pub use encoding::*;
+pub use encoding::Encoding;
 pub use format::*;
+#[cfg(feature = "macros")]
+pub use libpq_macros::query;
 pub use oid::*;
 pub use result::Result;
 pub use state::State;
@@ -36,6 +59,16 @@ pub use status::*;
 pub use types::Type;
 pub use verbosity::*;
 
+/**
+ * Returns `true` if libpq was built thread safe, required to use connections from several
+ * threads.
+ *
+ * See [PQisthreadsafe](https://www.postgresql.org/docs/current/libpq-threading.html#LIBPQ-PQISTHREADSAFE).
+ */
+pub fn is_thread_safe() -> bool {
+    unsafe { pq_sys::PQisthreadsafe() != 0 }
+}
+
 /**
  * Get the version of the libpq library in use.
  *
@@ -45,6 +78,21 @@ pub fn version() -> i32 {
     unsafe { pq_sys::PQlibVersion() }
 }
 
+/**
+ * Version of the libpq library, encoded like the server versions.
+ */
+pub type LibVersion = connection::ServerVersion;
+
+/**
+ * Like [`version`], decoded.
+ *
+ * This is the libpq the program is running with, the server may have another version, see
+ * [`Connection::server_version_info`].
+ */
+pub fn lib_version() -> LibVersion {
+    version().into()
+}
+
 #[cfg(test)]
 mod test {
     static INIT: std::sync::Once = std::sync::Once::new();
@@ -61,8 +109,36 @@ mod test {
         crate::Connection::new(&dsn()).unwrap()
     }
 
+    /**
+     * Directory of the Unix-domain socket of the server, which must run on this host.
+     */
+    #[cfg(unix)]
+    pub fn socket_dir() -> String {
+        let directories = new_conn().show("unix_socket_directories").unwrap();
+
+        directories
+            .split(',')
+            .map(str::trim)
+            .find(|x| !x.starts_with('@'))
+            .unwrap()
+            .to_string()
+    }
+
     #[test]
     fn version() {
         assert!(crate::version() > 0);
     }
+
+    #[test]
+    fn lib_version() {
+        let version = crate::lib_version();
+
+        assert!(version.major >= 10);
+        assert_eq!(version, crate::version().into());
+    }
+
+    #[test]
+    fn is_thread_safe() {
+        assert!(crate::is_thread_safe());
+    }
 }
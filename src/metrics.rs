@@ -0,0 +1,61 @@
+/** Number of executed queries, labelled by `operation`. */
+pub const QUERIES: &str = "libpq_queries_total";
+/** Number of failed queries, labelled by `operation` and SQLSTATE `class`. */
+pub const ERRORS: &str = "libpq_query_errors_total";
+/** Query latency in seconds, labelled by `operation`. */
+pub const QUERY_DURATION: &str = "libpq_query_duration_seconds";
+/** Number of bytes sent or received with `COPY`, labelled by `operation`. */
+pub const COPY_BYTES: &str = "libpq_copy_bytes_total";
+/** Number of open connections. */
+pub const CONNECTIONS: &str = "libpq_connections_active";
+
+/**
+ * Registers the description and unit of the metrics with the installed recorder.
+ */
+pub fn describe() {
+    metrics::describe_counter!(QUERIES, metrics::Unit::Count, "Number of executed queries");
+    metrics::describe_counter!(ERRORS, metrics::Unit::Count, "Number of failed queries");
+    metrics::describe_histogram!(QUERY_DURATION, metrics::Unit::Seconds, "Query latency");
+    metrics::describe_counter!(
+        COPY_BYTES,
+        metrics::Unit::Bytes,
+        "Number of bytes transferred with COPY"
+    );
+    metrics::describe_gauge!(
+        CONNECTIONS,
+        metrics::Unit::Count,
+        "Number of open connections"
+    );
+}
+
+pub(crate) fn query(
+    operation: &'static str,
+    duration: std::time::Duration,
+    error: Option<Option<String>>,
+) {
+    metrics::counter!(QUERIES, "operation" => operation).increment(1);
+    metrics::histogram!(QUERY_DURATION, "operation" => operation).record(duration.as_secs_f64());
+
+    if let Some(sqlstate) = error {
+        // The class is the first two characters of the SQLSTATE code.
+        let class = sqlstate
+            .as_deref()
+            .and_then(|x| x.get(..2))
+            .unwrap_or("unknown")
+            .to_string();
+
+        metrics::counter!(ERRORS, "operation" => operation, "class" => class).increment(1);
+    }
+}
+
+pub(crate) fn copy(operation: &'static str, bytes: usize) {
+    metrics::counter!(COPY_BYTES, "operation" => operation).increment(bytes as u64);
+}
+
+pub(crate) fn connection_opened() {
+    metrics::gauge!(CONNECTIONS).increment(1.);
+}
+
+pub(crate) fn connection_closed() {
+    metrics::gauge!(CONNECTIONS).decrement(1.);
+}
@@ -0,0 +1,186 @@
+/**
+ * Severity of a notice, as sent by the server.
+ */
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Severity {
+    Debug,
+    Log,
+    Info,
+    Notice,
+    Warning,
+    Error,
+    Fatal,
+    Panic,
+}
+
+impl Severity {
+    fn parse(severity: &str) -> Option<Self> {
+        let severity = match severity {
+            "DEBUG" => Self::Debug,
+            "LOG" => Self::Log,
+            "INFO" => Self::Info,
+            "NOTICE" => Self::Notice,
+            "WARNING" => Self::Warning,
+            "ERROR" => Self::Error,
+            "FATAL" => Self::Fatal,
+            "PANIC" => Self::Panic,
+            _ => return None,
+        };
+
+        Some(severity)
+    }
+
+    /**
+     * The corresponding [`log::Level`].
+     */
+    pub fn level(&self) -> log::Level {
+        match self {
+            Self::Debug => log::Level::Debug,
+            Self::Log | Self::Info | Self::Notice => log::Level::Info,
+            Self::Warning => log::Level::Warn,
+            Self::Error | Self::Fatal | Self::Panic => log::Level::Error,
+        }
+    }
+}
+
+impl std::fmt::Display for Severity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            Self::Debug => "DEBUG",
+            Self::Log => "LOG",
+            Self::Info => "INFO",
+            Self::Notice => "NOTICE",
+            Self::Warning => "WARNING",
+            Self::Error => "ERROR",
+            Self::Fatal => "FATAL",
+            Self::Panic => "PANIC",
+        };
+
+        f.write_str(s)
+    }
+}
+
+/**
+ * The fields of a notice result.
+ */
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Notice {
+    pub severity: Severity,
+    pub sqlstate: Option<String>,
+    pub message: String,
+    pub detail: Option<String>,
+    pub hint: Option<String>,
+    pub source_file: Option<String>,
+    pub source_line: Option<u32>,
+    pub source_function: Option<String>,
+}
+
+impl From<&crate::Result> for Notice {
+    fn from(result: &crate::Result) -> Self {
+        use crate::result::ErrorField;
+
+        let field = |field| result.error_field(field).ok().flatten().map(String::from);
+
+        // The non localized severity is only sent by 9.6+ servers.
+        let severity = field(ErrorField::SeverityNonlocalized)
+            .or_else(|| field(ErrorField::Severity))
+            .and_then(|x| Severity::parse(&x))
+            .unwrap_or(Severity::Notice);
+
+        Self {
+            severity,
+            sqlstate: field(ErrorField::Sqlstate),
+            message: field(ErrorField::MessagePrimary).unwrap_or_default(),
+            detail: field(ErrorField::MessageDetail),
+            hint: field(ErrorField::MessageHint),
+            source_file: field(ErrorField::SourceFile),
+            source_line: field(ErrorField::SourceLine).and_then(|x| x.parse().ok()),
+            source_function: field(ErrorField::SourceFunction),
+        }
+    }
+}
+
+/**
+ * Logs the notice with the [`log`] crate, under the `libpq::notice` target.
+ *
+ * ```no_run
+ * let conn = libpq::Connection::new("host=localhost").unwrap();
+ * conn.set_notice_receiver(libpq::notice::log);
+ * ```
+ */
+pub fn log(result: &crate::Result) {
+    let notice = Notice::from(result);
+
+    log::log!(
+        target: "libpq::notice",
+        notice.severity.level(),
+        sqlstate = notice.sqlstate.as_deref(),
+        detail = notice.detail.as_deref(),
+        hint = notice.hint.as_deref(),
+        source_file = notice.source_file.as_deref(),
+        source_line = notice.source_line,
+        source_function = notice.source_function.as_deref();
+        "{}: {}",
+        notice.severity,
+        notice.message
+    );
+}
+
+/**
+ * Emits the notice as a [`tracing`] event, under the `libpq::notice` target.
+ */
+#[cfg(feature = "tracing")]
+pub fn tracing(result: &crate::Result) {
+    let notice = Notice::from(result);
+
+    macro_rules! event {
+        ($level:expr) => {
+            tracing::event!(
+                target: "libpq::notice",
+                $level,
+                severity = %notice.severity,
+                sqlstate = notice.sqlstate.as_deref(),
+                detail = notice.detail.as_deref(),
+                hint = notice.hint.as_deref(),
+                source_file = notice.source_file.as_deref(),
+                source_line = notice.source_line,
+                source_function = notice.source_function.as_deref(),
+                "{}",
+                notice.message
+            )
+        };
+    }
+
+    match notice.severity.level() {
+        log::Level::Error => event!(tracing::Level::ERROR),
+        log::Level::Warn => event!(tracing::Level::WARN),
+        log::Level::Info => event!(tracing::Level::INFO),
+        log::Level::Debug => event!(tracing::Level::DEBUG),
+        log::Level::Trace => event!(tracing::Level::TRACE),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #[test]
+    fn notice() {
+        let conn = crate::test::new_conn();
+        let notices = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+
+        let n = notices.clone();
+        conn.set_notice_receiver(move |result| {
+            n.lock().unwrap().push(crate::notice::Notice::from(result));
+            crate::notice::log(result);
+        });
+        conn.exec("DO $$ BEGIN RAISE WARNING 'foo' USING HINT = 'bar'; END $$");
+
+        let notices = notices.lock().unwrap();
+        assert_eq!(notices.len(), 1);
+        assert_eq!(notices[0].severity, crate::notice::Severity::Warning);
+        assert_eq!(notices[0].severity.level(), log::Level::Warn);
+        assert_eq!(notices[0].sqlstate.as_deref(), Some("01000"));
+        assert_eq!(notices[0].message, "foo");
+        assert_eq!(notices[0].hint.as_deref(), Some("bar"));
+        assert!(notices[0].source_line.is_some());
+    }
+}
@@ -0,0 +1,200 @@
+/**
+ * Bearer token request sent to the hook set by [`set_token_hook`].
+ */
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TokenRequest {
+    /** OpenID discovery URL of the issuer. */
+    pub openid_configuration: String,
+    /** Scope requested by the server, if any. */
+    pub scope: Option<String>,
+}
+
+/**
+ * Device authorization prompt, see [`set_device_prompt_hook`].
+ */
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DevicePrompt {
+    /** URL the user has to visit. */
+    pub verification_uri: String,
+    /** Code the user has to enter at `verification_uri`. */
+    pub user_code: String,
+    /** URL including the user code, if provided by the issuer. */
+    pub verification_uri_complete: Option<String>,
+    /** Number of seconds before the user code expires. */
+    pub expires_in: i32,
+}
+
+type TokenHook = dyn Fn(&crate::Connection, &TokenRequest) -> Option<String> + Send + Sync;
+type DevicePromptHook = dyn Fn(&crate::Connection, &DevicePrompt) + Send + Sync;
+
+struct Hooks {
+    token: Option<std::sync::Arc<TokenHook>>,
+    device_prompt: Option<std::sync::Arc<DevicePromptHook>>,
+}
+
+static HOOKS: std::sync::RwLock<Hooks> = std::sync::RwLock::new(Hooks {
+    token: None,
+    device_prompt: None,
+});
+
+/**
+ * Sets a closure providing OAuth bearer tokens, for example from a cloud IAM SDK, instead of the
+ * builtin device authorization flow.
+ *
+ * Returning `None` falls back to the builtin flow. The hook is global to the process.
+ *
+ * See [PQsetAuthDataHook](https://www.postgresql.org/docs/current/libpq-oauth.html#LIBPQ-OAUTH-AUTHDATA-HOOKS).
+ */
+pub fn set_token_hook<F>(hook: F)
+where
+    F: Fn(&crate::Connection, &TokenRequest) -> Option<String> + Send + Sync + 'static,
+{
+    HOOKS.write().unwrap_or_else(|e| e.into_inner()).token = Some(std::sync::Arc::new(hook));
+    install();
+}
+
+/**
+ * Removes the closure set by [`set_token_hook`].
+ */
+pub fn unset_token_hook() {
+    HOOKS.write().unwrap_or_else(|e| e.into_inner()).token = None;
+    install();
+}
+
+/**
+ * Sets a closure displaying the device authorization prompt of the builtin flow, instead of
+ * printing it on the standard error.
+ *
+ * The hook is global to the process.
+ *
+ * See [PQsetAuthDataHook](https://www.postgresql.org/docs/current/libpq-oauth.html#LIBPQ-OAUTH-AUTHDATA-HOOKS).
+ */
+pub fn set_device_prompt_hook<F>(hook: F)
+where
+    F: Fn(&crate::Connection, &DevicePrompt) + Send + Sync + 'static,
+{
+    HOOKS
+        .write()
+        .unwrap_or_else(|e| e.into_inner())
+        .device_prompt = Some(std::sync::Arc::new(hook));
+    install();
+}
+
+/**
+ * Removes the closure set by [`set_device_prompt_hook`].
+ */
+pub fn unset_device_prompt_hook() {
+    HOOKS
+        .write()
+        .unwrap_or_else(|e| e.into_inner())
+        .device_prompt = None;
+    install();
+}
+
+fn install() {
+    let hooks = HOOKS.read().unwrap_or_else(|e| e.into_inner());
+
+    let hook: pq_sys::PQauthDataHook_type =
+        if hooks.token.is_some() || hooks.device_prompt.is_some() {
+            Some(auth_data_hook_trampoline)
+        } else {
+            // Restores the default hook.
+            None
+        };
+
+    unsafe {
+        pq_sys::PQsetAuthDataHook(hook);
+    }
+}
+
+unsafe extern "C" fn auth_data_hook_trampoline(
+    type_: pq_sys::PGauthData,
+    conn: *mut pq_sys::PGconn,
+    data: *mut libc::c_void,
+) -> libc::c_int {
+    let r = crate::ffi::catch_unwind("OAuth hook", || match type_ {
+        pq_sys::PGauthData::PQAUTHDATA_OAUTH_BEARER_TOKEN => {
+            token(conn, data as *mut pq_sys::PGoauthBearerRequest)
+        }
+        pq_sys::PGauthData::PQAUTHDATA_PROMPT_OAUTH_DEVICE => {
+            device_prompt(conn, data as *const pq_sys::PGpromptOAuthDevice)
+        }
+        _ => None,
+    });
+
+    match r {
+        Some(Some(handled)) => handled,
+        Some(None) => pq_sys::PQdefaultAuthDataHook(type_, conn, data),
+        None => -1,
+    }
+}
+
+unsafe fn token(
+    conn: *mut pq_sys::PGconn,
+    request: *mut pq_sys::PGoauthBearerRequest,
+) -> Option<libc::c_int> {
+    // Don't call the user closure with the lock held.
+    let hook = HOOKS
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .token
+        .clone()?;
+
+    let token_request = TokenRequest {
+        openid_configuration: crate::ffi::to_string((*request).openid_configuration).ok()?,
+        scope: crate::ffi::to_option_string((*request).scope)
+            .ok()
+            .flatten(),
+    };
+
+    let token = crate::Connection::with_raw(conn, |conn| hook(conn, &token_request))?;
+
+    let token = match std::ffi::CString::new(token) {
+        Ok(token) => token,
+        Err(_) => {
+            log::error!("OAuth token contains a nul byte");
+            return Some(-1);
+        }
+    };
+
+    (*request).token = token.into_raw();
+    (*request).cleanup = Some(token_cleanup);
+
+    Some(1)
+}
+
+unsafe extern "C" fn token_cleanup(
+    _: *mut pq_sys::PGconn,
+    request: *mut pq_sys::PGoauthBearerRequest,
+) {
+    if !(*request).token.is_null() {
+        drop(std::ffi::CString::from_raw((*request).token));
+        (*request).token = std::ptr::null_mut();
+    }
+}
+
+unsafe fn device_prompt(
+    conn: *mut pq_sys::PGconn,
+    prompt: *const pq_sys::PGpromptOAuthDevice,
+) -> Option<libc::c_int> {
+    let hook = HOOKS
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .device_prompt
+        .clone()?;
+
+    let prompt = DevicePrompt {
+        verification_uri: crate::ffi::to_string((*prompt).verification_uri).ok()?,
+        user_code: crate::ffi::to_string((*prompt).user_code).ok()?,
+        verification_uri_complete: crate::ffi::to_option_string(
+            (*prompt).verification_uri_complete,
+        )
+        .ok()
+        .flatten(),
+        expires_in: (*prompt).expires_in,
+    };
+
+    crate::Connection::with_raw(conn, |conn| hook(conn, &prompt));
+
+    Some(1)
+}
@@ -0,0 +1,108 @@
+/**
+ * Builds the parameter values of [`Connection::exec_params`](crate::Connection::exec_params) and
+ * similar functions from heterogeneous values, in text format.
+ *
+ * ```no_run
+ * # let conn = libpq::Connection::new("")?;
+ * let (id, name) = (1, "foo");
+ *
+ * conn.exec_params(
+ *     "SELECT $1::int4, $2::text, $3::bool",
+ *     &[],
+ *     &libpq::params![id, name, None::<bool>],
+ *     &[],
+ *     libpq::Format::Text,
+ * );
+ * # Ok::<(), libpq::errors::Error>(())
+ * ```
+ */
+#[macro_export]
+macro_rules! params {
+    () => {
+        ::std::vec::Vec::<::std::option::Option<::std::vec::Vec<u8>>>::new()
+    };
+    ($($param:expr),+ $(,)?) => {
+        ::std::vec![$($crate::params::ToParam::to_param(&$param)),+]
+    };
+}
+
+/**
+ * A value usable as a text format parameter, see [`params!`](crate::params!).
+ */
+pub trait ToParam {
+    /**
+     * Returns the text representation of the value, nul terminated as expected by libpq, `None`
+     * for SQL `NULL`.
+     */
+    fn to_param(&self) -> Option<Vec<u8>>;
+}
+
+macro_rules! to_string {
+    ($($ty:ty),+) => {
+        $(
+            impl ToParam for $ty {
+                fn to_param(&self) -> Option<Vec<u8>> {
+                    Some(format!("{self}\0").into_bytes())
+                }
+            }
+        )+
+    };
+}
+
+to_string!(i8, i16, i32, i64, u8, u16, u32, u64, f32, f64, char, str, String);
+
+impl ToParam for bool {
+    fn to_param(&self) -> Option<Vec<u8>> {
+        Some(if *self { b"t\0" } else { b"f\0" }.to_vec())
+    }
+}
+
+impl<T: ToParam> ToParam for Option<T> {
+    fn to_param(&self) -> Option<Vec<u8>> {
+        self.as_ref().and_then(ToParam::to_param)
+    }
+}
+
+impl<T: ToParam + ?Sized> ToParam for &T {
+    fn to_param(&self) -> Option<Vec<u8>> {
+        (*self).to_param()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #[test]
+    fn params() {
+        let name = String::from("foo");
+        let params = crate::params![1, "bar", &name, true, None::<i32>, 1.5];
+
+        assert_eq!(
+            params,
+            vec![
+                Some(b"1\0".to_vec()),
+                Some(b"bar\0".to_vec()),
+                Some(b"foo\0".to_vec()),
+                Some(b"t\0".to_vec()),
+                None,
+                Some(b"1.5\0".to_vec()),
+            ]
+        );
+        assert!(crate::params![].is_empty());
+    }
+
+    #[test]
+    fn exec_params() {
+        let conn = crate::test::new_conn();
+
+        let result = conn.exec_params(
+            "SELECT $1::int4 + 1, $2::text",
+            &[],
+            &crate::params![41, "foo"],
+            &[],
+            crate::Format::Text,
+        );
+
+        assert_eq!(result.value(0, 0), Some(&b"42"[..]));
+        assert_eq!(result.value(0, 1), Some(&b"foo"[..]));
+    }
+}
@@ -1,23 +1,82 @@
+/**
+ * Options of [`Result::print`](crate::Result::print).
+ *
+ * See [PQprint](https://www.postgresql.org/docs/current/libpq-exec.html#LIBPQ-PQPRINT).
+ */
 #[derive(Clone, Debug)]
 pub struct Options {
-    /* print output field headings and row count */
+    /** Print output field headings and row count. */
     pub header: bool,
-    /* fill align the fields */
+    /** Fill align the fields. */
     pub align: bool,
-    /* old brain dead format */
+    /** Old brain dead format. */
     pub standard: bool,
-    /* output HTML tables */
+    /** Output HTML tables. */
     pub html3: bool,
-    /* expand tables */
+    /** Expand tables. */
     pub expanded: bool,
-    /* use pager for output if needed */
+    /** Use pager for output if needed. */
     pub pager: bool,
-    /* field separator */
+    /** Field separator. */
     pub field_sep: String,
-    /* attributes for HTML table element */
+    /** Attributes for HTML table element. */
     pub table_opt: String,
-    /* HTML table caption */
+    /** HTML table caption. */
     pub caption: String,
-    /* null-terminated array of replacement field names */
+    /** Replacement field names. */
     pub field_name: Vec<String>,
 }
+
+impl Default for Options {
+    /**
+     * Aligned fields separated by `|`, with headings, like `psql`.
+     */
+    fn default() -> Self {
+        Self {
+            header: true,
+            align: true,
+            standard: false,
+            html3: false,
+            expanded: false,
+            pager: false,
+            field_sep: "|".to_string(),
+            table_opt: String::new(),
+            caption: String::new(),
+            field_name: Vec::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #[test]
+    fn print() {
+        let conn = crate::test::new_conn();
+        let result = conn.exec("SELECT 1 AS one, 'foo' AS two");
+
+        let mut output = Vec::new();
+        result
+            .print(&mut output, &crate::print::Options::default())
+            .unwrap();
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.contains("one|two"));
+        assert!(output.contains("(1 row)"));
+
+        let options = crate::print::Options {
+            html3: true,
+            caption: "numbers".to_string(),
+            ..Default::default()
+        };
+        let mut output = Vec::new();
+        result.print(&mut output, &options).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.contains("<table"));
+        assert!(output.contains("numbers"));
+
+        let options = crate::print::Options {
+            field_sep: "\0".to_string(),
+            ..Default::default()
+        };
+        assert!(result.print(&mut Vec::new(), &options).is_err());
+    }
+}
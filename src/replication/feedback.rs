@@ -0,0 +1,153 @@
+/**
+ * Standby status update, reporting the WAL locations processed by the client.
+ *
+ * The streams send them by themselves, it's only needed to drive a
+ * [`CopyBoth`](crate::connection::CopyBoth) by hand.
+ *
+ * See [Standby status update](https://www.postgresql.org/docs/current/protocol-replication.html#PROTOCOL-REPLICATION-STANDBY-STATUS-UPDATE).
+ */
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct StandbyStatusUpdate {
+    /** Location of the last WAL byte received and written to disk, plus one. */
+    pub write: super::Lsn,
+    /** Location of the last WAL byte flushed to disk, plus one. */
+    pub flush: super::Lsn,
+    /** Location of the last WAL byte applied, plus one. */
+    pub apply: super::Lsn,
+    /** Sending time, now if `None`. */
+    pub time: Option<std::time::SystemTime>,
+    /** Asks the server to reply immediately with a keepalive. */
+    pub reply: bool,
+}
+
+impl StandbyStatusUpdate {
+    pub fn new(write: super::Lsn, flush: super::Lsn, apply: super::Lsn) -> Self {
+        Self {
+            write,
+            flush,
+            apply,
+            ..Default::default()
+        }
+    }
+
+    pub fn time(mut self, time: std::time::SystemTime) -> Self {
+        self.time = Some(time);
+        self
+    }
+
+    pub fn reply(mut self, reply: bool) -> Self {
+        self.reply = reply;
+        self
+    }
+
+    /**
+     * Returns the message, to send with [`CopyWriter::write`](crate::connection::CopyWriter::write).
+     */
+    pub fn encode(&self) -> Vec<u8> {
+        let mut message = Vec::with_capacity(34);
+        message.push(b'r');
+        message.extend_from_slice(&self.write.0.to_be_bytes());
+        message.extend_from_slice(&self.flush.0.to_be_bytes());
+        message.extend_from_slice(&self.apply.0.to_be_bytes());
+        message.extend_from_slice(&timestamp(self.time).to_be_bytes());
+        message.push(self.reply as u8);
+
+        message
+    }
+}
+
+/**
+ * Hot standby feedback, reporting the oldest transactions still needed by the queries of a
+ * standby, so the primary keeps the rows they may see.
+ *
+ * A transaction ID of 0 means no transaction is needed.
+ *
+ * See [Hot standby feedback](https://www.postgresql.org/docs/current/protocol-replication.html#PROTOCOL-REPLICATION-HOT-STANDBY-FEEDBACK-MESSAGE).
+ */
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct HotStandbyFeedback {
+    /** Sending time, now if `None`. */
+    pub time: Option<std::time::SystemTime>,
+    /** Oldest `xmin` of the standby. */
+    pub xmin: u32,
+    /** Epoch of `xmin`. */
+    pub xmin_epoch: u32,
+    /** Oldest `catalog_xmin` of the replication slots of the standby. */
+    pub catalog_xmin: u32,
+    /** Epoch of `catalog_xmin`. */
+    pub catalog_xmin_epoch: u32,
+}
+
+impl HotStandbyFeedback {
+    pub fn new(xmin: u32, xmin_epoch: u32) -> Self {
+        Self {
+            xmin,
+            xmin_epoch,
+            ..Default::default()
+        }
+    }
+
+    pub fn time(mut self, time: std::time::SystemTime) -> Self {
+        self.time = Some(time);
+        self
+    }
+
+    pub fn catalog_xmin(mut self, xmin: u32, epoch: u32) -> Self {
+        self.catalog_xmin = xmin;
+        self.catalog_xmin_epoch = epoch;
+        self
+    }
+
+    /**
+     * Returns the message, to send with [`CopyWriter::write`](crate::connection::CopyWriter::write).
+     */
+    pub fn encode(&self) -> Vec<u8> {
+        let mut message = Vec::with_capacity(25);
+        message.push(b'h');
+        message.extend_from_slice(&timestamp(self.time).to_be_bytes());
+        message.extend_from_slice(&self.xmin.to_be_bytes());
+        message.extend_from_slice(&self.xmin_epoch.to_be_bytes());
+        message.extend_from_slice(&self.catalog_xmin.to_be_bytes());
+        message.extend_from_slice(&self.catalog_xmin_epoch.to_be_bytes());
+
+        message
+    }
+}
+
+fn timestamp(time: Option<std::time::SystemTime>) -> i64 {
+    super::protocol::to_timestamp(time.unwrap_or_else(std::time::SystemTime::now))
+}
+
+#[cfg(test)]
+mod test {
+    #[test]
+    fn encode() {
+        let epoch = std::time::UNIX_EPOCH + std::time::Duration::from_secs(946_684_800);
+
+        let update = super::StandbyStatusUpdate::new(
+            crate::replication::Lsn(1),
+            crate::replication::Lsn(2),
+            crate::replication::Lsn(3),
+        )
+        .time(epoch)
+        .reply(true);
+
+        let mut expected = vec![b'r'];
+        for x in [1_u64, 2, 3, 0] {
+            expected.extend_from_slice(&x.to_be_bytes());
+        }
+        expected.push(1);
+        assert_eq!(update.encode(), expected);
+
+        let feedback = super::HotStandbyFeedback::new(42, 1)
+            .catalog_xmin(40, 1)
+            .time(epoch);
+
+        let mut expected = vec![b'h'];
+        expected.extend_from_slice(&0_i64.to_be_bytes());
+        for x in [42_u32, 1, 40, 1] {
+            expected.extend_from_slice(&x.to_be_bytes());
+        }
+        assert_eq!(feedback.encode(), expected);
+    }
+}
@@ -0,0 +1,225 @@
+/**
+ * Change decoded from a logical replication stream, see [`LogicalStream`].
+ */
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Change {
+    /** WAL location of the change. */
+    pub lsn: super::Lsn,
+    pub message: super::pgoutput::Message,
+}
+
+/**
+ * Logical replication stream decoded by the `pgoutput` plugin.
+ *
+ * ```no_run
+ * use libpq::replication::pgoutput::Message;
+ *
+ * # let conn = libpq::Connection::new("replication=database")?;
+ * let mut stream = libpq::replication::LogicalStream::start(
+ *     &conn,
+ *     "slot",
+ *     &["publication"],
+ *     libpq::replication::Lsn::INVALID,
+ * )?;
+ *
+ * while let Some(change) = stream.next_change()? {
+ *     if let Message::Commit(commit) = change.message {
+ *         stream.flush(commit.end_lsn);
+ *     }
+ * }
+ * # Ok::<(), libpq::errors::Error>(())
+ * ```
+ *
+ * Keepalives are answered and the position is reported every [`STATUS_INTERVAL`](super::STATUS_INTERVAL) while reading.
+ * The slot only advances up to the position confirmed with [`LogicalStream::flush`].
+ */
+#[derive(Debug)]
+pub struct LogicalStream<'conn> {
+    reader: crate::connection::CopyReader<'conn>,
+    writer: crate::connection::CopyWriter<'conn>,
+    feedback: super::protocol::Feedback,
+}
+
+impl<'conn> LogicalStream<'conn> {
+    /**
+     * Starts streaming the changes of `publications` from `slot`, created with the `pgoutput`
+     * plugin.
+     */
+    pub fn start(
+        conn: &'conn crate::Connection,
+        slot: &str,
+        publications: &[&str],
+        lsn: super::Lsn,
+    ) -> crate::errors::Result<Self> {
+        let publications = publications
+            .iter()
+            .map(|x| Ok(conn.escape_identifier(x)?.to_str()?.to_string()))
+            .collect::<crate::errors::Result<Vec<_>>>()?
+            .join(",");
+
+        Self::start_plugin(
+            conn,
+            slot,
+            lsn,
+            &[
+                ("proto_version", "1"),
+                ("publication_names", publications.as_str()),
+            ],
+        )
+    }
+
+    /**
+     * Starts streaming from `slot`, whatever its output plugin, with the plugin `options`.
+     */
+    pub(crate) fn start_plugin(
+        conn: &'conn crate::Connection,
+        slot: &str,
+        lsn: super::Lsn,
+        options: &[(&str, &str)],
+    ) -> crate::errors::Result<Self> {
+        let (reader, writer) = conn
+            .start_replication(&super::Start::Logical { slot, lsn, options })?
+            .split();
+
+        let mut feedback = super::protocol::Feedback::new(super::STATUS_INTERVAL);
+        feedback.write = lsn;
+        feedback.flush = lsn;
+        feedback.apply = lsn;
+
+        Ok(Self {
+            reader,
+            writer,
+            feedback,
+        })
+    }
+
+    /**
+     * Waits for the next change, `None` once the server ended the stream.
+     */
+    pub fn next_change(&mut self) -> crate::errors::Result<Option<Change>> {
+        let change = self
+            .next_with(super::pgoutput::Message::parse)?
+            .map(|(lsn, message)| Change { lsn, message });
+
+        Ok(change)
+    }
+
+    /**
+     * Waits for the next message and decodes it with `decode`.
+     */
+    pub(crate) fn next_with<T, F>(
+        &mut self,
+        decode: F,
+    ) -> crate::errors::Result<Option<(super::Lsn, T)>>
+    where
+        F: FnOnce(&[u8]) -> crate::errors::Result<T>,
+    {
+        loop {
+            let data = match self.reader.read()? {
+                Some(data) => data,
+                None => return Ok(None),
+            };
+
+            match super::protocol::Message::parse(&data)? {
+                super::protocol::Message::XLogData { start, data, .. } => {
+                    self.feedback.write = self.feedback.write.max(start);
+                    self.feedback.send_if_due(&mut self.writer, false)?;
+
+                    return Ok(Some((start, decode(data)?)));
+                }
+                super::protocol::Message::Keepalive { end, reply, .. } => {
+                    self.feedback.write = self.feedback.write.max(end);
+                    self.feedback.send_if_due(&mut self.writer, reply)?;
+                }
+            }
+        }
+    }
+
+    /**
+     * Confirms the changes up to `lsn` are processed, the server may then discard them.
+     *
+     * The position is sent with the next status update.
+     */
+    pub fn flush(&mut self, lsn: super::Lsn) {
+        self.feedback.flush = self.feedback.flush.max(lsn);
+        self.feedback.apply = self.feedback.flush;
+    }
+
+    /**
+     * Sends a status update now, instead of waiting for the next one.
+     */
+    pub fn send_feedback(&mut self) -> crate::errors::Result {
+        self.feedback.send(&mut self.writer, false)
+    }
+
+    /**
+     * Ends the stream, the connection can then run other replication commands.
+     */
+    pub fn stop(mut self) -> crate::errors::Result {
+        self.writer.finish()?;
+
+        while self.reader.read()?.is_some() {}
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #[test]
+    fn logical_stream() {
+        let conn = crate::test::new_conn();
+        conn.exec("CREATE TABLE IF NOT EXISTS logical_stream (id int4 PRIMARY KEY)");
+        conn.exec("DROP PUBLICATION IF EXISTS logical_stream");
+        conn.exec("CREATE PUBLICATION logical_stream FOR TABLE logical_stream");
+
+        let replication = crate::connection::Builder::parse(&crate::test::dsn())
+            .unwrap()
+            .replication(crate::connection::Replication::Database)
+            .connect()
+            .unwrap();
+        let slot = replication
+            .create_replication_slot(
+                "libpq_logical_stream",
+                &crate::replication::SlotKind::Logical {
+                    plugin: "pgoutput".to_string(),
+                },
+                true,
+            )
+            .unwrap();
+
+        conn.exec("INSERT INTO logical_stream VALUES (1)");
+
+        let mut stream = super::LogicalStream::start(
+            &replication,
+            &slot.name,
+            &["logical_stream"],
+            crate::replication::Lsn::INVALID,
+        )
+        .unwrap();
+
+        let mut inserted = None;
+
+        while let Some(change) = stream.next_change().unwrap() {
+            match change.message {
+                crate::replication::pgoutput::Message::Insert(insert) => inserted = Some(insert),
+                crate::replication::pgoutput::Message::Commit(commit) => {
+                    stream.flush(commit.end_lsn);
+                    break;
+                }
+                _ => (),
+            }
+        }
+
+        assert_eq!(
+            inserted.map(|x| x.new),
+            Some(vec![crate::replication::pgoutput::Value::Text(
+                b"1".to_vec()
+            )])
+        );
+
+        stream.stop().unwrap();
+        conn.exec("DROP PUBLICATION logical_stream");
+        conn.exec("DROP TABLE logical_stream");
+    }
+}
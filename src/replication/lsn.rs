@@ -0,0 +1,92 @@
+/**
+ * Location in the write-ahead log, displayed as `XXX/XXX` by the server.
+ */
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Lsn(pub u64);
+
+impl Lsn {
+    pub const INVALID: Self = Self(0);
+}
+
+impl From<u64> for Lsn {
+    fn from(lsn: u64) -> Self {
+        Self(lsn)
+    }
+}
+
+impl From<Lsn> for u64 {
+    fn from(lsn: Lsn) -> Self {
+        lsn.0
+    }
+}
+
+impl std::ops::Add<u64> for Lsn {
+    type Output = Self;
+
+    fn add(self, bytes: u64) -> Self {
+        Self(self.0 + bytes)
+    }
+}
+
+impl std::ops::AddAssign<u64> for Lsn {
+    fn add_assign(&mut self, bytes: u64) {
+        self.0 += bytes;
+    }
+}
+
+impl std::ops::Sub<u64> for Lsn {
+    type Output = Self;
+
+    fn sub(self, bytes: u64) -> Self {
+        Self(self.0 - bytes)
+    }
+}
+
+/**
+ * Number of bytes of WAL between two locations.
+ */
+impl std::ops::Sub for Lsn {
+    type Output = u64;
+
+    fn sub(self, other: Self) -> u64 {
+        self.0 - other.0
+    }
+}
+
+impl std::fmt::Display for Lsn {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:X}/{:X}", self.0 >> 32, self.0 & 0xFFFF_FFFF)
+    }
+}
+
+impl std::str::FromStr for Lsn {
+    type Err = crate::errors::Error;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let (high, low) = s
+            .split_once('/')
+            .ok_or_else(|| crate::errors::Error::Backend(format!("invalid LSN: \"{s}\"")))?;
+
+        let high = u32::from_str_radix(high, 16)?;
+        let low = u32::from_str_radix(low, 16)?;
+
+        Ok(Self(((high as u64) << 32) | low as u64))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #[test]
+    fn lsn() {
+        let lsn = "16/B374D848".parse::<super::Lsn>().unwrap();
+
+        assert_eq!(lsn, super::Lsn(0x16_B374_D848));
+        assert_eq!(lsn.to_string(), "16/B374D848");
+        assert_eq!(super::Lsn::INVALID.to_string(), "0/0");
+        assert!("16B374D848".parse::<super::Lsn>().is_err());
+
+        assert_eq!(lsn + 0x100, super::Lsn(0x16_B374_D948));
+        assert_eq!((lsn + 0x100) - lsn, 0x100);
+        assert!(lsn < lsn + 1);
+    }
+}
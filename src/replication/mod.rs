@@ -0,0 +1,322 @@
+/*!
+ * Helpers for the [streaming replication protocol](https://www.postgresql.org/docs/current/protocol-replication.html).
+ *
+ * The connection must be opened in replication mode, see
+ * [`Builder::replication`](crate::connection::Builder::replication).
+ */
+
+mod feedback;
+mod logical;
+mod lsn;
+pub mod pgoutput;
+mod physical;
+mod protocol;
+mod slot;
+#[cfg(feature = "serde_json")]
+mod wal2json;
+
+pub use feedback::*;
+pub use logical::*;
+pub use lsn::*;
+pub use physical::*;
+pub use slot::*;
+#[cfg(feature = "serde_json")]
+pub use wal2json::*;
+
+/**
+ * Interval of the standby status updates sent by the streams.
+ */
+pub const STATUS_INTERVAL: std::time::Duration = std::time::Duration::from_secs(10);
+
+/**
+ * Result of [`Connection::identify_system`](crate::Connection::identify_system).
+ */
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SystemIdentification {
+    /** Unique system identifier of the cluster. */
+    pub system_id: String,
+    /** Current timeline ID. */
+    pub timeline: u32,
+    /** Current WAL flush location. */
+    pub xlogpos: Lsn,
+    /** Database connected to, `None` for a physical replication connection. */
+    pub dbname: Option<String>,
+}
+
+/**
+ * Kind of slot created by
+ * [`Connection::create_replication_slot`](crate::Connection::create_replication_slot).
+ */
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SlotKind {
+    /** Physical slot, reserving the WAL immediately if `reserve_wal` is set. */
+    Physical { reserve_wal: bool },
+    /** Logical slot decoding the changes with the `plugin` output plugin, like `pgoutput`. */
+    Logical { plugin: String },
+}
+
+/**
+ * Replication slot created by
+ * [`Connection::create_replication_slot`](crate::Connection::create_replication_slot).
+ */
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Slot {
+    pub name: String,
+    /** WAL location at which the slot became consistent. */
+    pub consistent_point: Option<Lsn>,
+    /** Snapshot exported by the command, if any. */
+    pub snapshot_name: Option<String>,
+    /** Output plugin of a logical slot. */
+    pub output_plugin: Option<String>,
+}
+
+/**
+ * Arguments of [`Connection::start_replication`](crate::Connection::start_replication).
+ */
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Start<'a> {
+    /** Streams the WAL from `lsn`, through `slot` if any. */
+    Physical {
+        slot: Option<&'a str>,
+        lsn: Lsn,
+        timeline: Option<u32>,
+    },
+    /**
+     * Streams the changes decoded by the plugin of `slot`, from `lsn`. The option values can't
+     * contain backslashes.
+     */
+    Logical {
+        slot: &'a str,
+        lsn: Lsn,
+        options: &'a [(&'a str, &'a str)],
+    },
+}
+
+impl crate::Connection {
+    /**
+     * Requests the server to identify itself.
+     *
+     * See [IDENTIFY_SYSTEM](https://www.postgresql.org/docs/current/protocol-replication.html#PROTOCOL-REPLICATION-IDENTIFY-SYSTEM).
+     */
+    pub fn identify_system(&self) -> crate::errors::Result<SystemIdentification> {
+        let result = self.exec_checked("IDENTIFY_SYSTEM", &[crate::Status::TupplesOk])?;
+
+        if result.ntuples() != 1 || result.nfields() < 4 {
+            return Err(crate::errors::Error::Backend(
+                "unexpected IDENTIFY_SYSTEM response".to_string(),
+            ));
+        }
+
+        let text = |column| -> crate::errors::Result<Option<String>> {
+            Ok(result
+                .value(0, column)
+                .map(std::str::from_utf8)
+                .transpose()?
+                .map(String::from))
+        };
+
+        Ok(SystemIdentification {
+            system_id: text(0)?.unwrap_or_default(),
+            timeline: text(1)?.unwrap_or_default().parse()?,
+            xlogpos: text(2)?.unwrap_or_default().parse()?,
+            dbname: text(3)?,
+        })
+    }
+
+    /**
+     * Creates a replication slot, dropped at the end of the session if `temporary` is set.
+     *
+     * No snapshot is exported for a logical slot.
+     *
+     * See [CREATE_REPLICATION_SLOT](https://www.postgresql.org/docs/current/protocol-replication.html#PROTOCOL-REPLICATION-CREATE-REPLICATION-SLOT).
+     */
+    pub fn create_replication_slot(
+        &self,
+        name: &str,
+        kind: &SlotKind,
+        temporary: bool,
+    ) -> crate::errors::Result<Slot> {
+        let mut command = format!(
+            "CREATE_REPLICATION_SLOT {}",
+            self.escape_identifier(name)?.to_str()?
+        );
+
+        if temporary {
+            command.push_str(" TEMPORARY");
+        }
+
+        match kind {
+            SlotKind::Physical { reserve_wal } => {
+                command.push_str(" PHYSICAL");
+
+                if *reserve_wal {
+                    command.push_str(" RESERVE_WAL");
+                }
+            }
+            SlotKind::Logical { plugin } => {
+                command.push_str(" LOGICAL ");
+                command.push_str(self.escape_identifier(plugin)?.to_str()?);
+                command.push_str(" NOEXPORT_SNAPSHOT");
+            }
+        }
+
+        let result = self.exec_checked(&command, &[crate::Status::TupplesOk])?;
+
+        let text = |column| -> crate::errors::Result<Option<String>> {
+            Ok(result
+                .value(0, column)
+                .map(std::str::from_utf8)
+                .transpose()?
+                .map(String::from))
+        };
+
+        Ok(Slot {
+            name: text(0)?.unwrap_or_else(|| name.to_string()),
+            consistent_point: text(1)?.map(|x| x.parse()).transpose()?,
+            snapshot_name: text(2)?,
+            output_plugin: text(3)?,
+        })
+    }
+
+    /**
+     * Drops a replication slot, waiting for it to become inactive if `wait` is set.
+     *
+     * See [DROP_REPLICATION_SLOT](https://www.postgresql.org/docs/current/protocol-replication.html#PROTOCOL-REPLICATION-DROP-REPLICATION-SLOT).
+     */
+    pub fn drop_replication_slot(&self, name: &str, wait: bool) -> crate::errors::Result {
+        let mut command = format!(
+            "DROP_REPLICATION_SLOT {}",
+            self.escape_identifier(name)?.to_str()?
+        );
+
+        if wait {
+            command.push_str(" WAIT");
+        }
+
+        self.exec_checked(&command, &[crate::Status::CommandOk])?;
+
+        Ok(())
+    }
+
+    /**
+     * Switches the connection to `COPY BOTH` mode, streaming the WAL or the logical changes.
+     *
+     * The messages are then read, and the standby status updates sent, with the returned handle.
+     *
+     * See [START_REPLICATION](https://www.postgresql.org/docs/current/protocol-replication.html#PROTOCOL-REPLICATION-START-REPLICATION).
+     */
+    pub fn start_replication(
+        &self,
+        start: &Start,
+    ) -> crate::errors::Result<crate::connection::CopyBoth<'_>> {
+        let command = match start {
+            Start::Physical {
+                slot,
+                lsn,
+                timeline,
+            } => {
+                let mut command = "START_REPLICATION".to_string();
+
+                if let Some(slot) = slot {
+                    command.push_str(" SLOT ");
+                    command.push_str(self.escape_identifier(slot)?.to_str()?);
+                }
+
+                command.push_str(&format!(" PHYSICAL {lsn}"));
+
+                if let Some(timeline) = timeline {
+                    command.push_str(&format!(" TIMELINE {timeline}"));
+                }
+
+                command
+            }
+            Start::Logical { slot, lsn, options } => {
+                let mut command = format!(
+                    "START_REPLICATION SLOT {} LOGICAL {lsn}",
+                    self.escape_identifier(slot)?.to_str()?
+                );
+
+                if !options.is_empty() {
+                    let options = options
+                        .iter()
+                        .map(|(name, value)| {
+                            Ok(format!(
+                                "{} {}",
+                                self.escape_identifier(name)?.to_str()?,
+                                option_literal(name, value)?
+                            ))
+                        })
+                        .collect::<crate::errors::Result<Vec<_>>>()?;
+
+                    command.push_str(&format!(" ({})", options.join(", ")));
+                }
+
+                command
+            }
+        };
+
+        self.copy_both(&command)
+    }
+}
+
+/**
+ * Quotes `value` as a replication command string, where `E'…'` isn't accepted, so backslashes
+ * can't be escaped.
+ */
+fn option_literal(name: &str, value: &str) -> crate::errors::Result<String> {
+    if value.contains('\\') {
+        return Err(crate::errors::Error::Backend(format!(
+            "backslash in the value of the replication option \"{name}\""
+        )));
+    }
+
+    Ok(format!("'{}'", value.replace('\'', "''")))
+}
+
+#[cfg(test)]
+mod test {
+    fn replication_conn() -> crate::Connection {
+        crate::connection::Builder::parse(&crate::test::dsn())
+            .unwrap()
+            .replication(crate::connection::Replication::Database)
+            .connect()
+            .unwrap()
+    }
+
+    #[test]
+    fn option_literal() {
+        assert_eq!(super::option_literal("proto_version", "1").unwrap(), "'1'");
+        assert_eq!(
+            super::option_literal("publication_names", "it's").unwrap(),
+            "'it''s'"
+        );
+        assert!(super::option_literal("publication_names", "a\\b").is_err());
+    }
+
+    #[test]
+    fn identify_system() {
+        let conn = replication_conn();
+        let system = conn.identify_system().unwrap();
+
+        assert!(!system.system_id.is_empty());
+        assert!(system.timeline > 0);
+        assert!(system.dbname.is_some());
+    }
+
+    #[test]
+    fn replication_slot() {
+        let conn = replication_conn();
+
+        let slot = conn
+            .create_replication_slot(
+                "libpq_replication_slot",
+                &super::SlotKind::Physical { reserve_wal: true },
+                true,
+            )
+            .unwrap();
+        assert_eq!(slot.name, "libpq_replication_slot");
+
+        conn.drop_replication_slot("libpq_replication_slot", false)
+            .unwrap();
+    }
+}
@@ -0,0 +1,279 @@
+use super::protocol::Reader;
+
+/**
+ * Message of the `pgoutput` plugin, protocol version 1.
+ *
+ * See [Logical Replication Message Formats](https://www.postgresql.org/docs/current/protocol-logicalrep-message-formats.html).
+ */
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Message {
+    Begin(Begin),
+    Commit(Commit),
+    Relation(Relation),
+    Type(Type),
+    Insert(Insert),
+    Update(Update),
+    Delete(Delete),
+    /** Message not decoded by this crate, like `Origin` or `Truncate`, with its tag. */
+    Other(u8, Vec<u8>),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Begin {
+    /** LSN of the commit record of the transaction. */
+    pub final_lsn: super::Lsn,
+    pub timestamp: std::time::SystemTime,
+    pub xid: u32,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Commit {
+    pub flags: u8,
+    pub commit_lsn: super::Lsn,
+    /** End of the transaction, to confirm with [`LogicalStream::flush`](super::LogicalStream::flush). */
+    pub end_lsn: super::Lsn,
+    pub timestamp: std::time::SystemTime,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Relation {
+    pub oid: crate::Oid,
+    pub namespace: String,
+    pub name: String,
+    /** `REPLICA IDENTITY` setting, `d`efault, `n`othing, `f`ull or `i`ndex. */
+    pub replica_identity: u8,
+    pub columns: Vec<Column>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Column {
+    /** Whether the column is part of the key. */
+    pub key: bool,
+    pub name: String,
+    pub type_oid: crate::Oid,
+    pub type_modifier: i32,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Type {
+    pub oid: crate::Oid,
+    pub namespace: String,
+    pub name: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Insert {
+    pub relation: crate::Oid,
+    pub new: Tuple,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Update {
+    pub relation: crate::Oid,
+    /** Key columns, sent if the key changed. */
+    pub key: Option<Tuple>,
+    /** Old row, sent for a `REPLICA IDENTITY FULL` table. */
+    pub old: Option<Tuple>,
+    pub new: Tuple,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Delete {
+    pub relation: crate::Oid,
+    /** Key columns, unless the table is `REPLICA IDENTITY FULL`. */
+    pub key: Option<Tuple>,
+    /** Old row, sent for a `REPLICA IDENTITY FULL` table. */
+    pub old: Option<Tuple>,
+}
+
+pub type Tuple = Vec<Value>;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Value {
+    Null,
+    /** TOASTed value not changed by the update, so not sent. */
+    Unchanged,
+    Text(Vec<u8>),
+    Binary(Vec<u8>),
+}
+
+impl Message {
+    pub fn parse(data: &[u8]) -> crate::errors::Result<Self> {
+        let mut reader = Reader::new(data);
+
+        let message = match reader.u8()? {
+            b'B' => Self::Begin(Begin {
+                final_lsn: reader.lsn()?,
+                timestamp: reader.time()?,
+                xid: reader.u32()?,
+            }),
+            b'C' => Self::Commit(Commit {
+                flags: reader.u8()?,
+                commit_lsn: reader.lsn()?,
+                end_lsn: reader.lsn()?,
+                timestamp: reader.time()?,
+            }),
+            b'R' => {
+                let oid = reader.u32()?;
+                let namespace = reader.string()?;
+                let name = reader.string()?;
+                let replica_identity = reader.u8()?;
+                let ncolumns = reader.i16()?;
+
+                let columns = (0..ncolumns)
+                    .map(|_| {
+                        Ok(Column {
+                            key: reader.u8()? & 1 != 0,
+                            name: reader.string()?,
+                            type_oid: reader.u32()?,
+                            type_modifier: reader.i32()?,
+                        })
+                    })
+                    .collect::<crate::errors::Result<_>>()?;
+
+                Self::Relation(Relation {
+                    oid,
+                    namespace,
+                    name,
+                    replica_identity,
+                    columns,
+                })
+            }
+            b'Y' => Self::Type(Type {
+                oid: reader.u32()?,
+                namespace: reader.string()?,
+                name: reader.string()?,
+            }),
+            b'I' => {
+                let relation = reader.u32()?;
+                expect(&mut reader, b'N')?;
+
+                Self::Insert(Insert {
+                    relation,
+                    new: tuple(&mut reader)?,
+                })
+            }
+            b'U' => {
+                let relation = reader.u32()?;
+                let mut key = None;
+                let mut old = None;
+
+                let new = loop {
+                    match reader.u8()? {
+                        b'K' => key = Some(tuple(&mut reader)?),
+                        b'O' => old = Some(tuple(&mut reader)?),
+                        b'N' => break tuple(&mut reader)?,
+                        tag => return Err(unexpected(tag)),
+                    }
+                };
+
+                Self::Update(Update {
+                    relation,
+                    key,
+                    old,
+                    new,
+                })
+            }
+            b'D' => {
+                let relation = reader.u32()?;
+
+                let (key, old) = match reader.u8()? {
+                    b'K' => (Some(tuple(&mut reader)?), None),
+                    b'O' => (None, Some(tuple(&mut reader)?)),
+                    tag => return Err(unexpected(tag)),
+                };
+
+                Self::Delete(Delete { relation, key, old })
+            }
+            tag => Self::Other(tag, reader.rest().to_vec()),
+        };
+
+        Ok(message)
+    }
+}
+
+fn tuple(reader: &mut Reader) -> crate::errors::Result<Tuple> {
+    let ncolumns = reader.i16()?;
+
+    (0..ncolumns)
+        .map(|_| {
+            let value = match reader.u8()? {
+                b'n' => Value::Null,
+                b'u' => Value::Unchanged,
+                b't' => {
+                    let len = reader.i32()?;
+                    Value::Text(reader.bytes(len as usize)?.to_vec())
+                }
+                b'b' => {
+                    let len = reader.i32()?;
+                    Value::Binary(reader.bytes(len as usize)?.to_vec())
+                }
+                tag => return Err(unexpected(tag)),
+            };
+
+            Ok(value)
+        })
+        .collect()
+}
+
+fn expect(reader: &mut Reader, expected: u8) -> crate::errors::Result {
+    match reader.u8()? {
+        tag if tag == expected => Ok(()),
+        tag => Err(unexpected(tag)),
+    }
+}
+
+fn unexpected(tag: u8) -> crate::errors::Error {
+    crate::errors::Error::Backend(format!("unexpected '{}' in pgoutput message", tag as char))
+}
+
+#[cfg(test)]
+mod test {
+    #[test]
+    fn parse() {
+        let mut data = b"I".to_vec();
+        data.extend_from_slice(&16384_u32.to_be_bytes());
+        data.push(b'N');
+        data.extend_from_slice(&3_i16.to_be_bytes());
+        data.extend_from_slice(b"t\0\0\0\x011n");
+        data.push(b'u');
+
+        assert_eq!(
+            super::Message::parse(&data),
+            Ok(super::Message::Insert(super::Insert {
+                relation: 16384,
+                new: vec![
+                    super::Value::Text(b"1".to_vec()),
+                    super::Value::Null,
+                    super::Value::Unchanged,
+                ],
+            }))
+        );
+
+        let mut data = b"R".to_vec();
+        data.extend_from_slice(&16384_u32.to_be_bytes());
+        data.extend_from_slice(b"public\0users\0d");
+        data.extend_from_slice(&1_i16.to_be_bytes());
+        data.extend_from_slice(b"\x01id\0");
+        data.extend_from_slice(&23_u32.to_be_bytes());
+        data.extend_from_slice(&(-1_i32).to_be_bytes());
+
+        assert_eq!(
+            super::Message::parse(&data),
+            Ok(super::Message::Relation(super::Relation {
+                oid: 16384,
+                namespace: "public".to_string(),
+                name: "users".to_string(),
+                replica_identity: b'd',
+                columns: vec![super::Column {
+                    key: true,
+                    name: "id".to_string(),
+                    type_oid: 23,
+                    type_modifier: -1,
+                }],
+            }))
+        );
+
+        assert!(super::Message::parse(b"I\0\0").is_err());
+    }
+}
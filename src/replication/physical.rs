@@ -0,0 +1,210 @@
+/**
+ * Message of a physical replication stream, see [`PhysicalStream`].
+ */
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum WalMessage {
+    XLogData(XLogData),
+    Keepalive(Keepalive),
+}
+
+/**
+ * WAL data sent by the server.
+ */
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct XLogData {
+    /** WAL location of the first byte of `data`. */
+    pub start: super::Lsn,
+    /** Current end of the WAL on the server. */
+    pub end: super::Lsn,
+    pub time: std::time::SystemTime,
+    pub data: Vec<u8>,
+}
+
+/**
+ * Keepalive sent by the server, it's answered by the stream if requested.
+ */
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Keepalive {
+    /** Current end of the WAL on the server. */
+    pub end: super::Lsn,
+    pub time: std::time::SystemTime,
+    pub reply: bool,
+}
+
+/**
+ * Physical replication stream, receiving the raw WAL.
+ *
+ * ```no_run
+ * # let conn = libpq::Connection::new("replication=true")?;
+ * # let mut archive = Vec::new();
+ * let system = conn.identify_system()?;
+ * let mut stream = libpq::replication::PhysicalStream::start(&conn, None, system.xlogpos, None)?;
+ *
+ * while let Some(message) = stream.next_message()? {
+ *     if let libpq::replication::WalMessage::XLogData(xlog) = message {
+ *         archive.extend_from_slice(&xlog.data);
+ *         stream.flush(xlog.start + xlog.data.len() as u64);
+ *     }
+ * }
+ * # Ok::<(), libpq::errors::Error>(())
+ * ```
+ *
+ * The write position follows the received data, the flush and apply ones are reported with
+ * [`PhysicalStream::flush`] and [`PhysicalStream::apply`]. They are sent every
+ * [`STATUS_INTERVAL`](super::STATUS_INTERVAL) while reading and when the server asks for them.
+ */
+#[derive(Debug)]
+pub struct PhysicalStream<'conn> {
+    reader: crate::connection::CopyReader<'conn>,
+    writer: crate::connection::CopyWriter<'conn>,
+    feedback: super::protocol::Feedback,
+}
+
+impl<'conn> PhysicalStream<'conn> {
+    /**
+     * Starts streaming the WAL from `lsn`, through `slot` if any.
+     */
+    pub fn start(
+        conn: &'conn crate::Connection,
+        slot: Option<&str>,
+        lsn: super::Lsn,
+        timeline: Option<u32>,
+    ) -> crate::errors::Result<Self> {
+        let (reader, writer) = conn
+            .start_replication(&super::Start::Physical {
+                slot,
+                lsn,
+                timeline,
+            })?
+            .split();
+
+        let mut feedback = super::protocol::Feedback::new(super::STATUS_INTERVAL);
+        feedback.write = lsn;
+        feedback.flush = lsn;
+        feedback.apply = lsn;
+
+        Ok(Self {
+            reader,
+            writer,
+            feedback,
+        })
+    }
+
+    /**
+     * Waits for the next message, `None` once the server ended the stream, at the end of the
+     * timeline.
+     */
+    pub fn next_message(&mut self) -> crate::errors::Result<Option<WalMessage>> {
+        let data = match self.reader.read()? {
+            Some(data) => data,
+            None => return Ok(None),
+        };
+
+        let message = match super::protocol::Message::parse(&data)? {
+            super::protocol::Message::XLogData {
+                start,
+                end,
+                time,
+                data,
+            } => {
+                let written = start + data.len() as u64;
+                self.feedback.write = self.feedback.write.max(written);
+                self.feedback.send_if_due(&mut self.writer, false)?;
+
+                WalMessage::XLogData(XLogData {
+                    start,
+                    end,
+                    time,
+                    data: data.to_vec(),
+                })
+            }
+            super::protocol::Message::Keepalive { end, time, reply } => {
+                self.feedback.send_if_due(&mut self.writer, reply)?;
+
+                WalMessage::Keepalive(Keepalive { end, time, reply })
+            }
+        };
+
+        Ok(Some(message))
+    }
+
+    /**
+     * Reports the WAL up to `lsn` as written to durable storage.
+     */
+    pub fn flush(&mut self, lsn: super::Lsn) {
+        self.feedback.flush = self.feedback.flush.max(lsn);
+    }
+
+    /**
+     * Reports the WAL up to `lsn` as applied.
+     */
+    pub fn apply(&mut self, lsn: super::Lsn) {
+        self.feedback.apply = self.feedback.apply.max(lsn);
+    }
+
+    /**
+     * Returns the reported write, flush and apply positions.
+     */
+    pub fn positions(&self) -> (super::Lsn, super::Lsn, super::Lsn) {
+        (
+            self.feedback.write,
+            self.feedback.flush,
+            self.feedback.apply,
+        )
+    }
+
+    /**
+     * Reports the oldest transactions needed by the queries of the standby.
+     */
+    pub fn send_hot_standby_feedback(
+        &mut self,
+        feedback: &super::HotStandbyFeedback,
+    ) -> crate::errors::Result {
+        self.writer.write(&feedback.encode())
+    }
+
+    /**
+     * Sends a status update now, instead of waiting for the next one.
+     */
+    pub fn send_feedback(&mut self) -> crate::errors::Result {
+        self.feedback.send(&mut self.writer, false)
+    }
+
+    /**
+     * Ends the stream, the connection can then run other replication commands.
+     */
+    pub fn stop(mut self) -> crate::errors::Result {
+        self.writer.finish()?;
+
+        while self.reader.read()?.is_some() {}
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #[test]
+    fn physical_stream() {
+        let conn = crate::connection::Builder::parse(&crate::test::dsn())
+            .unwrap()
+            .replication(crate::connection::Replication::Physical)
+            .connect()
+            .unwrap();
+        let system = conn.identify_system().unwrap();
+
+        let mut stream =
+            super::PhysicalStream::start(&conn, None, system.xlogpos, Some(system.timeline))
+                .unwrap();
+
+        crate::test::new_conn().exec("SELECT pg_switch_wal()");
+
+        let message = stream.next_message().unwrap();
+        assert!(message.is_some());
+
+        let (write, _, _) = stream.positions();
+        assert!(write >= system.xlogpos);
+
+        stream.stop().unwrap();
+    }
+}
@@ -0,0 +1,235 @@
+/**
+ * Time between the Unix epoch and the PostgreSQL one, 2000-01-01.
+ */
+const POSTGRES_EPOCH: std::time::Duration = std::time::Duration::from_secs(946_684_800);
+
+/**
+ * Message of the replication stream, sent by the server in `COPY BOTH` mode.
+ *
+ * See [Streaming Replication Protocol](https://www.postgresql.org/docs/current/protocol-replication.html#PROTOCOL-REPLICATION-XLOGDATA).
+ */
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) enum Message<'a> {
+    XLogData {
+        start: super::Lsn,
+        end: super::Lsn,
+        time: std::time::SystemTime,
+        data: &'a [u8],
+    },
+    Keepalive {
+        end: super::Lsn,
+        time: std::time::SystemTime,
+        reply: bool,
+    },
+}
+
+impl<'a> Message<'a> {
+    pub fn parse(data: &'a [u8]) -> crate::errors::Result<Self> {
+        let mut reader = Reader::new(data);
+
+        let message = match reader.u8()? {
+            b'w' => Self::XLogData {
+                start: reader.lsn()?,
+                end: reader.lsn()?,
+                time: reader.time()?,
+                data: reader.rest(),
+            },
+            b'k' => Self::Keepalive {
+                end: reader.lsn()?,
+                time: reader.time()?,
+                reply: reader.u8()? != 0,
+            },
+            tag => {
+                return Err(crate::errors::Error::Backend(format!(
+                    "unexpected replication message '{}'",
+                    tag as char
+                )))
+            }
+        };
+
+        Ok(message)
+    }
+}
+
+/**
+ * WAL locations reported to the server by the standby status updates.
+ */
+#[derive(Debug)]
+pub(crate) struct Feedback {
+    pub write: super::Lsn,
+    pub flush: super::Lsn,
+    pub apply: super::Lsn,
+    pub interval: std::time::Duration,
+    last: std::time::Instant,
+}
+
+impl Feedback {
+    pub fn new(interval: std::time::Duration) -> Self {
+        Self {
+            write: super::Lsn::INVALID,
+            flush: super::Lsn::INVALID,
+            apply: super::Lsn::INVALID,
+            interval,
+            last: std::time::Instant::now(),
+        }
+    }
+
+    /**
+     * Sends a standby status update if requested by the server or if `interval` elapsed.
+     */
+    pub fn send_if_due(
+        &mut self,
+        writer: &mut crate::connection::CopyWriter,
+        reply: bool,
+    ) -> crate::errors::Result {
+        if reply || self.last.elapsed() >= self.interval {
+            self.send(writer, false)
+        } else {
+            Ok(())
+        }
+    }
+
+    /**
+     * Sends a standby status update, asking the server to answer immediately if `reply` is set.
+     */
+    pub fn send(
+        &mut self,
+        writer: &mut crate::connection::CopyWriter,
+        reply: bool,
+    ) -> crate::errors::Result {
+        let update =
+            super::StandbyStatusUpdate::new(self.write, self.flush, self.apply).reply(reply);
+
+        writer.write(&update.encode())?;
+        self.last = std::time::Instant::now();
+
+        Ok(())
+    }
+}
+
+pub(crate) fn to_time(timestamp: i64) -> std::time::SystemTime {
+    let epoch = std::time::UNIX_EPOCH + POSTGRES_EPOCH;
+    let offset = std::time::Duration::from_micros(timestamp.unsigned_abs());
+
+    if timestamp >= 0 {
+        epoch + offset
+    } else {
+        epoch - offset
+    }
+}
+
+pub(crate) fn to_timestamp(time: std::time::SystemTime) -> i64 {
+    let epoch = std::time::UNIX_EPOCH + POSTGRES_EPOCH;
+
+    match time.duration_since(epoch) {
+        Ok(duration) => duration.as_micros() as i64,
+        Err(err) => -(err.duration().as_micros() as i64),
+    }
+}
+
+/**
+ * Reads the big endian fields of a message.
+ */
+pub(crate) struct Reader<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    pub fn new(data: &'a [u8]) -> Self {
+        Self { data }
+    }
+
+    pub fn bytes(&mut self, len: usize) -> crate::errors::Result<&'a [u8]> {
+        if self.data.len() < len {
+            return Err(crate::errors::Error::Backend(
+                "truncated replication message".to_string(),
+            ));
+        }
+
+        let (bytes, data) = self.data.split_at(len);
+        self.data = data;
+
+        Ok(bytes)
+    }
+
+    pub fn rest(&mut self) -> &'a [u8] {
+        std::mem::take(&mut self.data)
+    }
+
+    pub fn u8(&mut self) -> crate::errors::Result<u8> {
+        Ok(self.bytes(1)?[0])
+    }
+
+    pub fn i16(&mut self) -> crate::errors::Result<i16> {
+        Ok(i16::from_be_bytes(self.bytes(2)?.try_into().unwrap()))
+    }
+
+    pub fn i32(&mut self) -> crate::errors::Result<i32> {
+        Ok(i32::from_be_bytes(self.bytes(4)?.try_into().unwrap()))
+    }
+
+    pub fn u32(&mut self) -> crate::errors::Result<u32> {
+        Ok(u32::from_be_bytes(self.bytes(4)?.try_into().unwrap()))
+    }
+
+    pub fn i64(&mut self) -> crate::errors::Result<i64> {
+        Ok(i64::from_be_bytes(self.bytes(8)?.try_into().unwrap()))
+    }
+
+    pub fn lsn(&mut self) -> crate::errors::Result<super::Lsn> {
+        Ok(super::Lsn(u64::from_be_bytes(
+            self.bytes(8)?.try_into().unwrap(),
+        )))
+    }
+
+    pub fn time(&mut self) -> crate::errors::Result<std::time::SystemTime> {
+        Ok(to_time(self.i64()?))
+    }
+
+    /**
+     * Reads a nul terminated string.
+     */
+    pub fn string(&mut self) -> crate::errors::Result<String> {
+        let len = self.data.iter().position(|c| *c == 0).ok_or_else(|| {
+            crate::errors::Error::Backend("unterminated string in replication message".to_string())
+        })?;
+
+        let s = std::str::from_utf8(self.bytes(len)?)?.to_string();
+        self.bytes(1)?;
+
+        Ok(s)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #[test]
+    fn parse() {
+        let mut data = vec![b'k'];
+        data.extend_from_slice(&0x16_B374_D848_u64.to_be_bytes());
+        data.extend_from_slice(&0_i64.to_be_bytes());
+        data.push(1);
+
+        assert_eq!(
+            super::Message::parse(&data),
+            Ok(super::Message::Keepalive {
+                end: crate::replication::Lsn(0x16_B374_D848),
+                time: std::time::UNIX_EPOCH + super::POSTGRES_EPOCH,
+                reply: true,
+            })
+        );
+
+        assert!(super::Message::parse(&data[..5]).is_err());
+    }
+
+    #[test]
+    fn timestamp() {
+        let time = std::time::UNIX_EPOCH + std::time::Duration::from_secs(1_000_000_000);
+
+        assert_eq!(super::to_time(super::to_timestamp(time)), time);
+        assert_eq!(
+            super::to_timestamp(std::time::UNIX_EPOCH),
+            -946_684_800_000_000
+        );
+    }
+}
@@ -0,0 +1,110 @@
+/**
+ * Replication slot, as listed by [`Connection::replication_slots`](crate::Connection::replication_slots).
+ */
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SlotInfo {
+    pub name: String,
+    /** Output plugin of a logical slot. */
+    pub plugin: Option<String>,
+    /** `true` for a physical slot, `false` for a logical one. */
+    pub physical: bool,
+    /** Database of a logical slot. */
+    pub database: Option<String>,
+    pub temporary: bool,
+    /** Whether a stream is currently using the slot. */
+    pub active: bool,
+    /** Oldest WAL location still required by the slot. */
+    pub restart_lsn: Option<super::Lsn>,
+    /** Location up to which the consumer of a logical slot confirmed the changes. */
+    pub confirmed_flush_lsn: Option<super::Lsn>,
+}
+
+impl crate::result::FromRow for SlotInfo {
+    fn from_row(row: &crate::result::Row) -> crate::errors::Result<Self> {
+        Ok(Self {
+            name: row.text(0)?.unwrap_or_default().to_string(),
+            plugin: row.text(1)?.map(String::from),
+            physical: row.text(2)?.as_deref() == Some("physical"),
+            database: row.text(3)?.map(String::from),
+            temporary: row.text(4)?.as_deref() == Some("t"),
+            active: row.text(5)?.as_deref() == Some("t"),
+            restart_lsn: row.parse(6)?,
+            confirmed_flush_lsn: row.parse(7)?,
+        })
+    }
+}
+
+impl crate::Connection {
+    /**
+     * Lists the replication slots of the cluster.
+     *
+     * See [pg_replication_slots](https://www.postgresql.org/docs/current/view-pg-replication-slots.html).
+     */
+    pub fn replication_slots(&self) -> crate::errors::Result<Vec<SlotInfo>> {
+        self.exec_checked(
+            "SELECT slot_name, plugin, slot_type, database, temporary, active, restart_lsn, confirmed_flush_lsn \
+             FROM pg_catalog.pg_replication_slots",
+            &[crate::Status::TupplesOk],
+        )?
+        .to_vec()
+    }
+
+    /**
+     * Advances the slot `name` up to `lsn`, without consuming its changes, and returns the
+     * position reached.
+     *
+     * Runs SQL, so it requires a regular connection or a `replication=database` one.
+     *
+     * See [pg_replication_slot_advance](https://www.postgresql.org/docs/current/functions-admin.html#FUNCTIONS-REPLICATION).
+     */
+    pub fn advance_replication_slot(
+        &self,
+        name: &str,
+        lsn: super::Lsn,
+    ) -> crate::errors::Result<super::Lsn> {
+        let query = "SELECT end_lsn FROM pg_catalog.pg_replication_slot_advance($1, $2::pg_lsn)";
+        let result = self.check_result(
+            self.exec_params(
+                query,
+                &[],
+                &crate::params![name, lsn.to_string()],
+                &[],
+                crate::Format::Text,
+            ),
+            &[crate::Status::TupplesOk],
+            query,
+        )?;
+
+        result
+            .rows()
+            .next()
+            .and_then(|row| row.parse(0).transpose())
+            .unwrap_or(Ok(lsn))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #[test]
+    fn replication_slots() {
+        let conn = crate::test::new_conn();
+        conn.exec("SELECT pg_create_physical_replication_slot('libpq_slots', true)");
+
+        let slot = conn
+            .replication_slots()
+            .unwrap()
+            .into_iter()
+            .find(|x| x.name == "libpq_slots")
+            .unwrap();
+        assert!(slot.physical);
+        assert!(!slot.active);
+
+        let restart_lsn = slot.restart_lsn.unwrap();
+        assert_eq!(
+            conn.advance_replication_slot("libpq_slots", restart_lsn),
+            Ok(restart_lsn)
+        );
+
+        conn.exec("SELECT pg_drop_replication_slot('libpq_slots')");
+    }
+}
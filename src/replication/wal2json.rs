@@ -0,0 +1,87 @@
+/**
+ * Change emitted by the `wal2json` plugin, see [`Wal2JsonStream`].
+ */
+#[derive(Clone, Debug, PartialEq)]
+pub struct Wal2JsonChange {
+    /** WAL location of the change. */
+    pub lsn: super::Lsn,
+    pub value: serde_json::Value,
+}
+
+/**
+ * Logical replication stream decoded by the [wal2json](https://github.com/eulerto/wal2json)
+ * plugin, in its `format-version` 2: an event per change, with an `action` key.
+ *
+ * ```no_run
+ * # let conn = libpq::Connection::new("replication=database")?;
+ * let mut stream = libpq::replication::Wal2JsonStream::start(
+ *     &conn,
+ *     "slot",
+ *     &[("include-timestamp", "1")],
+ *     libpq::replication::Lsn::INVALID,
+ * )?;
+ *
+ * while let Some(change) = stream.next_change()? {
+ *     if change.value["action"] == "C" {
+ *         stream.flush(change.lsn);
+ *     }
+ * }
+ * # Ok::<(), libpq::errors::Error>(())
+ * ```
+ *
+ * See [`LogicalStream`](super::LogicalStream) for the feedback.
+ */
+#[derive(Debug)]
+pub struct Wal2JsonStream<'conn> {
+    stream: super::LogicalStream<'conn>,
+}
+
+impl<'conn> Wal2JsonStream<'conn> {
+    /**
+     * Starts streaming from `slot`, created with the `wal2json` plugin, with additional plugin
+     * `options`.
+     */
+    pub fn start(
+        conn: &'conn crate::Connection,
+        slot: &str,
+        options: &[(&str, &str)],
+        lsn: super::Lsn,
+    ) -> crate::errors::Result<Self> {
+        let mut options = options.to_vec();
+        options.insert(0, ("format-version", "2"));
+
+        let stream = super::LogicalStream::start_plugin(conn, slot, lsn, &options)?;
+
+        Ok(Self { stream })
+    }
+
+    /**
+     * Waits for the next change, `None` once the server ended the stream.
+     */
+    pub fn next_change(&mut self) -> crate::errors::Result<Option<Wal2JsonChange>> {
+        let change = self
+            .stream
+            .next_with(|data| {
+                serde_json::from_slice(data)
+                    .map_err(|err| crate::errors::Error::Backend(err.to_string()))
+            })?
+            .map(|(lsn, value)| Wal2JsonChange { lsn, value });
+
+        Ok(change)
+    }
+
+    /** See [`LogicalStream::flush`](super::LogicalStream::flush). */
+    pub fn flush(&mut self, lsn: super::Lsn) {
+        self.stream.flush(lsn);
+    }
+
+    /** See [`LogicalStream::send_feedback`](super::LogicalStream::send_feedback). */
+    pub fn send_feedback(&mut self) -> crate::errors::Result {
+        self.stream.send_feedback()
+    }
+
+    /** See [`LogicalStream::stop`](super::LogicalStream::stop). */
+    pub fn stop(self) -> crate::errors::Result {
+        self.stream.stop()
+    }
+}
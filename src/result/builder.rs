@@ -0,0 +1,131 @@
+/**
+ * Builds a [`Result`](crate::Result) without a server, to fabricate query results in tests.
+ *
+ * ```
+ * let result = libpq::result::Builder::new(libpq::Status::TupplesOk)
+ *     .column("id", &libpq::types::INT4)
+ *     .column("name", &libpq::types::TEXT)
+ *     .row(&[Some("1"), Some("foo")])
+ *     .row(&[Some("2"), None])
+ *     .build()
+ *     .unwrap();
+ *
+ * assert_eq!(result.ntuples(), 2);
+ * assert_eq!(result.field_type(0), libpq::types::INT4.oid);
+ * assert_eq!(result.value(0, 1), Some(&b"foo"[..]));
+ * assert_eq!(result.value(1, 1), None);
+ * ```
+ */
+#[derive(Clone, Debug)]
+pub struct Builder {
+    status: crate::Status,
+    attributes: Vec<crate::result::Attribute>,
+    rows: Vec<Vec<Option<String>>>,
+}
+
+impl Builder {
+    pub fn new(status: crate::Status) -> Self {
+        Self {
+            status,
+            attributes: Vec::new(),
+            rows: Vec::new(),
+        }
+    }
+
+    /**
+     * Adds a text column of type `ty`.
+     *
+     * Its `typlen` is `-1`, the length of the variable-length types, [`crate::Type`] not carrying
+     * the storage size: use [`Builder::attribute`] when [`crate::Result::field_size`]
+     * matters.
+     */
+    pub fn column(self, name: &str, ty: &crate::Type) -> Self {
+        self.attribute(crate::result::Attribute {
+            name: name.to_string(),
+            tableid: crate::oid::INVALID,
+            columnid: 0,
+            format: crate::Format::Text as i32,
+            typid: ty.oid,
+            typlen: -1,
+            atttypmod: -1,
+        })
+    }
+
+    /**
+     * Adds a column described by `attribute`.
+     */
+    pub fn attribute(mut self, attribute: crate::result::Attribute) -> Self {
+        self.attributes.push(attribute);
+        self
+    }
+
+    /**
+     * Adds a row, `None` and missing values are null.
+     */
+    pub fn row(mut self, values: &[Option<&str>]) -> Self {
+        self.rows
+            .push(values.iter().map(|x| x.map(String::from)).collect());
+        self
+    }
+
+    /**
+     * Creates the result.
+     *
+     * It fails if a row has more values than there are columns.
+     */
+    pub fn build(&self) -> crate::errors::Result<crate::Result> {
+        let raw = unsafe { pq_sys::PQmakeEmptyPGresult(std::ptr::null_mut(), self.status.into()) };
+
+        if raw.is_null() {
+            return Err(crate::errors::Error::CallFailed("PQmakeEmptyPGresult"));
+        }
+
+        let mut result = crate::Result::from(raw);
+
+        if !self.attributes.is_empty() {
+            result.set_attrs(&self.attributes.iter().collect::<Vec<_>>())?;
+        }
+
+        for (tuple, row) in self.rows.iter().enumerate() {
+            for field in 0..row.len().max(self.attributes.len()) {
+                let value = row.get(field).and_then(|x| x.as_deref());
+                result.set_value(tuple, field, value)?;
+            }
+        }
+
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #[test]
+    fn build() {
+        let result = crate::result::Builder::new(crate::Status::TupplesOk)
+            .column("id", &crate::types::INT4)
+            .column("name", &crate::types::TEXT)
+            .row(&[Some("1"), Some("foo")])
+            .row(&[Some("2")])
+            .build()
+            .unwrap();
+
+        assert_eq!(result.status(), crate::Status::TupplesOk);
+        assert_eq!(result.nfields(), 2);
+        assert_eq!(result.ntuples(), 2);
+        assert_eq!(result.field_name(1), Ok(Some("name".to_string())));
+        assert_eq!(result.field_type(1), crate::types::TEXT.oid);
+        assert_eq!(result.value(0, 0), Some(&b"1"[..]));
+        assert_eq!(result.value(1, 0), Some(&b"2"[..]));
+        assert_eq!(result.value(1, 1), None);
+    }
+
+    #[test]
+    fn build_too_many_values() {
+        let result = crate::result::Builder::new(crate::Status::TupplesOk)
+            .column("id", &crate::types::INT4)
+            .row(&[Some("1"), Some("2")])
+            .build();
+
+        assert!(result.is_err());
+    }
+}
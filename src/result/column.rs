@@ -0,0 +1,41 @@
+/**
+ * Metadata of a result column.
+ */
+#[derive(Clone, Debug, PartialEq)]
+pub struct Column {
+    /** See [`Result::field_name`](crate::Result::field_name). */
+    pub name: String,
+    /** See [`Result::field_type`](crate::Result::field_type). */
+    pub type_oid: crate::Oid,
+    /** The built-in type matching `type_oid`, `None` for user-defined types. */
+    pub ty: Option<crate::Type>,
+    /** See [`Result::field_table`](crate::Result::field_table). */
+    pub table: Option<crate::Oid>,
+    /** See [`Result::field_tablecol`](crate::Result::field_tablecol). */
+    pub table_column: usize,
+    /** See [`Result::field_format`](crate::Result::field_format). */
+    pub format: crate::Format,
+    /** See [`Result::field_mod`](crate::Result::field_mod). */
+    pub fmod: Option<i32>,
+    /** See [`Result::field_size`](crate::Result::field_size). */
+    pub fsize: Option<usize>,
+}
+
+impl Column {
+    pub(crate) fn new(result: &crate::Result, number: usize) -> crate::errors::Result<Self> {
+        let type_oid = result.field_type(number);
+
+        let column = Self {
+            name: result.field_name(number)?.unwrap_or_default(),
+            type_oid,
+            ty: crate::Type::try_from(type_oid).ok(),
+            table: result.field_table(number),
+            table_column: result.field_tablecol(number),
+            format: result.field_format(number),
+            fmod: result.field_mod(number),
+            fsize: result.field_size(number),
+        };
+
+        Ok(column)
+    }
+}
@@ -0,0 +1,16 @@
+bitflags::bitflags! {
+    /**
+     * Parts of a result copied by [`Result::copy`](crate::Result::copy).
+     */
+    #[repr(transparent)]
+    pub struct CopyFlags : u32 {
+        /** Copy the result's attributes (column definitions). */
+        const ATTRS = pq_sys::PG_COPYRES_ATTRS;
+        /** Copy the result's tuples, this implies copying the attributes. */
+        const TUPLES = pq_sys::PG_COPYRES_TUPLES;
+        /** Copy the result's events. */
+        const EVENTS = pq_sys::PG_COPYRES_EVENTS;
+        /** Copy the result's notice hooks. */
+        const NOTICEHOOKS = pq_sys::PG_COPYRES_NOTICEHOOKS;
+    }
+}
@@ -1,12 +1,34 @@
 mod attribute;
+mod builder;
+mod column;
+mod copy;
 mod error_field;
+mod row;
+mod scoped;
+mod snapshot;
+mod table;
 
 pub use attribute::*;
+pub use builder::*;
+pub use column::*;
+pub use copy::*;
 pub use error_field::*;
+pub use row::*;
+pub use scoped::*;
+pub use snapshot::*;
+pub use table::*;
 
 #[derive(Clone)]
 pub struct Result {
     result: *mut pq_sys::PGresult,
+    pub(crate) notices: Vec<crate::notice::Notice>,
+    /** Error raised before reaching libpq, see `Connection::error_result`. */
+    pub(crate) error: Option<String>,
+    /** Client encoding of the connection when the result was created. */
+    pub(crate) encoding: crate::Encoding,
+    /** Types of the columns, resolved by `Connection::query_sql`. */
+    #[cfg(feature = "with-postgres-types")]
+    pub(crate) sql_types: Vec<postgres_types::Type>,
 }
 
 impl Result {
@@ -19,9 +41,35 @@ impl Result {
     pub fn new(conn: &crate::Connection, status: crate::Status) -> Self {
         let result = unsafe { pq_sys::PQmakeEmptyPGresult(conn.into(), status.into()) };
 
+        conn.to_result(result)
+    }
+
+    /**
+     * Takes the ownership of `result`, created by libpq outside of this crate: it's freed with
+     * `PQclear` when the returned value is dropped.
+     *
+     * # Safety
+     *
+     * `result` must be a valid result, not cleared nor owned by anything else.
+     */
+    pub unsafe fn from_raw(result: *mut pq_sys::PGresult) -> Self {
         result.into()
     }
 
+    /**
+     * Releases the ownership of the result, which must be freed with `PQclear` by the caller.
+     */
+    pub fn into_raw(self) -> *mut pq_sys::PGresult {
+        let result = std::mem::ManuallyDrop::new(self);
+        // Only the notices, the error and the types are owned by Rust.
+        drop(unsafe { std::ptr::read(&result.notices) });
+        drop(unsafe { std::ptr::read(&result.error) });
+        #[cfg(feature = "with-postgres-types")]
+        drop(unsafe { std::ptr::read(&result.sql_types) });
+
+        result.result
+    }
+
     /**
      * Returns the result status of the command.
      *
@@ -31,33 +79,56 @@ impl Result {
         unsafe { pq_sys::PQresultStatus(self.into()) }.into()
     }
 
+    /**
+     * Returns the client encoding of the connection when the result was created, used to decode
+     * its strings.
+     *
+     * It's `UTF8` for the results created without a connection.
+     */
+    pub fn encoding(&self) -> crate::Encoding {
+        self.encoding
+    }
+
     /**
      * Returns the error message associated with the command, or an empty string if there was no error.
      *
+     * Invalid byte sequences for the [`Result::encoding`] are replaced, so the message of the
+     * server is never lost.
+     *
      * See [PQresultErrorMessage](https://www.postgresql.org/docs/current/libpq-exec.html#LIBPQ-PQRESULTERRORMESSAGE).
      */
     pub fn error_message(&self) -> crate::errors::Result<Option<String>> {
-        crate::ffi::to_option_string(unsafe { pq_sys::PQresultErrorMessage(self.into()) })
+        if let Some(error) = &self.error {
+            return Ok(Some(error.clone()));
+        }
+
+        let raw = unsafe { pq_sys::PQresultErrorMessage(self.into()) };
+
+        Ok(self.decode_lossy(raw))
     }
 
     /**
      * Returns a reformatted version of the error message associated with a `libpq::Result` object.
      *
+     * Like [`Result::error_message`], invalid byte sequences are replaced.
+     *
      * See [PQresultErrorField](https://www.postgresql.org/docs/current/libpq-exec.html#LIBPQ-PQRESULTERRORFIELD).
      */
     pub fn error_field(
         &self,
         field: crate::result::ErrorField,
-    ) -> crate::errors::Result<Option<&'static str>> {
-        unsafe {
-            let ptr = pq_sys::PQresultErrorField(self.into(), field.into());
+    ) -> crate::errors::Result<Option<String>> {
+        let raw = unsafe { pq_sys::PQresultErrorField(self.into(), field.into()) };
 
-            if ptr.is_null() {
-                return Ok(None);
-            }
+        Ok(self.decode_lossy(raw))
+    }
 
-            crate::ffi::to_option_str(ptr)
-        }
+    /**
+     * Returns the notices and warnings raised while executing the command, when captured with
+     * [`Connection::capture_notices`](crate::Connection::capture_notices).
+     */
+    pub fn notices(&self) -> &[crate::notice::Notice] {
+        &self.notices
     }
 
     /**
@@ -87,10 +158,12 @@ impl Result {
         let raw = unsafe { pq_sys::PQfname(self.into(), number as i32) };
 
         if raw.is_null() {
-            Ok(None)
-        } else {
-            Some(crate::ffi::to_string(raw)).transpose()
+            return Ok(None);
         }
+
+        let name = unsafe { std::ffi::CStr::from_ptr(raw) }.to_bytes();
+
+        Ok(Some(self.encoding.decode_strict(name)?.into_owned()))
     }
 
     /**
@@ -99,7 +172,7 @@ impl Result {
      * See [PQfnumber](https://www.postgresql.org/docs/current/libpq-exec.html#LIBPQ-PQFNUMBER).
      */
     pub fn field_number(&self, name: &str) -> Option<usize> {
-        let c_name = crate::ffi::to_cstr(name);
+        let c_name = crate::ffi::try_to_cstr(name).ok()?;
         let number = unsafe { pq_sys::PQfnumber(self.into(), c_name.as_ptr()) };
 
         if number == -1 {
@@ -184,6 +257,15 @@ impl Result {
         }
     }
 
+    /**
+     * Returns the metadata of all the columns.
+     */
+    pub fn columns(&self) -> crate::errors::Result<Vec<crate::result::Column>> {
+        (0..self.nfields())
+            .map(|x| crate::result::Column::new(self, x))
+            .collect()
+    }
+
     /**
      * Returns `true` if the `Result` contains binary data and `false` if it contains text data.
      *
@@ -214,6 +296,67 @@ impl Result {
         }
     }
 
+    /**
+     * Like [`Result::value`], without copying: the returned [`bytes::Bytes`] keeps the result
+     * alive until it's dropped.
+     */
+    #[cfg(feature = "bytes")]
+    pub fn value_bytes_shared(
+        self: &std::sync::Arc<Self>,
+        row: usize,
+        column: usize,
+    ) -> Option<bytes::Bytes> {
+        self.value(row, column)?;
+
+        Some(bytes::Bytes::from_owner(SharedValue {
+            result: self.clone(),
+            row,
+            column,
+        }))
+    }
+
+    /**
+     * Returns a bytea field value, decoded from the text output of the server if needed.
+     *
+     * The hex format is decoded with [`crate::escape::decode_hex_bytea`], the escape format with
+     * [`crate::escape::decode_escape_bytea`].
+     */
+    pub fn bytea(&self, row: usize, column: usize) -> crate::errors::Result<Option<Vec<u8>>> {
+        let mut bytea = Vec::new();
+
+        if self.bytea_into(row, column, &mut bytea)? {
+            Ok(Some(bytea))
+        } else {
+            Ok(None)
+        }
+    }
+
+    /**
+     * Like [`Result::bytea`], appending the value to a buffer of the caller, which can be reused
+     * across rows. Returns `false` for a null value.
+     */
+    pub fn bytea_into(
+        &self,
+        row: usize,
+        column: usize,
+        buffer: &mut Vec<u8>,
+    ) -> crate::errors::Result<bool> {
+        let value = match self.value(row, column) {
+            Some(value) => value,
+            None => return Ok(false),
+        };
+
+        match self.field_format(column) {
+            crate::Format::Binary => buffer.extend_from_slice(value),
+            crate::Format::Text if value.starts_with(b"\\x") => {
+                crate::escape::decode_hex_bytea_into(value, buffer)?
+            }
+            crate::Format::Text => crate::escape::decode_escape_bytea_into(value, buffer)?,
+        }
+
+        Ok(true)
+    }
+
     /**
      * Tests a field for a null value.
      *
@@ -257,19 +400,59 @@ impl Result {
     }
 
     /**
-     * Prints out all the rows and, optionally, the column names to the specified output stream.
+     * Returns the data types of all the parameters of a prepared statement, as described by
+     * [`Connection::describe_prepared`](crate::Connection::describe_prepared), to check or convert
+     * values before executing it.
+     *
+     * A type unknown to [`crate::types`], like a user-defined type, is returned with its oid, an
+     * empty name and the [`Kind::Unknow`](crate::types::Kind::Unknow) kind: with the
+     * `with-postgres-types` feature, `Connection::sql_type` reads its definition from the catalog.
+     *
+     * ```no_run
+     * # let conn = libpq::Connection::new("")?;
+     * conn.prepare(Some("find"), "SELECT * FROM users WHERE id = $1 AND name = $2", &[]);
+     * let description = conn.describe_prepared(Some("find"));
+     *
+     * assert_eq!(
+     *     description.param_types(),
+     *     [libpq::types::INT4, libpq::types::TEXT]
+     * );
+     * # Ok::<(), libpq::errors::Error>(())
+     * ```
+     */
+    pub fn param_types(&self) -> Vec<crate::Type> {
+        (0..self.nparams())
+            .map(|x| {
+                let oid = self.param_type(x).unwrap_or(crate::oid::INVALID);
+
+                crate::Type::try_from(oid).unwrap_or(crate::Type {
+                    oid,
+                    descr: "",
+                    name: "",
+                    kind: crate::types::Kind::Unknow,
+                })
+            })
+            .collect()
+    }
+
+    /**
+     * Prints out all the rows and, optionally, the column names to `output`.
+     *
+     * libpq writes to a temporary `FILE`, copied to `output` afterwards.
      *
      * See [PQprint](https://www.postgresql.org/docs/current/libpq-exec.html#LIBPQ-PQPRINT).
      */
     #[cfg(unix)]
-    pub fn print(&self, output: &dyn std::os::unix::io::AsRawFd, option: &crate::print::Options) {
-        let c_mode = crate::ffi::to_cstr("w");
-
-        let (_c_field_name, ptr_field_name) = crate::ffi::vec_to_nta(&option.field_name);
+    pub fn print<W: std::io::Write>(
+        &self,
+        mut output: W,
+        option: &crate::print::Options,
+    ) -> crate::errors::Result {
+        let (_c_field_name, ptr_field_name) = crate::ffi::vec_to_nta(&option.field_name)?;
 
-        let c_field_sep = crate::ffi::to_cstr(&option.field_sep);
-        let c_table_opt = crate::ffi::to_cstr(&option.table_opt);
-        let c_caption = crate::ffi::to_cstr(&option.caption);
+        let c_field_sep = crate::ffi::try_to_cstr(&option.field_sep)?;
+        let c_table_opt = crate::ffi::try_to_cstr(&option.table_opt)?;
+        let c_caption = crate::ffi::try_to_cstr(&option.caption)?;
 
         let c_option = pq_sys::_PQprintOpt {
             header: option.header as pq_sys::pqbool,
@@ -284,11 +467,50 @@ impl Result {
             fieldName: ptr_field_name.as_ptr() as *mut *mut libc::c_char,
         };
 
-        unsafe {
-            let stream = libc::fdopen(output.as_raw_fd(), c_mode.as_ptr());
+        let io_error = |err: std::io::Error| crate::errors::Error::Backend(err.to_string());
+
+        let stream = unsafe { libc::tmpfile() };
 
-            pq_sys::PQprint(stream as *mut _, self.into(), &c_option);
+        if stream.is_null() {
+            return Err(io_error(std::io::Error::last_os_error()));
         }
+
+        let mut copy = || {
+            unsafe {
+                pq_sys::PQprint(stream as *mut _, self.into(), &c_option);
+                libc::rewind(stream);
+            }
+
+            let mut buffer = [0u8; 8192];
+
+            loop {
+                let n = unsafe {
+                    libc::fread(
+                        buffer.as_mut_ptr() as *mut libc::c_void,
+                        1,
+                        buffer.len(),
+                        stream,
+                    )
+                };
+
+                if n == 0 {
+                    break;
+                }
+
+                output.write_all(&buffer[..n]).map_err(io_error)?;
+            }
+
+            if unsafe { libc::ferror(stream) } != 0 {
+                return Err(io_error(std::io::Error::last_os_error()));
+            }
+
+            output.flush().map_err(io_error)
+        };
+
+        let result = copy();
+        unsafe { libc::fclose(stream) };
+
+        result
     }
 
     /**
@@ -297,7 +519,19 @@ impl Result {
      * See [PQcmdStatus](https://www.postgresql.org/docs/current/libpq-exec.html#LIBPQ-PQCMDSTATUS).
      */
     pub fn cmd_status(&self) -> crate::errors::Result<Option<String>> {
-        crate::ffi::to_option_string(unsafe { pq_sys::PQcmdStatus(self.into()) })
+        let raw = unsafe { pq_sys::PQcmdStatus(self.into()) };
+
+        if raw.is_null() {
+            return Ok(None);
+        }
+
+        let status = unsafe { std::ffi::CStr::from_ptr(raw) }.to_bytes();
+
+        if status.is_empty() {
+            Ok(None)
+        } else {
+            Ok(Some(self.encoding.decode_strict(status)?.into_owned()))
+        }
     }
 
     /**
@@ -312,10 +546,39 @@ impl Result {
     }
 
     /**
-     * Returns the OID of the inserted row.
+     * Returns the number of rows affected by an `INSERT`, `UPDATE`, `DELETE`, `MERGE`, `SELECT`,
+     * `CREATE TABLE AS`, `MOVE`, `FETCH` or `COPY` command, `None` for the other commands.
+     *
+     * The count of [`Result::cmd_tuples`] is read from the [`Result::cmd_status`] tag when libpq
+     * doesn't report it, like for `MERGE` before libpq 15.
+     */
+    pub fn rows_affected(&self) -> Option<u64> {
+        let tag = self.cmd_status().ok()??;
+        let command = tag.split(' ').next()?;
+
+        if !matches!(
+            command,
+            "INSERT" | "UPDATE" | "DELETE" | "MERGE" | "SELECT" | "MOVE" | "FETCH" | "COPY"
+        ) {
+            return None;
+        }
+
+        let ntuples = crate::ffi::to_string(unsafe { pq_sys::PQcmdTuples(self.into()) }).ok()?;
+
+        if ntuples.is_empty() {
+            tag.rsplit(' ').next()?.parse().ok()
+        } else {
+            ntuples.parse().ok()
+        }
+    }
+
+    /**
+     * Returns the OID of the row inserted by a single-row `INSERT` into a table created `WITH OIDS`,
+     * which requires a server older than PostgreSQL 12.
      *
      * See [PQoidValue](https://www.postgresql.org/docs/current/libpq-exec.html#LIBPQ-PQOIDVALUE).
      */
+    #[doc(alias = "inserted_oid")]
     pub fn oid_value(&self) -> Option<crate::Oid> {
         let oid = unsafe { pq_sys::PQoidValue(self.into()) };
 
@@ -339,16 +602,23 @@ impl Result {
     /**
      * Makes a copy of a `Result` object.
      *
+     * The copy is independent of the original, it can be modified with
+     * [`Result::set_attrs`] and [`Result::set_value`]. With [`CopyFlags::EVENTS`], the
+     * `PGEVT_RESULTCOPY` event is fired for the registered [`Event`](crate::event::Event)s.
+     *
      * See
      * [PQcopyResult](https://www.postgresql.org/docs/current/libpq-misc.html#LIBPQ-PQCOPYRESULT).
      */
-    pub fn copy(&self, flags: i32) -> crate::errors::Result<Self> {
-        let raw = unsafe { pq_sys::PQcopyResult(self.into(), flags) };
+    pub fn copy(&self, flags: CopyFlags) -> crate::errors::Result<Self> {
+        let raw = unsafe { pq_sys::PQcopyResult(self.into(), flags.bits() as i32) };
 
         if raw.is_null() {
-            Err(crate::errors::Error::Unknow)
+            Err(crate::errors::Error::CallFailed("PQcopyResult"))
         } else {
-            Ok(raw.into())
+            let mut copy = Self::from(raw);
+            copy.encoding = self.encoding;
+
+            Ok(copy)
         }
     }
 
@@ -370,7 +640,7 @@ impl Result {
         };
 
         if success == 0 {
-            Err(crate::errors::Error::Unknow)
+            Err(crate::errors::Error::CallFailed("PQsetResultAttrs"))
         } else {
             Ok(())
         }
@@ -387,18 +657,18 @@ impl Result {
         field: usize,
         value: Option<&str>,
     ) -> crate::errors::Result {
-        let (v, len) = if let Some(v) = value {
-            let cstring = std::ffi::CString::new(v)?;
-            (cstring.into_raw(), v.len() as i32)
-        } else {
-            (std::ptr::null_mut(), -1)
+        // libpq copies the value, the string only has to outlive the call.
+        let value = value.map(crate::ffi::try_to_cstr).transpose()?;
+        let (v, len) = match &value {
+            Some(v) => (v.as_ptr() as *mut _, v.as_bytes().len() as i32),
+            None => (std::ptr::null_mut(), -1),
         };
 
         let success =
-            unsafe { pq_sys::PQsetvalue(self.into(), tuple as i32, field as i32, v, len as i32) };
+            unsafe { pq_sys::PQsetvalue(self.into(), tuple as i32, field as i32, v, len) };
 
         if success == 0 {
-            Err(crate::errors::Error::Unknow)
+            Err(crate::errors::Error::CallFailed("PQsetvalue"))
         } else {
             Ok(())
         }
@@ -418,7 +688,7 @@ impl Result {
         let space = pq_sys::PQresultAlloc(self.into(), nbytes as pq_sys::size_t);
 
         if space.is_null() {
-            Err(crate::errors::Error::Unknow)
+            Err(crate::errors::Error::CallFailed("PQresultAlloc"))
         } else {
             Ok(space)
         }
@@ -434,6 +704,70 @@ impl Result {
         unsafe { pq_sys::PQresultMemorySize(self.into()) }
     }
 
+    /**
+     * Attaches `data` to this result for the event `E`, replacing the previous one.
+     *
+     * It fails if `E` is not registered on the connection that created this result. The data is
+     * dropped with the result.
+     *
+     * See [PQresultSetInstanceData](https://www.postgresql.org/docs/current/libpq-events.html#LIBPQ-PQRESULTSETINSTANCEDATA).
+     */
+    pub fn set_instance_data<E, T>(&mut self, data: T) -> crate::errors::Result
+    where
+        E: crate::event::Event,
+        T: std::any::Any + Send + Sync,
+    {
+        let proc: pq_sys::PGEventProc = Some(crate::event::proc::<E>);
+        let ptr = crate::event::into_raw(std::sync::Arc::new(data));
+
+        unsafe {
+            let previous = pq_sys::PQresultInstanceData(self.into(), proc);
+
+            if pq_sys::PQresultSetInstanceData(self.into(), proc, ptr) == 0 {
+                drop(crate::event::from_raw(ptr));
+                Err(crate::errors::Error::CallFailed("PQresultSetInstanceData"))
+            } else {
+                drop(crate::event::from_raw(previous));
+                Ok(())
+            }
+        }
+    }
+
+    /**
+     * Returns the data attached to this result for the event `E`, if it is a `T`.
+     *
+     * See [PQresultInstanceData](https://www.postgresql.org/docs/current/libpq-events.html#LIBPQ-PQRESULTINSTANCEDATA).
+     */
+    pub fn instance_data<E, T>(&self) -> Option<std::sync::Arc<T>>
+    where
+        E: crate::event::Event,
+        T: std::any::Any + Send + Sync,
+    {
+        unsafe {
+            let ptr = pq_sys::PQresultInstanceData(self.into(), Some(crate::event::proc::<E>));
+
+            crate::event::downcast(ptr)
+        }
+    }
+
+    /**
+     * Fires the `PGEVT_RESULTCREATE` event of the [`Event`](crate::event::Event)s registered on
+     * `conn`, for a result created with [`Result::new`] or [`Result::copy`].
+     *
+     * Events already fired for this result are skipped. It fails if an event returns `false`.
+     *
+     * See [PQfireResultCreateEvents](https://www.postgresql.org/docs/current/libpq-events.html#LIBPQ-PQFIRERESULTCREATEEVENTS).
+     */
+    pub fn fire_create_events(&mut self, conn: &crate::Connection) -> crate::errors::Result {
+        let success = unsafe { pq_sys::PQfireResultCreateEvents(conn.into(), self.into()) };
+
+        if success == 0 {
+            Err(crate::errors::Error::CallFailed("PQfireResultCreateEvents"))
+        } else {
+            Ok(())
+        }
+    }
+
     /**
      * Really old printing routines.
      */
@@ -445,15 +779,15 @@ impl Result {
         field_sep: Option<&str>,
         print_header: bool,
         quiet: bool,
-    ) {
+    ) -> crate::errors::Result {
         use std::os::unix::io::IntoRawFd;
 
         let c_mode = crate::ffi::to_cstr("w");
+        let c_sep = field_sep.map(crate::ffi::try_to_cstr).transpose()?;
 
         unsafe {
             let fp = libc::fdopen(file.into_raw_fd(), c_mode.as_ptr());
 
-            let c_sep = field_sep.map(crate::ffi::to_cstr);
             let sep = if let Some(c_sep) = c_sep {
                 c_sep.as_ptr()
             } else {
@@ -469,6 +803,43 @@ impl Result {
                 quiet as i32,
             );
         }
+
+        Ok(())
+    }
+
+    /**
+     * Decodes a string owned by the result, replacing the invalid byte sequences, `None` if it's
+     * null or empty.
+     */
+    fn decode_lossy(&self, raw: *const std::os::raw::c_char) -> Option<String> {
+        if raw.is_null() {
+            return None;
+        }
+
+        let bytes = unsafe { std::ffi::CStr::from_ptr(raw) }.to_bytes();
+
+        if bytes.is_empty() {
+            None
+        } else {
+            Some(self.encoding.decode(bytes).into_owned())
+        }
+    }
+}
+
+/**
+ * Owner of the bytes returned by [`Result::value_bytes_shared`].
+ */
+#[cfg(feature = "bytes")]
+struct SharedValue {
+    result: std::sync::Arc<Result>,
+    row: usize,
+    column: usize,
+}
+
+#[cfg(feature = "bytes")]
+impl AsRef<[u8]> for SharedValue {
+    fn as_ref(&self) -> &[u8] {
+        self.result.value(self.row, self.column).unwrap_or_default()
     }
 }
 
@@ -485,7 +856,14 @@ impl Drop for Result {
 #[doc(hidden)]
 impl From<*mut pq_sys::PGresult> for Result {
     fn from(result: *mut pq_sys::PGresult) -> Self {
-        Result { result }
+        Result {
+            result,
+            notices: Vec::new(),
+            error: None,
+            encoding: crate::Encoding::UTF8,
+            #[cfg(feature = "with-postgres-types")]
+            sql_types: Vec::new(),
+        }
     }
 }
 
@@ -522,6 +900,7 @@ impl std::fmt::Debug for Result {
             .field("cmd_tuples", &self.cmd_tuples())
             .field("oid_value", &self.oid_value())
             .field("nparams", &self.nparams())
+            .field("notices", &self.notices)
             .finish()
     }
 }
@@ -0,0 +1,178 @@
+/**
+ * A row of a [`Result`](crate::Result), see [`Result::rows`](crate::Result::rows).
+ */
+#[derive(Clone, Copy, Debug)]
+pub struct Row<'a> {
+    result: &'a crate::Result,
+    index: usize,
+}
+
+impl<'a> Row<'a> {
+    pub(crate) fn new(result: &'a crate::Result, index: usize) -> Self {
+        Self { result, index }
+    }
+
+    /**
+     * Returns the result this row belongs to.
+     */
+    pub fn result(&self) -> &'a crate::Result {
+        self.result
+    }
+
+    /**
+     * Returns the number of the row in the result.
+     */
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /**
+     * Returns the number of columns.
+     */
+    pub fn len(&self) -> usize {
+        self.result.nfields()
+    }
+
+    /**
+     * Returns `true` if the row has no columns.
+     */
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /**
+     * See [`Result::value`](crate::Result::value).
+     */
+    pub fn value(&self, column: usize) -> Option<&'a [u8]> {
+        self.result.value(self.index, column)
+    }
+
+    /**
+     * Returns a value of a text format column, decoded with the
+     * [`Result::encoding`](crate::Result::encoding).
+     *
+     * It's only borrowed from the result when no conversion is needed, like for `UTF8`.
+     */
+    pub fn text(&self, column: usize) -> crate::errors::Result<Option<std::borrow::Cow<'a, str>>> {
+        self.value(column)
+            .map(|x| self.result.encoding().decode_strict(x))
+            .transpose()
+    }
+
+    /**
+     * Parses a value of a text format column.
+     */
+    pub fn parse<T>(&self, column: usize) -> crate::errors::Result<Option<T>>
+    where
+        T: std::str::FromStr,
+        T::Err: std::fmt::Display,
+    {
+        self.text(column)?
+            .map(|x| {
+                x.parse()
+                    .map_err(|err: T::Err| crate::errors::Error::Backend(err.to_string()))
+            })
+            .transpose()
+    }
+}
+
+/**
+ * Decodes a value from a [`Row`], see [`Result::to_vec`](crate::Result::to_vec).
+ *
+ * ```no_run
+ * struct User {
+ *     id: i32,
+ *     name: Option<String>,
+ * }
+ *
+ * impl libpq::result::FromRow for User {
+ *     fn from_row(row: &libpq::result::Row) -> libpq::errors::Result<Self> {
+ *         Ok(Self {
+ *             id: row.parse(0)?.unwrap_or_default(),
+ *             name: row.text(1)?.map(String::from),
+ *         })
+ *     }
+ * }
+ * ```
+ */
+pub trait FromRow: Sized {
+    fn from_row(row: &Row) -> crate::errors::Result<Self>;
+}
+
+impl crate::Result {
+    /**
+     * Iterates over the rows of the result.
+     */
+    pub fn rows(&self) -> impl ExactSizeIterator<Item = Row<'_>> + DoubleEndedIterator {
+        (0..self.ntuples()).map(|x| Row::new(self, x))
+    }
+
+    /**
+     * Decodes all the rows of the result.
+     */
+    pub fn to_vec<T: FromRow>(&self) -> crate::errors::Result<Vec<T>> {
+        self.rows().map(|x| T::from_row(&x)).collect()
+    }
+
+    /**
+     * Like [`Result::rows`], as a parallel iterator.
+     */
+    #[cfg(feature = "rayon")]
+    pub fn par_rows(&self) -> impl rayon::iter::IndexedParallelIterator<Item = Row<'_>> {
+        use rayon::iter::{IntoParallelIterator, ParallelIterator};
+
+        (0..self.ntuples())
+            .into_par_iter()
+            .map(|x| Row::new(self, x))
+    }
+
+    /**
+     * Like [`Result::to_vec`], decoding the rows across the threads of the rayon pool.
+     */
+    #[cfg(feature = "rayon")]
+    pub fn par_to_vec<T: FromRow + Send>(&self) -> crate::errors::Result<Vec<T>> {
+        use rayon::iter::ParallelIterator;
+
+        self.par_rows().map(|x| T::from_row(&x)).collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #[derive(Debug, PartialEq)]
+    struct Row {
+        id: i32,
+        name: Option<String>,
+    }
+
+    impl crate::result::FromRow for Row {
+        fn from_row(row: &crate::result::Row) -> crate::errors::Result<Self> {
+            Ok(Self {
+                id: row.parse(0)?.unwrap_or_default(),
+                name: row.text(1)?.map(String::from),
+            })
+        }
+    }
+
+    #[test]
+    fn to_vec() {
+        let conn = crate::test::new_conn();
+        let result = conn.exec(
+            "SELECT x, CASE WHEN x % 2 = 0 THEN 'name' || x END FROM generate_series(1, 100) x",
+        );
+
+        let rows = result.to_vec::<Row>().unwrap();
+        assert_eq!(rows.len(), 100);
+        assert_eq!(
+            rows[1],
+            Row {
+                id: 2,
+                name: Some("name2".to_string())
+            }
+        );
+        assert_eq!(rows[2].name, None);
+
+        #[cfg(feature = "rayon")]
+        assert_eq!(result.par_to_vec::<Row>().unwrap(), rows);
+    }
+}
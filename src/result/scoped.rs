@@ -0,0 +1,62 @@
+/**
+ * A [`Result`](crate::Result) which can't outlive the connection it comes from, see
+ * [`Connection::scoped_exec`](crate::Connection::scoped_exec).
+ *
+ * libpq results stay valid after `PQfinish`, but data attached by the caller to the connection
+ * (event instance data, memory owned by a notice receiver…) doesn't: the borrow makes the
+ * compiler reject code keeping such a result after the connection is dropped.
+ */
+#[derive(Debug)]
+pub struct Scoped<'conn> {
+    result: crate::Result,
+    _conn: std::marker::PhantomData<&'conn crate::Connection>,
+}
+
+impl<'conn> Scoped<'conn> {
+    /**
+     * Ties `result` to the lifetime of `conn`.
+     */
+    pub fn new(_conn: &'conn crate::Connection, result: crate::Result) -> Self {
+        Self {
+            result,
+            _conn: std::marker::PhantomData,
+        }
+    }
+
+    /**
+     * Releases the result from the connection lifetime.
+     */
+    pub fn into_inner(self) -> crate::Result {
+        self.result
+    }
+}
+
+impl std::ops::Deref for Scoped<'_> {
+    type Target = crate::Result;
+
+    fn deref(&self) -> &Self::Target {
+        &self.result
+    }
+}
+
+impl AsRef<crate::Result> for Scoped<'_> {
+    fn as_ref(&self) -> &crate::Result {
+        &self.result
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #[test]
+    fn scoped_exec() {
+        let conn = crate::test::new_conn();
+        let result = conn.scoped_exec("SELECT 1");
+
+        assert_eq!(result.status(), crate::Status::TupplesOk);
+        assert_eq!(result.value(0, 0), Some(&b"1"[..]));
+
+        let result = result.into_inner();
+        drop(conn);
+        assert_eq!(result.ntuples(), 1);
+    }
+}
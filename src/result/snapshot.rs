@@ -0,0 +1,270 @@
+/**
+ * Owned copy of a [`Result`](crate::Result), detached from libpq, see
+ * [`Result::snapshot`](crate::Result::snapshot).
+ *
+ * With the `serde` feature, it can be serialized, to be cached or sent to another process, and
+ * turned back into a `Result` with [`ResultSnapshot::to_result`] to be read with the usual
+ * accessors.
+ *
+ * With the `rkyv` feature, [`ResultSnapshot::to_bytes`] archives it in a form readable in place,
+ * for example from a memory mapped file, with [`ResultSnapshot::access`].
+ */
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "rkyv",
+    derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)
+)]
+pub struct ResultSnapshot {
+    status: crate::Status,
+    attributes: Vec<crate::result::Attribute>,
+    rows: Vec<Vec<Option<Vec<u8>>>>,
+}
+
+impl ResultSnapshot {
+    /**
+     * See [`Result::status`](crate::Result::status).
+     */
+    pub fn status(&self) -> crate::Status {
+        self.status
+    }
+
+    /**
+     * Returns the metadata of the columns.
+     */
+    pub fn attributes(&self) -> &[crate::result::Attribute] {
+        &self.attributes
+    }
+
+    /**
+     * See [`Result::ntuples`](crate::Result::ntuples).
+     */
+    pub fn ntuples(&self) -> usize {
+        self.rows.len()
+    }
+
+    /**
+     * See [`Result::nfields`](crate::Result::nfields).
+     */
+    pub fn nfields(&self) -> usize {
+        self.attributes.len()
+    }
+
+    /**
+     * See [`Result::value`](crate::Result::value).
+     */
+    pub fn value(&self, row: usize, column: usize) -> Option<&[u8]> {
+        self.rows.get(row)?.get(column)?.as_deref()
+    }
+
+    /**
+     * Creates a new `Result` with the status, columns and values of the snapshot.
+     */
+    pub fn to_result(&self) -> crate::errors::Result<crate::Result> {
+        let raw = unsafe { pq_sys::PQmakeEmptyPGresult(std::ptr::null_mut(), self.status.into()) };
+
+        if raw.is_null() {
+            return Err(crate::errors::Error::CallFailed("PQmakeEmptyPGresult"));
+        }
+
+        let mut result = crate::Result::from(raw);
+
+        if !self.attributes.is_empty() {
+            result.set_attrs(&self.attributes.iter().collect::<Vec<_>>())?;
+        }
+
+        for (tuple, row) in self.rows.iter().enumerate() {
+            for (field, value) in row.iter().enumerate() {
+                let (v, len) = match value {
+                    Some(value) => (value.as_ptr() as *mut libc::c_char, value.len() as i32),
+                    None => (std::ptr::null_mut(), -1),
+                };
+
+                // The value is copied by libpq.
+                let success = unsafe {
+                    pq_sys::PQsetvalue((&result).into(), tuple as i32, field as i32, v, len)
+                };
+
+                if success == 0 {
+                    return Err(crate::errors::Error::CallFailed("PQsetvalue"));
+                }
+            }
+        }
+
+        Ok(result)
+    }
+}
+
+#[cfg(feature = "rkyv")]
+impl ResultSnapshot {
+    /**
+     * Archives the snapshot in the [rkyv](https://rkyv.org) format.
+     */
+    pub fn to_bytes(&self) -> crate::errors::Result<rkyv::util::AlignedVec> {
+        rkyv::to_bytes::<rkyv::rancor::Error>(self).map_err(rkyv_error)
+    }
+
+    /**
+     * Checks `bytes`, created by [`ResultSnapshot::to_bytes`], and returns the archived snapshot
+     * they contain, without deserializing it.
+     *
+     * `bytes` must be aligned like the buffer returned by `to_bytes`, a memory mapped file is.
+     */
+    pub fn access(bytes: &[u8]) -> crate::errors::Result<&ArchivedResultSnapshot> {
+        rkyv::access::<ArchivedResultSnapshot, rkyv::rancor::Error>(bytes).map_err(rkyv_error)
+    }
+}
+
+#[cfg(feature = "rkyv")]
+impl ArchivedResultSnapshot {
+    /**
+     * See [`Result::ntuples`](crate::Result::ntuples).
+     */
+    pub fn ntuples(&self) -> usize {
+        self.rows.len()
+    }
+
+    /**
+     * See [`Result::nfields`](crate::Result::nfields).
+     */
+    pub fn nfields(&self) -> usize {
+        self.attributes.len()
+    }
+
+    /**
+     * Returns the name of `column`, `None` if it's out of range.
+     */
+    pub fn field_name(&self, column: usize) -> Option<&str> {
+        self.attributes.get(column).map(|x| x.name.as_str())
+    }
+
+    /**
+     * Returns the type of `column`, `None` if it's out of range.
+     */
+    pub fn field_type(&self, column: usize) -> Option<crate::Oid> {
+        self.attributes.get(column).map(|x| x.typid.to_native())
+    }
+
+    /**
+     * See [`Result::value`](crate::Result::value).
+     */
+    pub fn value(&self, row: usize, column: usize) -> Option<&[u8]> {
+        self.rows
+            .get(row)?
+            .get(column)?
+            .as_ref()
+            .map(|x| x.as_slice())
+    }
+
+    /**
+     * Deserializes the archived snapshot.
+     */
+    pub fn deserialize(&self) -> crate::errors::Result<ResultSnapshot> {
+        rkyv::deserialize::<ResultSnapshot, rkyv::rancor::Error>(self).map_err(rkyv_error)
+    }
+}
+
+#[cfg(feature = "rkyv")]
+fn rkyv_error(err: rkyv::rancor::Error) -> crate::errors::Error {
+    crate::errors::Error::Backend(err.to_string())
+}
+
+impl TryFrom<&crate::Result> for ResultSnapshot {
+    type Error = crate::errors::Error;
+
+    fn try_from(result: &crate::Result) -> crate::errors::Result<Self> {
+        let attributes = (0..result.nfields())
+            .map(|x| {
+                Ok(crate::result::Attribute {
+                    name: result.field_name(x)?.unwrap_or_default(),
+                    tableid: result.field_table(x).unwrap_or(crate::oid::INVALID),
+                    columnid: result.field_tablecol(x) as i32,
+                    format: result.field_format(x) as i32,
+                    typid: result.field_type(x),
+                    typlen: result.field_size(x).map_or(-1, |x| x as i32),
+                    atttypmod: result.field_mod(x).unwrap_or(-1),
+                })
+            })
+            .collect::<crate::errors::Result<_>>()?;
+
+        let rows = (0..result.ntuples())
+            .map(|row| {
+                (0..result.nfields())
+                    .map(|column| result.value(row, column).map(<[u8]>::to_vec))
+                    .collect()
+            })
+            .collect();
+
+        Ok(Self {
+            status: result.status(),
+            attributes,
+            rows,
+        })
+    }
+}
+
+impl crate::Result {
+    /**
+     * Copies the status, columns and values of the result in a [`ResultSnapshot`].
+     */
+    pub fn snapshot(&self) -> crate::errors::Result<ResultSnapshot> {
+        self.try_into()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #[test]
+    fn snapshot() {
+        let conn = crate::test::new_conn();
+        let result =
+            conn.exec("SELECT x AS id, NULLIF('', '') AS name FROM generate_series(1, 3) x");
+        let snapshot = result.snapshot().unwrap();
+
+        assert_eq!(snapshot.status(), crate::Status::TupplesOk);
+        assert_eq!(snapshot.ntuples(), 3);
+        assert_eq!(snapshot.nfields(), 2);
+        assert_eq!(snapshot.value(1, 0), Some(&b"2"[..]));
+        assert_eq!(snapshot.value(1, 1), None);
+
+        let copy = snapshot.to_result().unwrap();
+        assert_eq!(copy.status(), result.status());
+        assert_eq!(copy.columns(), result.columns());
+        assert_eq!(copy.value(2, 0), Some(&b"3"[..]));
+        assert!(copy.is_null(2, 1));
+        assert_eq!(copy.snapshot().unwrap(), snapshot);
+    }
+
+    #[test]
+    #[cfg(all(feature = "serde", feature = "serde_json"))]
+    fn serde() {
+        let conn = crate::test::new_conn();
+        let snapshot = conn.exec("SELECT 1 AS one").snapshot().unwrap();
+
+        let json = serde_json::to_string(&snapshot).unwrap();
+        let snapshot2: crate::result::ResultSnapshot = serde_json::from_str(&json).unwrap();
+        assert_eq!(snapshot2, snapshot);
+    }
+
+    #[test]
+    #[cfg(feature = "rkyv")]
+    fn rkyv() {
+        let conn = crate::test::new_conn();
+        let snapshot = conn
+            .exec("SELECT 1 AS one, NULL::text AS two")
+            .snapshot()
+            .unwrap();
+
+        let bytes = snapshot.to_bytes().unwrap();
+        let archived = crate::result::ResultSnapshot::access(&bytes).unwrap();
+        assert_eq!(archived.ntuples(), 1);
+        assert_eq!(archived.nfields(), 2);
+        assert_eq!(archived.field_name(1), Some("two"));
+        assert_eq!(archived.field_type(1), Some(crate::types::TEXT.oid));
+        assert_eq!(archived.value(0, 0), Some(&b"1"[..]));
+        assert_eq!(archived.value(0, 1), None);
+        assert_eq!(archived.deserialize().unwrap(), snapshot);
+
+        assert!(crate::result::ResultSnapshot::access(&[0xff; 64]).is_err());
+    }
+}
@@ -0,0 +1,397 @@
+/**
+ * When [`Result::format_table`](crate::Result::format_table) displays each row as a list of
+ * column name and value pairs, like `\x` in `psql`.
+ */
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Expanded {
+    /** Rows are displayed on a single line. */
+    #[default]
+    Off,
+    /** Rows are displayed on several lines. */
+    On,
+    /** Rows are displayed on several lines if the table is wider than the given width. */
+    Auto(usize),
+}
+
+/**
+ * Options of [`Result::format_table`](crate::Result::format_table).
+ */
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TableOptions {
+    /** Draws a frame around the table, like `\pset border 2` in `psql`. */
+    pub border: bool,
+    /** See [`Expanded`]. */
+    pub expanded: Expanded,
+    /** String displayed for the null values. */
+    pub null: String,
+    /** Displays the number of rows after the table. */
+    pub footer: bool,
+}
+
+impl Default for TableOptions {
+    fn default() -> Self {
+        Self {
+            border: false,
+            expanded: Expanded::Off,
+            null: String::new(),
+            footer: true,
+        }
+    }
+}
+
+impl crate::Result {
+    /**
+     * Formats the rows as an aligned text table, like `psql`.
+     *
+     * Numeric columns are right aligned, binary values are displayed in hexadecimal.
+     *
+     * ```no_run
+     * # let conn = libpq::Connection::new("")?;
+     * let result = conn.exec("SELECT 1 AS id, 'foo' AS name");
+     *
+     * assert_eq!(
+     *     result.format_table(&Default::default())?,
+     *     " id | name\n----+------\n  1 | foo\n(1 row)\n"
+     * );
+     * # Ok::<(), libpq::errors::Error>(())
+     * ```
+     */
+    pub fn format_table(&self, options: &TableOptions) -> crate::errors::Result<String> {
+        let headers = (0..self.nfields())
+            .map(|x| Ok(self.field_name(x)?.unwrap_or_default()))
+            .collect::<crate::errors::Result<Vec<_>>>()?;
+
+        let right = (0..self.nfields())
+            .map(|x| {
+                matches!(
+                    crate::Type::try_from(self.field_type(x)).map(|x| x.kind),
+                    Ok(crate::types::Kind::Numeric)
+                )
+            })
+            .collect::<Vec<_>>();
+
+        let rows = (0..self.ntuples())
+            .map(|row| {
+                (0..self.nfields())
+                    .map(|column| self.display_value(row, column, &options.null))
+                    .collect()
+            })
+            .collect::<Vec<Vec<_>>>();
+
+        let table = Table {
+            headers,
+            right,
+            rows,
+        };
+
+        let expanded = match options.expanded {
+            Expanded::Off => false,
+            Expanded::On => true,
+            Expanded::Auto(max) => table.width(options.border) > max,
+        };
+
+        let mut output = if expanded {
+            table.expanded(options.border)
+        } else {
+            table.aligned(options.border)
+        };
+
+        if options.footer && (!expanded || table.rows.is_empty()) {
+            let n = table.rows.len();
+            output.push_str(&format!("({n} row{})\n", if n == 1 { "" } else { "s" }));
+        }
+
+        Ok(output)
+    }
+
+    fn display_value(&self, row: usize, column: usize, null: &str) -> String {
+        match self.value(row, column) {
+            None => null.to_string(),
+            Some(value) if self.field_format(column) == crate::Format::Binary => {
+                let hex = value.iter().map(|x| format!("{x:02x}")).collect::<String>();
+
+                format!("\\x{hex}")
+            }
+            Some(value) => self.encoding().decode(value).into_owned(),
+        }
+    }
+}
+
+struct Table {
+    headers: Vec<String>,
+    right: Vec<bool>,
+    rows: Vec<Vec<String>>,
+}
+
+impl Table {
+    fn widths(&self) -> Vec<usize> {
+        self.headers
+            .iter()
+            .enumerate()
+            .map(|(x, header)| {
+                self.rows
+                    .iter()
+                    .map(|row| lines_width(&row[x]))
+                    .fold(width(header), usize::max)
+            })
+            .collect()
+    }
+
+    /**
+     * Width of the aligned table.
+     */
+    fn width(&self, border: bool) -> usize {
+        let widths = self.widths();
+        let inner = widths.iter().map(|x| x + 3).sum::<usize>();
+
+        if border {
+            inner + 1
+        } else {
+            inner.saturating_sub(1)
+        }
+    }
+
+    fn aligned(&self, border: bool) -> String {
+        let widths = self.widths();
+        let mut output = String::new();
+
+        let rule = widths
+            .iter()
+            .map(|x| "-".repeat(x + 2))
+            .collect::<Vec<_>>()
+            .join("+");
+        let rule = if border {
+            format!("+{rule}+\n")
+        } else {
+            format!("{rule}\n")
+        };
+
+        if border {
+            output.push_str(&rule);
+        }
+
+        let headers = self
+            .headers
+            .iter()
+            .zip(&widths)
+            .map(|(header, width)| center(header, *width))
+            .collect::<Vec<_>>();
+        output.push_str(&line(&headers, border));
+        output.push_str(&rule);
+
+        for row in &self.rows {
+            let height = row.iter().map(|x| x.split('\n').count()).max().unwrap_or(1);
+
+            for n in 0..height {
+                let cells = row
+                    .iter()
+                    .zip(&widths)
+                    .zip(&self.right)
+                    .map(|((value, width), right)| {
+                        let value = value.split('\n').nth(n).unwrap_or_default();
+
+                        pad(value, *width, *right)
+                    })
+                    .collect::<Vec<_>>();
+                output.push_str(&line(&cells, border));
+            }
+        }
+
+        if border && !self.rows.is_empty() {
+            output.push_str(&rule);
+        }
+
+        output
+    }
+
+    fn expanded(&self, border: bool) -> String {
+        let name_width = self.headers.iter().map(|x| width(x)).max().unwrap_or(0);
+        let value_width = self
+            .rows
+            .iter()
+            .flatten()
+            .map(|x| lines_width(x))
+            .max()
+            .unwrap_or(0);
+        // Widens the values for the longest record title to fit, followed by at least a dash.
+        let (frame, title_frame) = if border { (7, 3) } else { (3, 1) };
+        let title_width = width(&record_title(self.rows.len())) + title_frame;
+        let value_width = value_width.max(title_width.saturating_sub(frame + name_width));
+        let total = name_width + value_width + frame;
+
+        let mut output = String::new();
+
+        for (x, row) in self.rows.iter().enumerate() {
+            let title = record_title(x + 1);
+
+            if border {
+                let fill = "-".repeat(total - 2 - width(&title));
+                output.push_str(&format!("+{title}{fill}+\n"));
+            } else {
+                let fill = "-".repeat(total - width(&title));
+                output.push_str(&format!("{title}{fill}\n"));
+            }
+
+            for (header, value) in self.headers.iter().zip(row) {
+                for (n, value) in value.split('\n').enumerate() {
+                    let name = if n == 0 { header.as_str() } else { "" };
+                    let cells = [pad(name, name_width, false), pad(value, value_width, false)];
+
+                    if border {
+                        output.push_str(&line(&cells, border));
+                    } else {
+                        output.push_str(&format!("{}\n", cells.join(" | ").trim_end()));
+                    }
+                }
+            }
+        }
+
+        if border && !self.rows.is_empty() {
+            output.push_str(&format!(
+                "+{}+{}+\n",
+                "-".repeat(name_width + 2),
+                "-".repeat(value_width + 2)
+            ));
+        }
+
+        output
+    }
+}
+
+fn record_title(record: usize) -> String {
+    format!("-[ RECORD {record} ]")
+}
+
+fn line(cells: &[String], border: bool) -> String {
+    let line = cells.join(" | ");
+
+    if border {
+        format!("| {line} |\n")
+    } else {
+        format!("{}\n", format!(" {line}").trim_end())
+    }
+}
+
+fn width(s: &str) -> usize {
+    s.chars().count()
+}
+
+fn lines_width(s: &str) -> usize {
+    s.split('\n').map(width).max().unwrap_or(0)
+}
+
+fn pad(s: &str, width: usize, right: bool) -> String {
+    if right {
+        format!("{s:>width$}")
+    } else {
+        format!("{s:<width$}")
+    }
+}
+
+fn center(s: &str, width: usize) -> String {
+    let left = width.saturating_sub(self::width(s)) / 2;
+
+    pad(&format!("{}{s}", " ".repeat(left)), width, false)
+}
+
+#[cfg(test)]
+mod test {
+    fn result() -> crate::Result {
+        crate::result::Builder::new(crate::Status::TupplesOk)
+            .column("id", &crate::types::INT4)
+            .column("name", &crate::types::TEXT)
+            .row(&[Some("1"), Some("foo")])
+            .row(&[Some("22"), None])
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn aligned() {
+        let result = result();
+
+        assert_eq!(
+            result.format_table(&Default::default()).unwrap(),
+            " id | name
+----+------
+  1 | foo
+ 22 |
+(2 rows)
+"
+        );
+
+        let options = crate::result::TableOptions {
+            border: true,
+            null: "NULL".to_string(),
+            footer: false,
+            ..Default::default()
+        };
+        assert_eq!(
+            result.format_table(&options).unwrap(),
+            "+----+------+
+| id | name |
++----+------+
+|  1 | foo  |
+| 22 | NULL |
++----+------+
+"
+        );
+    }
+
+    #[test]
+    fn expanded() {
+        let result = result();
+
+        let options = crate::result::TableOptions {
+            expanded: crate::result::Expanded::On,
+            ..Default::default()
+        };
+        assert_eq!(
+            result.format_table(&options).unwrap(),
+            "-[ RECORD 1 ]-
+id   | 1
+name | foo
+-[ RECORD 2 ]-
+id   | 22
+name |
+"
+        );
+
+        let options = crate::result::TableOptions {
+            expanded: crate::result::Expanded::Auto(10),
+            border: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            result.format_table(&options).unwrap(),
+            "+-[ RECORD 1 ]-+
+| id   | 1     |
+| name | foo   |
++-[ RECORD 2 ]-+
+| id   | 22    |
+| name |       |
++------+-------+
+"
+        );
+    }
+
+    #[test]
+    fn multiline() {
+        let result = crate::result::Builder::new(crate::Status::TupplesOk)
+            .column("a", &crate::types::TEXT)
+            .column("b", &crate::types::TEXT)
+            .row(&[Some("x\nyy"), Some("z")])
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            result.format_table(&Default::default()).unwrap(),
+            " a  | b
+----+---
+ x  | z
+ yy |
+(1 row)
+"
+        );
+    }
+}
@@ -0,0 +1,121 @@
+/**
+ * Builds a dynamic SQL query from static fragments, identifiers and literals, escaped for a
+ * specific connection.
+ *
+ * ```no_run
+ * # let conn = libpq::Connection::new("")?;
+ * let query = libpq::sql::Composed::new()
+ *     .sql("SELECT * FROM ")
+ *     .identifier("my table")
+ *     .sql(" WHERE name = ")
+ *     .literal("O'Reilly")
+ *     .build(&conn)?;
+ *
+ * assert_eq!(query, r#"SELECT * FROM "my table" WHERE name = 'O''Reilly'"#);
+ * # Ok::<(), libpq::errors::Error>(())
+ * ```
+ */
+#[derive(Clone, Debug, Default)]
+pub struct Composed {
+    parts: Vec<Part>,
+}
+
+#[derive(Clone, Debug)]
+enum Part {
+    Sql(&'static str),
+    Identifier(String),
+    Literal(String),
+}
+
+impl Composed {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /**
+     * Appends a SQL fragment, as is.
+     *
+     * Only static strings are accepted, to prevent splicing user input without escaping it.
+     */
+    pub fn sql(mut self, sql: &'static str) -> Self {
+        self.parts.push(Part::Sql(sql));
+        self
+    }
+
+    /**
+     * Appends an identifier, such as a table or column name.
+     *
+     * See [`Connection::escape_identifier`](crate::Connection::escape_identifier).
+     */
+    pub fn identifier(mut self, identifier: &str) -> Self {
+        self.parts.push(Part::Identifier(identifier.to_string()));
+        self
+    }
+
+    /**
+     * Appends identifiers, separated by `separator`.
+     */
+    pub fn identifiers(mut self, identifiers: &[&str], separator: &'static str) -> Self {
+        for (x, identifier) in identifiers.iter().enumerate() {
+            if x > 0 {
+                self = self.sql(separator);
+            }
+
+            self = self.identifier(identifier);
+        }
+
+        self
+    }
+
+    /**
+     * Appends a literal value.
+     *
+     * See [`Connection::escape_literal`](crate::Connection::escape_literal).
+     */
+    pub fn literal(mut self, literal: &str) -> Self {
+        self.parts.push(Part::Literal(literal.to_string()));
+        self
+    }
+
+    /**
+     * Escapes the identifiers and literals for `conn` and returns the query.
+     */
+    pub fn build(&self, conn: &crate::Connection) -> crate::errors::Result<String> {
+        let mut query = String::new();
+
+        for part in &self.parts {
+            match part {
+                Part::Sql(sql) => query.push_str(sql),
+                Part::Identifier(identifier) => {
+                    query.push_str(conn.escape_identifier(identifier)?.to_str()?)
+                }
+                Part::Literal(literal) => query.push_str(conn.escape_literal(literal)?.to_str()?),
+            }
+        }
+
+        Ok(query)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #[test]
+    fn composed() {
+        let conn = crate::test::new_conn();
+
+        let query = crate::sql::Composed::new()
+            .sql("SELECT ")
+            .identifiers(&["a", "B"], ", ")
+            .sql(" FROM ")
+            .identifier("my \"table\"")
+            .sql(" WHERE name = ")
+            .literal("O'Reilly")
+            .build(&conn)
+            .unwrap();
+
+        assert_eq!(
+            query,
+            r#"SELECT "a", "B" FROM "my ""table""" WHERE name = 'O''Reilly'"#
+        );
+    }
+}
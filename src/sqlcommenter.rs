@@ -0,0 +1,158 @@
+/**
+ * Formats attributes as a [sqlcommenter](https://google.github.io/sqlcommenter/spec/) comment.
+ *
+ * Keys are sorted, keys and values are URL-encoded and values are quoted.
+ *
+ * ```
+ * assert_eq!(
+ *     libpq::sqlcommenter::format(&[
+ *         ("traceparent", "00-5bd66ef5095369c7b0d1f8f4bd33716a-c532cb4098ac3dd2-01"),
+ *         ("application", "my app"),
+ *     ]),
+ *     "/*application='my%20app',traceparent='00-5bd66ef5095369c7b0d1f8f4bd33716a-c532cb4098ac3dd2-01'*/"
+ * );
+ * ```
+ */
+pub fn format<K: AsRef<str>, V: AsRef<str>>(attributes: &[(K, V)]) -> String {
+    let mut attributes = attributes
+        .iter()
+        .map(|(k, v)| format!("{}='{}'", encode(k.as_ref()), encode(v.as_ref())))
+        .collect::<Vec<_>>();
+    attributes.sort();
+
+    format!("/*{}*/", attributes.join(","))
+}
+
+/**
+ * Appends the sqlcommenter comment to the query.
+ *
+ * The query is returned untouched if there is no attribute, or if it already ends with a comment
+ * (as required by the specification).
+ */
+pub fn append<K: AsRef<str>, V: AsRef<str>>(query: &str, attributes: &[(K, V)]) -> String {
+    let trimmed = query.trim_end();
+
+    if attributes.is_empty() || ends_with_comment(trimmed) {
+        return query.to_string();
+    }
+
+    let (statement, end) = match trimmed.strip_suffix(';') {
+        Some(statement) => (statement.trim_end(), ";"),
+        None => (trimmed, ""),
+    };
+
+    format!("{} {}{}", statement, format(attributes), end)
+}
+
+/**
+ * Whether the last token of `query` is a comment, skipping the string literals and quoted
+ * identifiers.
+ */
+fn ends_with_comment(query: &str) -> bool {
+    let mut chars = query.chars().peekable();
+    let mut comment = false;
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\'' | '"' => {
+                // A doubled quote is an escaped quote.
+                while let Some(x) = chars.next() {
+                    if x == c && chars.next_if_eq(&c).is_none() {
+                        break;
+                    }
+                }
+                comment = false;
+            }
+            '-' if chars.next_if_eq(&'-').is_some() => {
+                while chars.next_if(|x| *x != '\n').is_some() {}
+                comment = true;
+            }
+            '/' if chars.next_if_eq(&'*').is_some() => {
+                // Block comments nest.
+                let mut depth = 1;
+
+                while depth > 0 {
+                    match chars.next() {
+                        Some('*') if chars.next_if_eq(&'/').is_some() => depth -= 1,
+                        Some('/') if chars.next_if_eq(&'*').is_some() => depth += 1,
+                        Some(_) => (),
+                        None => break,
+                    }
+                }
+                comment = true;
+            }
+            c if c.is_whitespace() => (),
+            _ => comment = false,
+        }
+    }
+
+    comment
+}
+
+fn encode(s: &str) -> String {
+    let mut encoded = String::with_capacity(s.len());
+
+    for b in s.bytes() {
+        match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                encoded.push(b as char)
+            }
+            _ => encoded.push_str(&format!("%{:02X}", b)),
+        }
+    }
+
+    encoded
+}
+
+#[cfg(test)]
+mod test {
+    #[test]
+    fn append() {
+        let attributes = [("route", "/users/{id}"), ("framework", "libpq")];
+
+        assert_eq!(
+            crate::sqlcommenter::append("SELECT 1;", &attributes),
+            "SELECT 1 /*framework='libpq',route='%2Fusers%2F%7Bid%7D'*/;"
+        );
+        assert_eq!(
+            crate::sqlcommenter::append("SELECT 1 /* foo */", &attributes),
+            "SELECT 1 /* foo */"
+        );
+        assert_eq!(
+            crate::sqlcommenter::append("SELECT 1 -- foo", &attributes),
+            "SELECT 1 -- foo"
+        );
+        assert_eq!(
+            crate::sqlcommenter::append("SELECT '--', 1 /* a /* b */ */", &attributes),
+            "SELECT '--', 1 /* a /* b */ */"
+        );
+        assert_eq!(
+            crate::sqlcommenter::append("SELECT 'a--b' /* c */ , 'it''s /*'", &attributes),
+            "SELECT 'a--b' /* c */ , 'it''s /*' /*framework='libpq',route='%2Fusers%2F%7Bid%7D'*/"
+        );
+        assert_eq!(
+            crate::sqlcommenter::append::<&str, &str>("SELECT 1", &[]),
+            "SELECT 1"
+        );
+    }
+
+    #[test]
+    fn format() {
+        assert_eq!(
+            crate::sqlcommenter::format(&[("it's", "a'b")]),
+            "/*it%27s='a%27b'*/"
+        );
+    }
+
+    #[test]
+    fn commenter() {
+        let conn = crate::test::new_conn();
+        conn.set_sql_commenter(|| vec![("action".to_string(), "test".to_string())]);
+
+        let result = conn.exec("SELECT current_query()");
+        assert_eq!(
+            result.value(0, 0),
+            Some(&b"SELECT current_query() /*action='test'*/"[..])
+        );
+    }
+}
@@ -1,4 +1,4 @@
-#[derive(Clone, Copy, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq)]
 pub enum Attribute {
     /** Name of the SSL implementation in use. (Currently, only "OpenSSL" is implemented) */
     Library,
@@ -20,11 +20,23 @@ pub enum Attribute {
      * "off".
      */
     Compression,
+    /**
+     * Application protocol selected by the TLS Application-Layer Protocol Negotiation (ALPN)
+     * extension.
+     */
+    #[cfg(feature = "v16")]
+    Alpn,
+    /** An attribute unknown to this crate, reported by a newer libpq. */
+    Unknown(String),
 }
 
 impl ToString for Attribute {
     fn to_string(&self) -> String {
-        format!("{:?}", self).to_lowercase()
+        match self {
+            Self::KeyBits => "key_bits".to_string(),
+            Self::Unknown(name) => name.clone(),
+            _ => format!("{:?}", self).to_lowercase(),
+        }
     }
 }
 
@@ -37,7 +49,172 @@ impl From<&String> for Attribute {
             "key_bits" => Self::KeyBits,
             "cipher" => Self::Cipher,
             "compression" => Self::Compression,
-            _ => unimplemented!(),
+            #[cfg(feature = "v16")]
+            "alpn" => Self::Alpn,
+            _ => Self::Unknown(s.clone()),
         }
     }
 }
+
+/**
+ * SSL/TLS protocol version.
+ */
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Protocol {
+    TlsV1,
+    TlsV1_1,
+    TlsV1_2,
+    TlsV1_3,
+    Unknown(String),
+}
+
+impl From<&str> for Protocol {
+    fn from(s: &str) -> Self {
+        match s {
+            "TLSv1" => Self::TlsV1,
+            "TLSv1.1" => Self::TlsV1_1,
+            "TLSv1.2" => Self::TlsV1_2,
+            "TLSv1.3" => Self::TlsV1_3,
+            _ => Self::Unknown(s.to_string()),
+        }
+    }
+}
+
+/**
+ * How SSL encryption is negotiated with the server, value of the `sslnegotiation` parameter.
+ */
+#[cfg(feature = "v17")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Negotiation {
+    /** Performs the PostgreSQL protocol negotiation, asking the server first if SSL is supported. */
+    Postgres,
+    /** Starts the SSL handshake directly after establishing the TCP/IP connection. */
+    Direct,
+}
+
+#[cfg(feature = "v17")]
+impl Negotiation {
+    pub(crate) fn as_str(&self) -> &'static str {
+        match self {
+            Self::Postgres => "postgres",
+            Self::Direct => "direct",
+        }
+    }
+}
+
+/**
+ * SSL-related information about a connection, see
+ * [`Connection::ssl_info`](crate::Connection::ssl_info).
+ */
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Info {
+    /** Name of the SSL implementation in use. */
+    pub library: String,
+    /** SSL/TLS version in use. */
+    pub protocol: Protocol,
+    /** Short name of the ciphersuite used. */
+    pub cipher: String,
+    /** Number of key bits used by the encryption algorithm. */
+    pub key_bits: u32,
+    /** Whether SSL compression is in use. */
+    pub compression: bool,
+    /** Application protocol selected by ALPN, if any. */
+    pub alpn: Option<String>,
+}
+
+/**
+ * Allows applications to select which security libraries libpq initializes.
+ *
+ * Applications initializing OpenSSL themselves should call `init(false, false)` before opening
+ * any connection, to prevent libpq from initializing it again.
+ *
+ * See [PQinitOpenSSL](https://www.postgresql.org/docs/current/libpq-ssl.html#LIBPQ-PQINITOPENSSL).
+ */
+pub fn init(do_ssl: bool, do_crypto: bool) {
+    unsafe {
+        pq_sys::PQinitOpenSSL(do_ssl as i32, do_crypto as i32);
+    }
+}
+
+#[cfg(feature = "v13")]
+type KeyPassHook = dyn Fn(&crate::Connection) -> Option<String> + Send + Sync;
+
+#[cfg(feature = "v13")]
+static KEY_PASS_HOOK: std::sync::RwLock<Option<std::sync::Arc<KeyPassHook>>> =
+    std::sync::RwLock::new(None);
+
+/**
+ * Sets a closure providing the passphrase of the client certificate key, for example from a
+ * secret manager, instead of the `sslpassword` parameter or a terminal prompt.
+ *
+ * Returning `None`, or a passphrase too long for the buffer of libpq, makes the connection fail.
+ * The hook is global to the process.
+ *
+ * See [PQsetSSLKeyPassHook_OpenSSL](https://www.postgresql.org/docs/current/libpq-connect.html#LIBPQ-PQSETSSLKEYPASSHOOK-OPENSSL).
+ */
+#[cfg(feature = "v13")]
+pub fn set_key_pass_hook<F>(hook: F)
+where
+    F: Fn(&crate::Connection) -> Option<String> + Send + Sync + 'static,
+{
+    *KEY_PASS_HOOK.write().unwrap_or_else(|e| e.into_inner()) = Some(std::sync::Arc::new(hook));
+
+    unsafe {
+        pq_sys::PQsetSSLKeyPassHook_OpenSSL(Some(key_pass_hook_trampoline));
+    }
+}
+
+/**
+ * Restores the default passphrase hook, removing the closure set by [`set_key_pass_hook`].
+ */
+#[cfg(feature = "v13")]
+pub fn unset_key_pass_hook() {
+    unsafe {
+        pq_sys::PQsetSSLKeyPassHook_OpenSSL(None);
+    }
+
+    *KEY_PASS_HOOK.write().unwrap_or_else(|e| e.into_inner()) = None;
+}
+
+#[cfg(feature = "v13")]
+unsafe extern "C" fn key_pass_hook_trampoline(
+    buf: *mut libc::c_char,
+    size: libc::c_int,
+    conn: *mut pq_sys::PGconn,
+) -> libc::c_int {
+    // Don't call the user closure with the lock held.
+    let hook = KEY_PASS_HOOK
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone();
+
+    let hook = match hook {
+        Some(hook) if size > 0 => hook,
+        _ => return 0,
+    };
+
+    let r = crate::ffi::catch_unwind("SSL key passphrase hook", || {
+        crate::Connection::with_raw(conn, |conn| hook(conn))
+    });
+
+    let passphrase = match r {
+        Some(Some(passphrase)) => passphrase,
+        _ => return 0,
+    };
+
+    // Keeps room for the nul terminator, a truncated passphrase would only fail to decrypt the key.
+    let len = passphrase.len();
+
+    if len >= size as usize {
+        log::error!(
+            "SSL key passphrase is longer than the {} bytes accepted by libpq",
+            size - 1
+        );
+        return 0;
+    }
+
+    std::ptr::copy_nonoverlapping(passphrase.as_ptr() as *const libc::c_char, buf, len);
+    *buf.add(len) = 0;
+
+    len as libc::c_int
+}
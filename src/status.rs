@@ -1,4 +1,9 @@
 #[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "rkyv",
+    derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)
+)]
 pub enum Status {
     /** The server's response was not understood. */
     BadResponse,
@@ -34,6 +39,16 @@ pub enum Status {
     /** Command didn't run because of an abort earlier in a pipeline. */
     #[cfg(feature = "v14")]
     PipelineAborted,
+
+    /**
+     * The `libpq::Result` contains several result tuples from the current command. This status
+     * occurs only when chunked mode has been selected for the query.
+     */
+    #[cfg(feature = "v17")]
+    TuplesChunk,
+
+    /** A status unknown to this crate, returned by a newer libpq. */
+    Unknown(i32),
 }
 
 #[doc(hidden)]
@@ -54,8 +69,10 @@ impl From<pq_sys::ExecStatusType> for Status {
             pq_sys::ExecStatusType::PGRES_PIPELINE_SYNC => Self::PipelineSync,
             #[cfg(feature = "v14")]
             pq_sys::ExecStatusType::PGRES_PIPELINE_ABORTED => Self::PipelineAborted,
+            #[cfg(feature = "v17")]
+            pq_sys::ExecStatusType::PGRES_TUPLES_CHUNK => Self::TuplesChunk,
             #[allow(unreachable_patterns)]
-            _ => unreachable!(),
+            _ => Self::Unknown(status as i32),
         }
     }
 }
@@ -85,18 +102,47 @@ impl From<&Status> for pq_sys::ExecStatusType {
             Status::PipelineSync => pq_sys::ExecStatusType::PGRES_PIPELINE_SYNC,
             #[cfg(feature = "v14")]
             Status::PipelineAborted => pq_sys::ExecStatusType::PGRES_PIPELINE_ABORTED,
-            #[allow(unreachable_patterns)]
-            _ => unreachable!(),
+            #[cfg(feature = "v17")]
+            Status::TuplesChunk => pq_sys::ExecStatusType::PGRES_TUPLES_CHUNK,
+            // libpq can't represent a status it doesn't know.
+            Status::Unknown(_) => pq_sys::ExecStatusType::PGRES_BAD_RESPONSE,
         }
     }
 }
 
-impl ToString for Status {
-    fn to_string(&self) -> String {
-        let status = unsafe { pq_sys::PQresStatus(self.into()) };
+/**
+ * See [PQresStatus](https://www.postgresql.org/docs/current/libpq-exec.html#LIBPQ-PQRESSTATUS).
+ */
+impl std::fmt::Display for Status {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Self::Unknown(status) = self {
+            return write!(f, "unknown ExecStatusType code {status}");
+        }
+
+        // The string is static, it must not be freed.
+        let status = unsafe { std::ffi::CStr::from_ptr(pq_sys::PQresStatus(self.into())) };
+
+        f.write_str(&status.to_string_lossy())
+    }
+}
 
-        crate::connection::PqString::from_raw(status as *mut libc::c_void)
-            .to_string_lossy()
-            .to_string()
+#[cfg(test)]
+mod test {
+    #[test]
+    fn display() {
+        assert_eq!(crate::Status::TupplesOk.to_string(), "PGRES_TUPLES_OK");
+        assert_eq!(
+            crate::Status::Unknown(42).to_string(),
+            "unknown ExecStatusType code 42"
+        );
+        assert_eq!(crate::connection::Status::Ok.to_string(), "CONNECTION_OK");
+        assert_eq!(
+            crate::connection::Status::Unknown(42).to_string(),
+            "unknown ConnStatusType code 42"
+        );
+        assert_eq!(
+            crate::transaction::Status::InTrans.to_string(),
+            "PQTRANS_INTRANS"
+        );
     }
 }
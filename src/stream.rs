@@ -0,0 +1,95 @@
+/**
+ * A `FILE*` owned by Rust, optionally forwarding everything written to it to a
+ * [`std::io::Write`].
+ *
+ * The stream is closed, and the forwarding thread joined, on drop.
+ */
+pub(crate) struct Stream {
+    file: *mut libc::FILE,
+    thread: Option<std::thread::JoinHandle<std::io::Result<u64>>>,
+}
+
+unsafe impl Send for Stream {}
+
+impl Stream {
+    /**
+     * Takes the ownership of `file`.
+     */
+    pub fn from_file(file: std::fs::File) -> std::io::Result<Self> {
+        use std::os::unix::io::IntoRawFd;
+
+        Self::from_fd(file.into_raw_fd(), None)
+    }
+
+    /**
+     * Creates a pipe, the read end is copied into `writer` by a dedicated thread.
+     */
+    pub fn from_writer<W>(mut writer: W) -> std::io::Result<Self>
+    where
+        W: std::io::Write + Send + 'static,
+    {
+        use std::os::unix::io::FromRawFd;
+
+        let mut fds = [0; 2];
+        if unsafe { libc::pipe(fds.as_mut_ptr()) } == -1 {
+            return Err(std::io::Error::last_os_error());
+        }
+
+        let mut reader = unsafe { std::fs::File::from_raw_fd(fds[0]) };
+        let thread = std::thread::Builder::new()
+            .name("libpq-stream".to_string())
+            .spawn(move || {
+                let n = std::io::copy(&mut reader, &mut writer)?;
+                writer.flush()?;
+
+                Ok(n)
+            });
+
+        let thread = match thread {
+            Ok(thread) => thread,
+            Err(err) => {
+                unsafe { libc::close(fds[1]) };
+                return Err(err);
+            }
+        };
+
+        Self::from_fd(fds[1], Some(thread))
+    }
+
+    fn from_fd(
+        fd: libc::c_int,
+        thread: Option<std::thread::JoinHandle<std::io::Result<u64>>>,
+    ) -> std::io::Result<Self> {
+        let c_mode = crate::ffi::to_cstr("w");
+        let file = unsafe { libc::fdopen(fd, c_mode.as_ptr()) };
+
+        if file.is_null() {
+            let err = std::io::Error::last_os_error();
+            unsafe { libc::close(fd) };
+            return Err(err);
+        }
+
+        Ok(Self { file, thread })
+    }
+
+    pub fn as_ptr(&self) -> *mut libc::FILE {
+        self.file
+    }
+}
+
+impl Drop for Stream {
+    fn drop(&mut self) {
+        // Closing the write end lets the thread reach EOF.
+        unsafe {
+            libc::fclose(self.file);
+        }
+
+        if let Some(thread) = self.thread.take() {
+            match thread.join() {
+                Ok(Ok(_)) => (),
+                Ok(Err(err)) => log::error!("Unable to forward stream: {}", err),
+                Err(_) => log::error!("Stream thread panicked"),
+            }
+        }
+    }
+}
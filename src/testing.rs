@@ -0,0 +1,157 @@
+/*!
+ * Helpers to check query results in tests.
+ *
+ * ```no_run
+ * # let conn = libpq::Connection::new("")?;
+ * let result = conn.exec("SELECT id, name FROM users ORDER BY id");
+ *
+ * libpq::assert_rows_eq!(result, [[1, "foo"], [2, None::<&str>]]);
+ * # Ok::<(), libpq::errors::Error>(())
+ * ```
+ */
+
+/**
+ * Asserts the rows of a [`Result`](crate::Result), in text format, are equal to the expected
+ * values, given as arrays of [`ToParam`](crate::params::ToParam) values. `None` is SQL `NULL`.
+ *
+ * On mismatch, panics with the differences, see [`diff`](crate::testing::diff).
+ */
+#[macro_export]
+macro_rules! assert_rows_eq {
+    ($result:expr, [$([$($value:expr),* $(,)?]),* $(,)?] $(,)?) => {{
+        let expected = $crate::testing::from_params(::std::vec![$($crate::params![$($value),*]),*]);
+        let actual = $crate::testing::to_rows(&$result);
+
+        if let ::std::option::Option::Some(diff) = $crate::testing::diff(&actual, &expected) {
+            ::std::panic!("rows are not equal (- expected, + actual):\n{}", diff);
+        }
+    }};
+}
+
+/**
+ * Returns the values of all the rows of `result`, `None` for null. Invalid UTF-8 sequences are
+ * replaced.
+ */
+pub fn to_rows(result: &crate::Result) -> Vec<Vec<Option<String>>> {
+    result
+        .rows()
+        .map(|row| {
+            (0..row.len())
+                .map(|x| {
+                    row.value(x)
+                        .map(|x| String::from_utf8_lossy(x).into_owned())
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/**
+ * Converts rows built with [`params!`](crate::params!) to the form returned by [`to_rows`].
+ */
+pub fn from_params(rows: Vec<Vec<Option<Vec<u8>>>>) -> Vec<Vec<Option<String>>> {
+    rows.into_iter()
+        .map(|row| {
+            row.into_iter()
+                .map(|x| {
+                    x.map(|x| {
+                        let x = x.strip_suffix(b"\0").unwrap_or(&x);
+
+                        String::from_utf8_lossy(x).into_owned()
+                    })
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/**
+ * Compares rows, returns `None` if they are equal or a report listing all the rows, those only in
+ * `expected` prefixed by `-` and those only in `actual` by `+`.
+ */
+pub fn diff(actual: &[Vec<Option<String>>], expected: &[Vec<Option<String>>]) -> Option<String> {
+    if actual == expected {
+        return None;
+    }
+
+    let mut report = String::new();
+
+    if actual.len() != expected.len() {
+        report.push_str(&format!(
+            "expected {} rows, got {}\n",
+            expected.len(),
+            actual.len()
+        ));
+    }
+
+    for x in 0..actual.len().max(expected.len()) {
+        match (expected.get(x), actual.get(x)) {
+            (Some(expected), Some(actual)) if expected == actual => {
+                report.push_str(&format!("  {x}: {}\n", format_row(actual)));
+            }
+            (expected, actual) => {
+                if let Some(expected) = expected {
+                    report.push_str(&format!("- {x}: {}\n", format_row(expected)));
+                }
+
+                if let Some(actual) = actual {
+                    report.push_str(&format!("+ {x}: {}\n", format_row(actual)));
+                }
+            }
+        }
+    }
+
+    Some(report)
+}
+
+fn format_row(row: &[Option<String>]) -> String {
+    let values = row
+        .iter()
+        .map(|x| match x {
+            Some(x) => format!("{x:?}"),
+            None => "NULL".to_string(),
+        })
+        .collect::<Vec<_>>();
+
+    format!("[{}]", values.join(", "))
+}
+
+#[cfg(test)]
+mod test {
+    fn result() -> crate::Result {
+        crate::result::Builder::new(crate::Status::TupplesOk)
+            .column("id", &crate::types::INT4)
+            .column("name", &crate::types::TEXT)
+            .row(&[Some("1"), Some("foo")])
+            .row(&[Some("2"), None])
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn assert_rows_eq() {
+        crate::assert_rows_eq!(result(), [[1, "foo"], [2, None::<&str>]]);
+
+        let conn = crate::test::new_conn();
+        crate::assert_rows_eq!(conn.exec("SELECT true, 1.5 WHERE false"), []);
+        crate::assert_rows_eq!(conn.exec("SELECT true, 1.5"), [[true, 1.5]]);
+    }
+
+    #[test]
+    #[should_panic(expected = "- 1: [\"2\", \"bar\"]")]
+    fn assert_rows_ne() {
+        crate::assert_rows_eq!(result(), [[1, "foo"], [2, "bar"]]);
+    }
+
+    #[test]
+    fn diff() {
+        let actual = super::to_rows(&result());
+        assert_eq!(super::diff(&actual, &actual), None);
+
+        let expected = super::from_params(vec![crate::params![1, "foo"]]);
+        assert_eq!(
+            super::diff(&actual, &expected).unwrap(),
+            "expected 1 rows, got 2\n  0: [\"1\", \"foo\"]\n+ 1: [\"2\", NULL]\n"
+        );
+    }
+}
@@ -24,3 +24,17 @@ impl From<pq_sys::PGTransactionStatusType> for Status {
         }
     }
 }
+
+impl std::fmt::Display for Status {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            Self::Idle => "PQTRANS_IDLE",
+            Self::Active => "PQTRANS_ACTIVE",
+            Self::InTrans => "PQTRANS_INTRANS",
+            Self::InError => "PQTRANS_INERROR",
+            Self::Unknow => "PQTRANS_UNKNOWN",
+        };
+
+        f.write_str(s)
+    }
+}
@@ -12,6 +12,8 @@ pub enum Verbosity {
     /** only error severity and SQLSTATE code */
     #[cfg(feature = "v11")]
     Sqlstate,
+    /** A verbosity unknown to this crate, returned by a newer libpq. */
+    Unknown(i32),
 }
 
 #[doc(hidden)]
@@ -24,7 +26,7 @@ impl From<pq_sys::PGVerbosity> for Verbosity {
             #[cfg(feature = "v11")]
             pq_sys::PGVerbosity::PQERRORS_SQLSTATE => Self::Sqlstate,
             #[allow(unreachable_patterns)]
-            _ => unreachable!(),
+            _ => Self::Unknown(verbosity as i32),
         }
     }
 }
@@ -38,8 +40,8 @@ impl From<Verbosity> for pq_sys::PGVerbosity {
             Verbosity::Verbose => pq_sys::PGVerbosity::PQERRORS_VERBOSE,
             #[cfg(feature = "v11")]
             Verbosity::Sqlstate => pq_sys::PGVerbosity::PQERRORS_SQLSTATE,
-            #[allow(unreachable_patterns)]
-            _ => unreachable!(),
+            // libpq can't represent a verbosity it doesn't know.
+            Verbosity::Unknown(_) => pq_sys::PGVerbosity::PQERRORS_DEFAULT,
         }
     }
 }